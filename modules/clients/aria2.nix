@@ -0,0 +1,56 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.client.aria2 = {
+    base_url = lib.mkOption {
+      type = lib.types.str;
+      default = "http://localhost:6800/jsonrpc";
+      description = ''
+        The url of the aria2 JSON-RPC endpoint
+      '';
+    };
+    secret = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The RPC secret token for aria2 (optional)
+				Has higher priority than `secret_file`
+      '';
+    };
+    secret_file = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The path to a file containing the RPC secret token for aria2 (optional)
+				Has lower priority than `secret`
+      '';
+    };
+    use_magnet = lib.mkOption {
+      type = lib.types.nullOr lib.types.bool;
+      default = null;
+      description = ''
+        Whether or not to send magnet links or torrent links (optional)
+      '';
+    };
+    dir = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The directory to save downloaded torrents to (optional)
+      '';
+    };
+    max_connection_per_server = lib.mkOption {
+      type = lib.types.nullOr lib.types.int;
+      default = null;
+      description = ''
+        The maximum number of connections to one server for each download (optional)
+      '';
+    };
+    seed_ratio = lib.mkOption {
+      type = lib.types.nullOr lib.types.float;
+      default = null;
+      description = ''
+        The share ratio to seed torrents until (optional)
+      '';
+    };
+  };
+}
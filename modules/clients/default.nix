@@ -3,6 +3,12 @@
     ./qBittorrent.nix
     ./transmission.nix
     ./rqbit.nix
+    ./deluge.nix
+    ./aria2.nix
+    ./rtorrent.nix
+    ./webhook.nix
+    ./watch_folder.nix
     ./other.nix
+    ./profiles.nix
   ];
 }
@@ -0,0 +1,63 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.client.deluge = {
+    base_url = lib.mkOption {
+      type = lib.types.str;
+      default = "http://localhost:8112";
+      description = ''
+        The base url for the Deluge WebUI
+      '';
+    };
+    password = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The password to login to the Deluge WebUI (optional)
+				Has higher priority than `password_file`
+      '';
+    };
+    password_file = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The path to a file containing the password to login to the Deluge WebUI (optional)
+				Has lower priority than `password`
+      '';
+    };
+    use_magnet = lib.mkOption {
+      type = lib.types.nullOr lib.types.bool;
+      default = null;
+      description = ''
+        Whether or not to send magnet links or torrent links (optional)
+      '';
+    };
+    download_location = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The path to save downloaded torrents to (optional)
+      '';
+    };
+    label = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The label to add to torrents, requires the Label plugin (optional)
+      '';
+    };
+    paused = lib.mkOption {
+      type = lib.types.nullOr lib.types.bool;
+      default = null;
+      description = ''
+        Whether to add torrents paused (optional)
+      '';
+    };
+    move_completed_path = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The path to move completed torrents to (optional)
+      '';
+    };
+  };
+}
@@ -0,0 +1,12 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.client.profiles = lib.mkOption {
+    type = lib.types.attrsOf (lib.types.attrsOf lib.types.anything);
+    default = { };
+    description = ''
+      Named instances of download clients, each with a `client` and the options of that client
+      Example value:
+      `{ seedbox = { client = "qBittorrent"; base_url = "https://seedbox.example.com"; }; }`
+    '';
+  };
+}
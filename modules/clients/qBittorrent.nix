@@ -36,7 +36,7 @@
       type = lib.types.nullOr lib.types.bool;
       default = null;
       description = ''
-        Whether or not to send magnet links or torrent links (optional)
+        Whether to send magnet links, or to upload the torrent files downloaded by nyaa (optional)
       '';
     };
     savepath = lib.mkOption {
@@ -0,0 +1,63 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.client.rtorrent = {
+    base_url = lib.mkOption {
+      type = lib.types.str;
+      default = "http://localhost/RPC2";
+      description = ''
+        The url of the rTorrent XML-RPC endpoint or ruTorrent httprpc plugin
+      '';
+    };
+    username = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The username for HTTP basic authentication (optional)
+      '';
+    };
+    password = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The password for HTTP basic authentication (optional)
+				Has higher priority than `password_file`
+      '';
+    };
+    password_file = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The path to a file containing the password for HTTP basic authentication (optional)
+				Has lower priority than `password`
+      '';
+    };
+    use_magnet = lib.mkOption {
+      type = lib.types.nullOr lib.types.bool;
+      default = null;
+      description = ''
+        Whether or not to send magnet links or torrent links (optional)
+      '';
+    };
+    directory = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The directory to save downloaded torrents to (optional)
+      '';
+    };
+    label = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The label to set on torrents, stored in custom1 (optional)
+      '';
+    };
+    paused = lib.mkOption {
+      type = lib.types.nullOr lib.types.bool;
+      default = null;
+      description = ''
+        Whether to add torrents without starting them (optional)
+      '';
+    };
+  };
+}
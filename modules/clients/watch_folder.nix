@@ -0,0 +1,43 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.client.watch_folder = {
+    watch_dir = lib.mkOption {
+      type = lib.types.str;
+      default = "~/watch";
+      description = ''
+        The folder watched by your torrent client
+      '';
+    };
+    format = lib.mkOption {
+      type = lib.types.enum [
+        "Torrent"
+        "Magnet"
+      ];
+      default = "Torrent";
+      description = ''
+        Whether to save the `.torrent` file or a `.magnet` file containing the magnet link
+      '';
+    };
+    categories = lib.mkOption {
+      type = lib.types.attrsOf lib.types.str;
+      default = { };
+      description = ''
+        Subfolders of `watch_dir` to save each category to, like `{ AnimeEnglishTranslated = "anime"; }`
+      '';
+    };
+    create_dirs = lib.mkOption {
+      type = lib.types.bool;
+      default = true;
+      description = ''
+        Create the folders if they don't exist
+      '';
+    };
+    overwrite = lib.mkOption {
+      type = lib.types.bool;
+      default = false;
+      description = ''
+        Overwrite files that already exist
+      '';
+    };
+  };
+}
@@ -0,0 +1,57 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.client.webhook = {
+    url = lib.mkOption {
+      type = lib.types.str;
+      default = "http://localhost:5678/webhook/nyaa";
+      description = ''
+        The url to send the request to
+        Possible substitutions are:
+        - `{magnet}`
+        - `{torrent}`
+        - `{title}`
+        - `{hash}`
+        - `{id}`
+        - `{file}`
+      '';
+    };
+    method = lib.mkOption {
+      type = lib.types.str;
+      default = "POST";
+      description = ''
+        The HTTP method of the request
+      '';
+    };
+    headers = lib.mkOption {
+      type = lib.types.attrsOf lib.types.str;
+      default = { };
+      description = ''
+        Extra headers to send, values can contain substitutions
+      '';
+    };
+    format = lib.mkOption {
+      type = lib.types.enum [
+        "Json"
+        "Form"
+      ];
+      default = "Json";
+      description = ''
+        How to encode the body of the request
+      '';
+    };
+    body = lib.mkOption {
+      type = lib.types.nullOr lib.types.attrs;
+      default = null;
+      description = ''
+        The body of the request, string values can contain substitutions (optional)
+      '';
+    };
+    success_codes = lib.mkOption {
+      type = lib.types.nullOr (lib.types.listOf lib.types.int);
+      default = null;
+      description = ''
+        The status codes treated as success, any 2xx status code by default (optional)
+      '';
+    };
+  };
+}
@@ -44,7 +44,7 @@ in {
       type = lib.types.str;
       default = "qBittorrent";
       description = ''
-        Which download client to select on startup
+        Which download client or client profile to select on startup
       '';
     };
 
@@ -121,6 +121,33 @@ in {
       };
     };
 
+    keybinds = lib.mkOption {
+      type = lib.types.attrsOf (lib.types.attrsOf lib.types.str);
+      default = {};
+      description = ''
+        Keys to remap for each mode, applied on top of the default keybinds
+        For example, `{ normal = { x = "Categories"; c = "None"; }; }`
+      '';
+    };
+
+    watch = lib.mkOption {
+      type = lib.types.listOf (lib.types.attrsOf lib.types.anything);
+      default = [];
+      description = ''
+        A list of searches to check periodically with `nyaa watch`
+        Each entry can set `name`, `source`, `query`, `category`, `filter`, `user`, `client`, `interval`, and `download_existing`
+      '';
+    };
+
+    routing = lib.mkOption {
+      type = lib.types.listOf (lib.types.attrsOf lib.types.anything);
+      default = [];
+      description = ''
+        A list of rules that pick the download client and its options for each torrent
+        Each entry can set `source`, `categories`, `title`, `min_size`, `max_size`, `uploader`, `client`, and `overrides`
+      '';
+    };
+
     clipboard = {
       cmd = lib.mkOption {
         type = lib.types.nullOr lib.types.str;
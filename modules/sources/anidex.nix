@@ -0,0 +1,106 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.source.anidex = {
+    base_url = lib.mkOption {
+      type = lib.types.str;
+      default = "https://anidex.info/";
+      description = ''
+        The base url for AniDex
+      '';
+    };
+    default_sort = lib.mkOption {
+      type = lib.types.str;
+      default = "Date";
+      description = ''
+        The default sort for AniDex
+      '';
+    };
+    default_sort_dir = lib.mkOption {
+      type = lib.types.str;
+      default = "Desc";
+      description = ''
+        The default sort direction for AniDex
+      '';
+    };
+    default_filter = lib.mkOption {
+      type = lib.types.str;
+      default = "NoFilter";
+      description = ''
+        The default filter for AniDex
+      '';
+    };
+    default_category = lib.mkOption {
+      type = lib.types.str;
+      default = "AllCategories";
+      description = ''
+        The default category for AniDex
+      '';
+    };
+    default_search = lib.mkOption {
+      type = lib.types.str;
+      default = "";
+      description = ''
+        The default search for AniDex
+      '';
+    };
+    timeout = lib.mkOption {
+      type = lib.types.nullOr lib.types.int;
+      default = null;
+      description = ''
+        Override the global timeout for this source (measured in seconds) (optional)
+      '';
+    };
+
+    columns = {
+      category = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the category column (optional)
+        '';
+      };
+      title = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the title column (optional)
+        '';
+      };
+      size = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the size column (optional)
+        '';
+      };
+      date = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the date column (optional)
+        '';
+      };
+      seeders = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the seeders column (optional)
+        '';
+      };
+      leechers = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the leechers column (optional)
+        '';
+      };
+      downloads = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the completed downloads column (optional)
+        '';
+      };
+    };
+  };
+}
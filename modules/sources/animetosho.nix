@@ -0,0 +1,120 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.source.animetosho = {
+    base_url = lib.mkOption {
+      type = lib.types.str;
+      default = "https://feed.animetosho.org/";
+      description = ''
+        The base url for AnimeTosho
+      '';
+    };
+    default_sort = lib.mkOption {
+      type = lib.types.str;
+      default = "Date";
+      description = ''
+        The default sort for AnimeTosho
+      '';
+    };
+    default_sort_dir = lib.mkOption {
+      type = lib.types.str;
+      default = "Desc";
+      description = ''
+        The default sort direction for AnimeTosho
+      '';
+    };
+    default_filter = lib.mkOption {
+      type = lib.types.str;
+      default = "NoFilter";
+      description = ''
+        The default filter for AnimeTosho
+      '';
+    };
+    default_category = lib.mkOption {
+      type = lib.types.str;
+      default = "AllCategories";
+      description = ''
+        The default category for AnimeTosho
+      '';
+    };
+    default_search = lib.mkOption {
+      type = lib.types.str;
+      default = "";
+      description = ''
+        The default search for AnimeTosho
+      '';
+    };
+    timeout = lib.mkOption {
+      type = lib.types.nullOr lib.types.int;
+      default = null;
+      description = ''
+        Override the global timeout for this source (measured in seconds) (optional)
+      '';
+    };
+
+    columns = {
+      category = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the category column (optional)
+        '';
+      };
+      title = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the title column (optional)
+        '';
+      };
+      nyaa = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the Nyaa id column (optional)
+        '';
+      };
+      files = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the file count column (optional)
+        '';
+      };
+      size = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the size column (optional)
+        '';
+      };
+      date = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the date column (optional)
+        '';
+      };
+      seeders = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the seeders column (optional)
+        '';
+      };
+      leechers = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the leechers column (optional)
+        '';
+      };
+      downloads = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the downloads column (optional)
+        '';
+      };
+    };
+  };
+}
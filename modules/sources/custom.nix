@@ -0,0 +1,11 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.source.custom = lib.mkOption {
+    type = lib.types.attrsOf (lib.types.attrsOf lib.types.anything);
+    default = {};
+    description = ''
+      Sources scraped using CSS selectors, keyed by the name of the source
+      Each entry can set `base_url`, `search_url`, `categories`, `filters`, `sorts`, `selectors`, and the usual `default_*` options
+    '';
+  };
+}
@@ -3,5 +3,13 @@
     ./nyaa.nix
     ./sukebei.nix
     ./torrentgalaxy.nix
+    ./torznab.nix
+    ./animetosho.nix
+    ./tokyotosho.nix
+    ./anidex.nix
+    ./1337x.nix
+    ./custom.nix
+    ./plugin.nix
+    ./multi.nix
   ];
 }
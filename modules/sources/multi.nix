@@ -0,0 +1,43 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.source.multi = {
+    sources = lib.mkOption {
+      type = lib.types.listOf lib.types.str;
+      default = [
+        "Nyaa"
+        "TorrentGalaxy"
+      ];
+      description = ''
+        The sources to search at once
+      '';
+    };
+    default_sort = lib.mkOption {
+      type = lib.types.str;
+      default = "Date";
+      description = ''
+        The default sort for Multi
+      '';
+    };
+    default_sort_dir = lib.mkOption {
+      type = lib.types.str;
+      default = "Desc";
+      description = ''
+        The default sort direction for Multi
+      '';
+    };
+    default_filter = lib.mkOption {
+      type = lib.types.str;
+      default = "NoFilter";
+      description = ''
+        The default filter for Multi
+      '';
+    };
+    default_search = lib.mkOption {
+      type = lib.types.str;
+      default = "";
+      description = ''
+        The default search for Multi
+      '';
+    };
+  };
+}
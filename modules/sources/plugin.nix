@@ -0,0 +1,12 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.source.plugin = lib.mkOption {
+    type = lib.types.attrsOf (lib.types.attrsOf lib.types.anything);
+    default = {};
+    description = ''
+      Sources backed by an executable which is sent JSON requests, keyed by the name of the plugin
+      Each entry can set `cmd`, `args`, `timeout`, `columns`, and the usual `default_*` options
+      Relative commands are found in the plugins folder next to the config
+    '';
+  };
+}
@@ -0,0 +1,120 @@
+{ lib, ... }:
+{
+  options.programs.nyaa.source.torznab = {
+    base_url = lib.mkOption {
+      type = lib.types.str;
+      default = "http://localhost:9117/api/v2.0/indexers/all/results/torznab/";
+      description = ''
+        The base url for the Torznab indexer
+      '';
+    };
+    api_key = lib.mkOption {
+      type = lib.types.nullOr lib.types.str;
+      default = null;
+      description = ''
+        The API key for the Torznab indexer (optional)
+      '';
+    };
+    default_sort = lib.mkOption {
+      type = lib.types.str;
+      default = "Date";
+      description = ''
+        The default sort for Torznab
+      '';
+    };
+    default_sort_dir = lib.mkOption {
+      type = lib.types.str;
+      default = "Desc";
+      description = ''
+        The default sort direction for Torznab
+      '';
+    };
+    default_filter = lib.mkOption {
+      type = lib.types.str;
+      default = "NoFilter";
+      description = ''
+        The default filter for Torznab
+      '';
+    };
+    default_category = lib.mkOption {
+      type = lib.types.str;
+      default = "AllCategories";
+      description = ''
+        The default category for Torznab
+      '';
+    };
+    default_search = lib.mkOption {
+      type = lib.types.str;
+      default = "";
+      description = ''
+        The default search for Torznab
+      '';
+    };
+    limit = lib.mkOption {
+      type = lib.types.int;
+      default = 100;
+      description = ''
+        The number of results to request per page
+      '';
+    };
+    timeout = lib.mkOption {
+      type = lib.types.nullOr lib.types.int;
+      default = null;
+      description = ''
+        Override the global timeout for this source (measured in seconds) (optional)
+      '';
+    };
+
+    columns = {
+      category = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the category column (optional)
+        '';
+      };
+      title = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the title column (optional)
+        '';
+      };
+      size = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the size column (optional)
+        '';
+      };
+      date = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the date column (optional)
+        '';
+      };
+      seeders = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the seeders column (optional)
+        '';
+      };
+      leechers = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the leechers column (optional)
+        '';
+      };
+      downloads = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = ''
+          Whether to enable the grabs column (optional)
+        '';
+      };
+    };
+  };
+}
@@ -18,28 +18,36 @@ use tokio::{sync::mpsc, task::AbortHandle};
 #[cfg(feature = "captcha")]
 use crate::widget::captcha::CaptchaPopup;
 use crate::{
-    client::{Client, DownloadResult},
+    client::{Client, DownloadResult, Profile, StatusResult},
     clip::ClipboardManager,
     config::{Config, ConfigManager},
+    history::{self, DownloadChange, DownloadHistory, History, SavedSearch},
+    keybinds::{Action, Keybinds},
     results::Results,
+    routing,
     source::{
-        nyaa_html::NyaaHtmlSource, request_client, Item, Source, SourceInfo, SourceResults, Sources,
+        multi, nyaa_html::NyaaHtmlSource, request_client, Item, Source, SourceInfo, SourceResults,
+        Sources,
     },
-    sync::{EventSync, ReloadType, SearchQuery},
+    sync::{DetailsResult, EventSync, ReloadType, SearchQuery, TorrentResult},
     theme::{self, Theme},
     util::conv::key_to_string,
     widget::{
         batch::BatchWidget,
         category::CategoryPopup,
         clients::ClientsPopup,
+        details::{DetailsContent, DetailsPopup},
+        downloads::DownloadsPopup,
         filter::FilterPopup,
         help::HelpPopup,
+        history::HistoryPopup,
         notifications::NotificationWidget,
         page::PagePopup,
         results::ResultsWidget,
         search::SearchWidget,
         sort::{SortDir, SortPopup},
         sources::SourcesPopup,
+        status::StatusPopup,
         themes::ThemePopup,
         user::UserPopup,
         Widget,
@@ -60,6 +68,9 @@ pub static APP_NAME: &str = "nyaa";
 // To ensure that other events will get a chance to be received
 static ANIMATE_SLEEP_MILLIS: u64 = 5;
 
+// How often the status of torrents is loaded while the status popup is open
+static STATUS_REFRESH_SECS: u64 = 2;
+
 #[derive(PartialEq, Clone)]
 pub enum LoadType {
     Sourcing,
@@ -70,6 +81,10 @@ pub enum LoadType {
     Categorizing,
     Batching,
     Downloading,
+    Details,
+    Preview,
+    Restoring,
+    Redownloading,
 }
 
 #[derive(PartialEq, Clone)]
@@ -88,6 +103,10 @@ pub enum Mode {
     Page,
     User,
     Help,
+    Details,
+    History,
+    Downloads,
+    Status,
     Captcha,
 }
 
@@ -107,6 +126,10 @@ widgets! {
         page: [Mode::Page]  => PagePopup,
         user: [Mode::User] => UserPopup,
         help: [Mode::Help] => HelpPopup,
+        details: [Mode::Details] => DetailsPopup,
+        history: [Mode::History] => HistoryPopup,
+        downloads: [Mode::Downloads] => DownloadsPopup,
+        status: [Mode::Status] => StatusPopup,
         #[cfg(feature = "captcha")]
         captcha: [Mode::Captcha] => CaptchaPopup,
     }
@@ -123,6 +146,10 @@ impl Display for LoadType {
             LoadType::Categorizing => "Categorizing",
             LoadType::Batching => "Downloading Batch",
             LoadType::Downloading => "Downloading",
+            LoadType::Details => "Loading details",
+            LoadType::Preview => "Loading torrent",
+            LoadType::Restoring => "Searching",
+            LoadType::Redownloading => "Downloading",
         };
         write!(f, "{}", s)
     }
@@ -144,6 +171,10 @@ impl Display for Mode {
             Mode::Page => "Page",
             Mode::User => "User",
             Mode::Help => "Help",
+            Mode::Details => "Details",
+            Mode::History => "History",
+            Mode::Downloads => "Downloads",
+            Mode::Status => "Status",
             Mode::Captcha => "Captcha",
         }
         .to_owned();
@@ -164,13 +195,16 @@ pub struct Context {
     pub src_info: SourceInfo,
     pub theme: Theme,
     pub config: Config,
+    pub keys: Keybinds,
     pub page: usize,
     pub user: Option<String>,
     pub src: Sources,
-    pub client: Client,
+    pub client: Profile,
     pub batch: Vec<Item>,
     pub last_key: String,
     pub results: Results,
+    pub history: History,
+    pub downloads: DownloadHistory,
     pub deltatime: f64,
     errors: Vec<String>,
     notifications: Vec<String>,
@@ -178,6 +212,9 @@ pub struct Context {
     should_quit: bool,
     should_dismiss_notifications: bool,
     should_save_config: bool,
+    should_save_history: bool,
+    /// Changes to the download history that haven't been saved yet
+    download_changes: Vec<DownloadChange>,
 }
 
 impl Context {
@@ -198,6 +235,29 @@ impl Context {
         Ok(())
     }
 
+    pub fn save_history(&mut self) {
+        self.should_save_history = true;
+    }
+
+    pub fn save_downloads(&mut self, changes: Vec<DownloadChange>) {
+        self.download_changes.extend(changes);
+    }
+
+    /// The source a result was found on, which Multi remembers for each item
+    pub fn item_source(&self, item: &Item) -> Sources {
+        multi::item_source(item).unwrap_or(self.src.clone())
+    }
+
+    /// The names of the clients the routing rules will send the items to
+    pub fn routed_clients(&self, items: &[Item]) -> String {
+        let rules = self.config.routing.as_deref().unwrap_or_default();
+        routing::profiles(rules, Some(&self.src), items, &self.client)
+            .iter()
+            .map(Profile::to_string)
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
@@ -212,20 +272,25 @@ impl Default for Context {
             src_info: NyaaHtmlSource::info(),
             theme: Theme::default(),
             config: Config::default(),
+            keys: Keybinds::default(),
             errors: Vec::new(),
             notifications: Vec::new(),
             page: 1,
             user: None,
             src: Sources::Nyaa,
-            client: Client::Cmd,
+            client: Profile::Client(Client::Cmd),
             batch: vec![],
             last_key: "".to_owned(),
             results: Results::default(),
+            history: History::default(),
+            downloads: DownloadHistory::default(),
             deltatime: 0.0,
             failed_config_load: true,
             should_quit: false,
             should_dismiss_notifications: false,
             should_save_config: false,
+            should_save_history: false,
+            download_changes: vec![],
         }
     }
 }
@@ -241,12 +306,20 @@ impl App {
 
         let timer = tokio::time::sleep(Duration::from_millis(ANIMATE_SLEEP_MILLIS));
         tokio::pin!(timer);
+        let status_timer = tokio::time::sleep(Duration::from_secs(STATUS_REFRESH_SECS));
+        tokio::pin!(status_timer);
 
         let (tx_res, mut rx_res) =
             mpsc::channel::<Result<SourceResults, Box<dyn Error + Send + Sync>>>(32);
         let (tx_evt, mut rx_evt) = mpsc::channel::<Event>(100);
         let (tx_dl, mut rx_dl) = mpsc::channel::<DownloadResult>(100);
         let (tx_cfg, mut rx_cfg) = mpsc::channel::<ReloadType>(1);
+        let (tx_details, mut rx_details) = mpsc::channel::<DetailsResult>(1);
+        let (tx_torrent, mut rx_torrent) = mpsc::channel::<TorrentResult>(1);
+        let (tx_status, mut rx_status) = mpsc::channel::<StatusResult>(1);
+        let (tx_manage, mut rx_manage) =
+            mpsc::channel::<Result<String, Box<dyn Error + Send + Sync>>>(8);
+        let (tx_info, mut rx_info) = mpsc::channel::<Result<(), Box<dyn Error + Send + Sync>>>(1);
 
         tokio::task::spawn(sync.clone().read_event_loop(tx_evt));
         tokio::task::spawn(sync.clone().watch_config_loop(tx_cfg));
@@ -272,6 +345,22 @@ impl App {
             }
         }
 
+        if !TEST {
+            match history::load_history(config_manager.path()) {
+                Ok(h) => ctx.history = h,
+                Err(e) => ctx.show_error(format!("Failed to load search history:\n{}", e)),
+            }
+            match history::load_downloads(config_manager.path()) {
+                Ok(d) => ctx.downloads = d,
+                Err(e) => ctx.show_error(format!("Failed to load download history:\n{}", e)),
+            }
+        }
+
+        if !ctx.src.info_loaded(&ctx.config.sources) {
+            // Set the defaults again once the source has sent its info
+            ctx.mode = Mode::Loading(LoadType::Sourcing);
+        }
+
         let jar = Arc::new(Jar::default());
         let source_rqclient =
             request_client(&jar, ctx.config.timeout, ctx.config.request_proxy.clone())?;
@@ -279,6 +368,10 @@ impl App {
         let client_rqclient = request_client(&jar, ctx.config.timeout, None)?;
         let mut last_load_abort: Option<AbortHandle> = None;
         let mut last_time: Option<Instant> = None;
+        // Items sent to a download client, waiting for the result
+        let mut pending_downloads: Vec<(Item, Profile, Option<Sources>)> = vec![];
+        // Waiting on the info of a source before loading
+        let mut pending_info: Option<LoadType> = None;
 
         let (clipboard, err) = &mut if TEST {
             ClipboardManager::empty(ctx.config.clipboard.clone().unwrap_or_default())
@@ -295,6 +388,26 @@ impl App {
                     ctx.show_error(e);
                 }
             }
+            if ctx.should_save_history {
+                ctx.should_save_history = false;
+                if !TEST {
+                    if let Err(e) = history::store_history(config_manager.path(), &ctx.history) {
+                        ctx.show_error(format!("Failed to save search history:\n{}", e));
+                    }
+                }
+            }
+            if !ctx.download_changes.is_empty() {
+                let changes = std::mem::take(&mut ctx.download_changes);
+                if !TEST {
+                    // Keep anything saved by `nyaa watch` or another instance in the meantime
+                    match history::update_downloads(config_manager.path(), &changes) {
+                        Ok(d) => ctx.downloads = d,
+                        Err(e) => {
+                            ctx.show_error(format!("Failed to save download history:\n{}", e))
+                        }
+                    }
+                }
+            }
             if !ctx.notifications.is_empty() {
                 ctx.notifications
                     .clone()
@@ -322,8 +435,74 @@ impl App {
 
             self.get_help(ctx);
             terminal.draw(|f| self.draw(ctx, f))?;
+            if let Some((client, hash, name, action)) = self.widgets.status.action.take() {
+                tokio::spawn(sync.clone().manage_torrent(
+                    tx_manage.clone(),
+                    hash,
+                    name,
+                    action,
+                    ctx.config.client.clone(),
+                    client_rqclient.clone(),
+                    client,
+                ));
+            }
+            if ctx.mode == Mode::Status
+                && self.widgets.status.refresh
+                && !self.widgets.status.loading
+            {
+                self.widgets.status.refresh = false;
+                self.widgets.status.loading = true;
+                tokio::spawn(sync.clone().load_status(
+                    tx_status.clone(),
+                    StatusPopup::hashes(ctx),
+                    ctx.config.client.clone(),
+                    client_rqclient.clone(),
+                ));
+                status_timer
+                    .as_mut()
+                    .reset(tokio::time::Instant::now() + Duration::from_secs(STATUS_REFRESH_SECS));
+            }
             if let Mode::Loading(load_type) = ctx.mode.clone() {
                 ctx.mode = Mode::Normal;
+                let info_src = match load_type {
+                    LoadType::Downloading
+                    | LoadType::Redownloading
+                    | LoadType::Batching
+                    | LoadType::Details
+                    | LoadType::Preview => None,
+                    LoadType::Restoring => self
+                        .widgets
+                        .history
+                        .restore
+                        .as_ref()
+                        .map(|s| s.source.clone()),
+                    _ => Some(ctx.src.clone()),
+                };
+                if let Some(src) = info_src
+                    .clone()
+                    .filter(|s| !s.info_loaded(&ctx.config.sources))
+                {
+                    if let Some(handle) = last_load_abort.take() {
+                        handle.abort();
+                    }
+                    if pending_info.is_none() {
+                        tokio::spawn(sync.clone().load_info(
+                            tx_info.clone(),
+                            src,
+                            ctx.config.sources.clone(),
+                        ));
+                    }
+                    // Changing source still has to reset the defaults
+                    if pending_info != Some(LoadType::Sourcing) {
+                        pending_info = Some(load_type.clone());
+                    }
+                    ctx.load_type = Some(load_type);
+                    continue;
+                }
+                if info_src.is_some() {
+                    // Anything still waiting on info was replaced by this load
+                    pending_info = None;
+                }
                 match load_type {
                     LoadType::Downloading => {
                         if let Some(i) = self
@@ -333,37 +512,125 @@ impl App {
                             .selected()
                             .and_then(|i| ctx.results.response.items.get(i))
                         {
+                            pending_downloads.push((
+                                i.to_owned(),
+                                ctx.client.clone(),
+                                Some(ctx.item_source(i)),
+                            ));
                             tokio::spawn(sync.clone().download(
                                 tx_dl.clone(),
                                 false,
                                 vec![i.to_owned()],
                                 ctx.config.client.clone(),
                                 client_rqclient.clone(),
-                                ctx.client,
+                                ctx.client.clone(),
+                                ctx.src.clone(),
+                                ctx.config.routing.clone().unwrap_or_default(),
+                                source_rqclient.clone(),
+                                ctx.config.sources.clone(),
+                            ));
+                            ctx.notify(format!(
+                                "Downloading torrent with {}",
+                                ctx.routed_clients(&[i.to_owned()])
+                            ));
+                        }
+                        continue;
+                    }
+                    LoadType::Redownloading => {
+                        if let Some((i, src)) = self.widgets.downloads.resend.take() {
+                            ctx.notify(format!(
+                                "Downloading torrent with {}",
+                                ctx.routed_clients(&[i.to_owned()])
+                            ));
+                            pending_downloads.push((i.to_owned(), ctx.client.clone(), src.clone()));
+                            tokio::spawn(sync.clone().download(
+                                tx_dl.clone(),
+                                false,
+                                vec![i],
+                                ctx.config.client.clone(),
+                                client_rqclient.clone(),
+                                ctx.client.clone(),
+                                src.unwrap_or(ctx.src.clone()),
+                                ctx.config.routing.clone().unwrap_or_default(),
+                                source_rqclient.clone(),
+                                ctx.config.sources.clone(),
                             ));
-                            ctx.notify(format!("Downloading torrent with {}", ctx.client));
                         }
+                        ctx.mode = Mode::Downloads;
                         continue;
                     }
                     LoadType::Batching => {
+                        pending_downloads.extend(
+                            ctx.batch.iter().map(|i| {
+                                (i.to_owned(), ctx.client.clone(), Some(ctx.item_source(i)))
+                            }),
+                        );
                         tokio::spawn(sync.clone().download(
                             tx_dl.clone(),
                             true,
                             ctx.batch.clone(),
                             ctx.config.client.clone(),
                             client_rqclient.clone(),
-                            ctx.client,
+                            ctx.client.clone(),
+                            ctx.src.clone(),
+                            ctx.config.routing.clone().unwrap_or_default(),
+                            source_rqclient.clone(),
+                            ctx.config.sources.clone(),
                         ));
                         ctx.notify(format!(
                             "Downloading {} torrents with {}",
                             ctx.batch.len(),
-                            ctx.client
+                            ctx.routed_clients(&ctx.batch)
                         ));
                         continue;
                     }
+                    LoadType::Details => {
+                        if let Some(i) = self
+                            .widgets
+                            .results
+                            .table
+                            .selected()
+                            .and_then(|i| ctx.results.response.items.get(i))
+                        {
+                            self.widgets.details.loading(i.to_owned());
+                            ctx.mode = Mode::Details;
+                            tokio::spawn(sync.clone().load_details(
+                                tx_details.clone(),
+                                ctx.src.clone(),
+                                source_rqclient.clone(),
+                                i.to_owned(),
+                                ctx.config.sources.clone(),
+                            ));
+                        }
+                        continue;
+                    }
+                    LoadType::Preview => {
+                        if let Some(i) = self
+                            .widgets
+                            .results
+                            .table
+                            .selected()
+                            .and_then(|i| ctx.results.response.items.get(i))
+                        {
+                            self.widgets.details.loading(i.to_owned());
+                            ctx.mode = Mode::Details;
+                            tokio::spawn(sync.clone().load_torrent(
+                                tx_torrent.clone(),
+                                source_rqclient.clone(),
+                                i.to_owned(),
+                            ));
+                        }
+                        continue;
+                    }
+                    LoadType::Restoring => {
+                        if let Some(search) = self.widgets.history.restore.take() {
+                            self.restore_search(ctx, &search);
+                        }
+                        continue;
+                    }
                     LoadType::Sourcing => {
                         // On sourcing, update info, reset things like category, etc.
-                        ctx.src.apply(ctx, &mut self.widgets);
+                        ctx.src.clone().apply(ctx, &mut self.widgets);
                     }
                     _ => {}
                 }
@@ -382,11 +649,17 @@ impl App {
                     sort: self.widgets.sort.selected,
                     user: ctx.user.clone(),
                 };
+                ctx.history.push(SavedSearch::from_search(
+                    ctx.src.clone(),
+                    &search,
+                    &ctx.src_info,
+                ));
+                ctx.save_history();
 
                 let task = tokio::spawn(sync.clone().load_results(
                     tx_res.clone(),
                     load_type.clone(),
-                    ctx.src,
+                    ctx.src.clone(),
                     source_rqclient.clone(),
                     search,
                     ctx.config.sources.clone(),
@@ -446,6 +719,17 @@ impl App {
                         break;
                     },
                     Some(dl) = rx_dl.recv() => {
+                        // Failed items are dropped too, so a later download of them isn't
+                        // recorded with this request's client and source
+                        for id in dl.requested.iter() {
+                            if let Some(p) = pending_downloads.iter().position(|(i, _, _)| i.id.eq(id)) {
+                                let (item, client, src) = pending_downloads.remove(p);
+                                if dl.success_ids.contains(id) {
+                                    let changes = ctx.downloads.record(&[item], client, src, &dl);
+                                    ctx.save_downloads(changes);
+                                }
+                            }
+                        }
                         if dl.batch {
                             for id in dl.success_ids.iter() {
                                 ctx.batch.retain(|i| i.id.ne(id));
@@ -461,6 +745,61 @@ impl App {
                         }
                         break;
                     }
+                    Some((id, details)) = rx_details.recv() => {
+                        // Another item was opened before this one finished loading
+                        if !self.widgets.details.is_showing(&id) {
+                            break;
+                        }
+                        match details {
+                            Ok(details) => self.widgets.details.content = DetailsContent::Post(details),
+                            Err(e) => {
+                                if ctx.mode == Mode::Details {
+                                    ctx.mode = Mode::Normal;
+                                }
+                                ctx.show_error(format!("Failed to load details:\n{}", e));
+                            }
+                        }
+                        break;
+                    }
+                    Some((id, torrent)) = rx_torrent.recv() => {
+                        if !self.widgets.details.is_showing(&id) {
+                            break;
+                        }
+                        match torrent {
+                            Ok(info) => self.widgets.details.content = DetailsContent::Torrent(info),
+                            Err(e) => {
+                                if ctx.mode == Mode::Details {
+                                    ctx.mode = Mode::Normal;
+                                }
+                                ctx.show_error(format!("Failed to load torrent:\n{}", e));
+                            }
+                        }
+                        break;
+                    }
+                    Some(_) = rx_info.recv() => {
+                        // Failures are kept with the info, and shown by the search
+                        if let Some(load_type) = pending_info.take() {
+                            ctx.src_info = ctx.src.info(&ctx.config.sources);
+                            ctx.mode = Mode::Loading(load_type);
+                        }
+                        break;
+                    }
+                    Some(status) = rx_status.recv() => {
+                        self.widgets.status.update(ctx, status);
+                        break;
+                    }
+                    Some(res) = rx_manage.recv() => {
+                        match res {
+                            Ok(msg) => ctx.notify(msg),
+                            Err(e) => ctx.show_error(e),
+                        }
+                        self.widgets.status.refresh = true;
+                        break;
+                    }
+                    () = &mut status_timer, if ctx.mode == Mode::Status && !self.widgets.status.refresh => {
+                        self.widgets.status.refresh = true;
+                        break;
+                    }
                     Some(notif) = rx_cfg.recv() => {
                         match notif {
                             ReloadType::Config => {
@@ -470,6 +809,9 @@ impl App {
                                             Ok(()) => ctx.notify("Reloaded config".to_owned()),
                                             Err(e) => ctx.show_error(e),
                                         }
+                                        if !ctx.src.info_loaded(&ctx.config.sources) {
+                                            ctx.mode = Mode::Loading(LoadType::Sourcing);
+                                        }
                                     }
                                     Err(e) => ctx.show_error(e),
                                 }
@@ -565,7 +907,7 @@ impl App {
         }) = evt
         {
             #[cfg(unix)]
-            if let (KeyCode::Char('z'), &KeyModifiers::CONTROL) = (code, modifiers) {
+            if let Some(Action::Suspend) = ctx.keys.global.get(code, modifiers) {
                 if let Err(e) = term::suspend_self(terminal) {
                     ctx.show_error(format!("Failed to suspend:\n{}", e));
                 }
@@ -596,23 +938,58 @@ impl App {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Char('?') if ctx.mode != Mode::Search => {
-                    ctx.mode = Mode::Help;
-                }
-                KeyCode::F(1) => {
-                    ctx.mode = Mode::Help;
-                }
-                _ => {}
+            // Don't open help when typing characters into an input
+            let typing = matches!(code, KeyCode::Char(_))
+                && matches!(modifiers, &KeyModifiers::NONE | &KeyModifiers::SHIFT)
+                && (matches!(
+                    ctx.mode,
+                    Mode::Search | Mode::Page | Mode::User | Mode::Captcha
+                ) || (ctx.mode == Mode::History && self.widgets.history.naming)
+                    || (ctx.mode == Mode::Downloads && self.widgets.downloads.filtering)
+                    || (ctx.mode == Mode::Status && self.widgets.status.categorizing));
+            if ctx.keys.global.get(code, modifiers) == Some(Action::Help) && !typing {
+                ctx.mode = Mode::Help;
             }
         }
     }
 
+    /// Set the source, query, category, filter, sort and user of a stored
+    /// search, then run it
+    fn restore_search(&mut self, ctx: &mut Context, search: &SavedSearch) {
+        if search.source != ctx.src {
+            ctx.src = search.source.clone();
+            ctx.src.load_config(&mut ctx.config.sources);
+        }
+        let query = match search.to_query(&ctx.config.sources) {
+            Ok(query) => query,
+            Err(e) => return ctx.show_error(format!("Failed to restore search:\n{}", e)),
+        };
+        ctx.src.clone().apply(ctx, &mut self.widgets);
+
+        let w = &mut self.widgets;
+        w.search.input.input = query.query;
+        w.search
+            .input
+            .set_cursor(w.search.input.input.chars().count());
+        w.category.selected = query.category;
+        let (major, minor) = ctx.src_info.get_major_minor(query.category);
+        w.category.table.select(major + minor + 1);
+        w.category.major = major;
+        w.category.minor = minor;
+        w.filter.selected = query.filter;
+        w.filter.table.select(query.filter);
+        w.sort.selected = query.sort;
+        w.sort.table.select(query.sort.sort);
+        ctx.user = query.user;
+        ctx.mode = Mode::Loading(LoadType::Searching);
+    }
+
     fn get_help(&mut self, ctx: &Context) {
-        let help = self.widgets.get_help(&ctx.mode);
+        let help = self.widgets.get_help(&ctx.mode, &ctx.keys);
         if let Some(msg) = help {
             self.widgets.help.with_items(msg, ctx.mode.clone());
             self.widgets.help.table.select(0);
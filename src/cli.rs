@@ -0,0 +1,587 @@
+use std::{error::Error, fmt::Display, str::FromStr, sync::Arc};
+
+use reqwest::{cookie::Jar, StatusCode, Url};
+use scraper::{Html, Selector};
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr as _;
+
+use crate::{
+    app::LoadType,
+    client::{ClientConfig, DownloadResult, Profile},
+    config::Config,
+    results::ResultResponse,
+    routing, sel,
+    source::{
+        multi, request_client, Item, ItemType, SourceConfig, SourceInfo, SourceResponse, Sources,
+    },
+    sync::SearchQuery,
+    util::conv::get_hash,
+    widget::sort::{SelectedSort, SortDir},
+};
+
+pub enum Command {
+    Search(SearchArgs),
+    Download(DownloadArgs),
+    Watch { once: bool },
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    #[default]
+    Table,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "table" => Ok(OutputFormat::Table),
+            _ => Err(format!(
+                "Unknown format \"{}\", expected one of: json, csv, table",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OutputFormat::Json => "json",
+                OutputFormat::Csv => "csv",
+                OutputFormat::Table => "table",
+            }
+        )
+    }
+}
+
+/// Options shared by every subcommand which needs to run a search
+#[derive(Clone, Default)]
+pub struct QueryArgs {
+    pub query: Option<String>,
+    pub source: Option<String>,
+    pub category: Option<String>,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub sort_dir: Option<SortDir>,
+    pub user: Option<String>,
+    pub page: Option<usize>,
+}
+
+#[derive(Clone, Default)]
+pub struct SearchArgs {
+    pub query: QueryArgs,
+    pub format: OutputFormat,
+}
+
+#[derive(Clone, Default)]
+pub struct DownloadArgs {
+    /// The query, post url or magnet link is stored in `query.query`
+    pub query: QueryArgs,
+    pub client: Option<String>,
+    pub count: Option<usize>,
+}
+
+/// What the argument given to `nyaa download` refers to
+pub enum DownloadTarget {
+    Magnet(String),
+    Post(Url),
+    Query,
+}
+
+impl DownloadTarget {
+    pub fn from_arg(arg: &str) -> Self {
+        if arg.starts_with("magnet:") {
+            return DownloadTarget::Magnet(arg.to_owned());
+        }
+        match Url::parse(arg) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => DownloadTarget::Post(url),
+            _ => DownloadTarget::Query,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ItemRecord<'a> {
+    pub id: &'a str,
+    pub title: &'a str,
+    pub category: String,
+    pub category_id: usize,
+    pub size: &'a str,
+    pub bytes: usize,
+    pub date: &'a str,
+    pub seeders: u32,
+    pub leechers: u32,
+    pub downloads: u32,
+    pub trusted: bool,
+    pub remake: bool,
+    pub torrent_link: &'a str,
+    pub magnet_link: &'a str,
+    pub post_link: &'a str,
+}
+
+#[derive(Serialize)]
+struct SearchOutput<'a> {
+    source: String,
+    page: usize,
+    last_page: usize,
+    total_results: usize,
+    items: Vec<ItemRecord<'a>>,
+}
+
+impl<'a> ItemRecord<'a> {
+    pub fn new(item: &'a Item, info: &SourceInfo) -> Self {
+        Self {
+            id: &item.id,
+            title: &item.title,
+            category: info.clone().entry_from_id(item.category).cfg,
+            category_id: item.category,
+            size: &item.size,
+            bytes: item.bytes,
+            date: &item.date,
+            seeders: item.seeders,
+            leechers: item.leechers,
+            downloads: item.downloads,
+            trusted: matches!(item.item_type, ItemType::Trusted),
+            remake: matches!(item.item_type, ItemType::Remake),
+            torrent_link: &item.torrent_link,
+            magnet_link: &item.magnet_link,
+            post_link: &item.post_link,
+        }
+    }
+
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "id",
+            "title",
+            "category",
+            "category_id",
+            "size",
+            "bytes",
+            "date",
+            "seeders",
+            "leechers",
+            "downloads",
+            "trusted",
+            "remake",
+            "torrent_link",
+            "magnet_link",
+            "post_link",
+        ]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_owned(),
+            self.title.to_owned(),
+            self.category.to_owned(),
+            self.category_id.to_string(),
+            self.size.to_owned(),
+            self.bytes.to_string(),
+            self.date.to_owned(),
+            self.seeders.to_string(),
+            self.leechers.to_string(),
+            self.downloads.to_string(),
+            self.trusted.to_string(),
+            self.remake.to_string(),
+            self.torrent_link.to_owned(),
+            self.magnet_link.to_owned(),
+            self.post_link.to_owned(),
+        ]
+    }
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn find_index(names: &[String], name: &str, kind: &str) -> Result<usize, String> {
+    let norm = normalize(name);
+    names
+        .iter()
+        .position(|n| normalize(n) == norm)
+        .ok_or_else(|| {
+            format!(
+                "Unknown {} \"{}\", expected one of: {}",
+                kind,
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+pub fn find_source(name: &str, config: &SourceConfig) -> Result<Sources, String> {
+    let sources = Sources::all(config);
+    let names = sources
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+    find_index(&names, name, "source").map(|i| sources[i].clone())
+}
+
+pub fn find_client(name: &str, conf: &ClientConfig) -> Result<Profile, String> {
+    let profiles = conf.all_profiles();
+    let names = profiles
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+    find_index(&names, name, "download client").map(|i| profiles[i].clone())
+}
+
+pub fn find_category(info: &SourceInfo, name: &str) -> Result<usize, String> {
+    let norm = normalize(name);
+    info.cats
+        .iter()
+        .flat_map(|c| c.entries.iter())
+        .find(|e| normalize(&e.cfg) == norm || e.id.to_string() == name)
+        .map(|e| e.id)
+        .ok_or_else(|| {
+            format!(
+                "Unknown category \"{}\", expected one of: {}",
+                name,
+                info.cats
+                    .iter()
+                    .flat_map(|c| c.entries.iter().map(|e| e.cfg.to_owned()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+}
+
+/// Build a query for `src` from the given arguments, falling back on the
+/// configured source defaults for anything left unspecified.
+pub fn build_query(
+    src: &Sources,
+    args: &QueryArgs,
+    config: &SourceConfig,
+) -> Result<SearchQuery, String> {
+    let info = src.info(config);
+    let category = match args.category.as_ref() {
+        Some(c) => find_category(&info, c)?,
+        None => src.default_category(config),
+    };
+    let filter = match args.filter.as_ref() {
+        Some(f) => find_index(&info.filters, f, "filter")?,
+        None => src.default_filter(config),
+    };
+    let default_sort = src.default_sort(config);
+    let sort = SelectedSort {
+        sort: match args.sort.as_ref() {
+            Some(s) => find_index(&info.sorts, s, "sort")?,
+            None => default_sort.sort,
+        },
+        dir: args.sort_dir.unwrap_or(default_sort.dir),
+    };
+    Ok(SearchQuery {
+        query: args
+            .query
+            .clone()
+            .unwrap_or_else(|| src.default_search(config)),
+        page: args.page.unwrap_or(1).max(1),
+        category,
+        filter,
+        sort,
+        user: args.user.clone(),
+    })
+}
+
+/// Run a single search with the given config, without any terminal setup.
+pub async fn search(
+    args: &QueryArgs,
+    config: &Config,
+) -> Result<(Sources, SearchQuery, ResultResponse), Box<dyn Error + Send + Sync>> {
+    let src = match args.source.as_ref() {
+        Some(s) => find_source(s, &config.sources)?,
+        None => config.source.clone(),
+    };
+    let mut sources = config.sources.clone();
+    src.load_config(&mut sources);
+    src.load_info(&sources).await?;
+    let search = build_query(&src, args, &sources)?;
+
+    let jar = Arc::new(Jar::default());
+    let client = request_client(&jar, config.timeout, config.request_proxy.clone())
+        .map_err(|e| e.to_string())?;
+    let res = load_results(&src, &client, &search, &sources, config.date_format.clone()).await?;
+    Ok((src, search, res))
+}
+
+/// Load the results for a search, failing if the source asks for a captcha
+/// since there is no way to solve it outside of the TUI.
+pub async fn load_results(
+    src: &Sources,
+    client: &reqwest::Client,
+    search: &SearchQuery,
+    config: &SourceConfig,
+    date_format: Option<String>,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    match src
+        .load(LoadType::Searching, client, search, config, date_format)
+        .await?
+    {
+        SourceResponse::Results(res) => Ok(res),
+        #[cfg(feature = "captcha")]
+        SourceResponse::Captcha(_) => {
+            Err(format!("{} requires solving a captcha, use the TUI instead", src).into())
+        }
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub fn format_csv(records: &[ItemRecord]) -> String {
+    let mut out = ItemRecord::csv_header().join(",");
+    out.push('\n');
+    for rec in records.iter() {
+        out.push_str(
+            &rec.csv_fields()
+                .iter()
+                .map(|f| csv_escape(f))
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+pub fn format_plain_table(items: &[Item]) -> String {
+    let header = ["Cat", "Name", "Size", "Date", "S", "L", "D"];
+    let rows = items
+        .iter()
+        .map(|i| {
+            vec![
+                i.icon.label.to_owned(),
+                i.title.to_owned(),
+                i.size.to_owned(),
+                i.date.to_owned(),
+                i.seeders.to_string(),
+                i.leechers.to_string(),
+                i.downloads.to_string(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(c, h)| {
+            rows.iter()
+                .map(|r| r[c].width())
+                .chain([h.width()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+
+    let fmt_row = |row: Vec<&str>| {
+        let mut line = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, w)| format!("{}{}", cell, " ".repeat(w - cell.width())))
+            .collect::<Vec<String>>()
+            .join("  ");
+        line.truncate(line.trim_end().len());
+        line.push('\n');
+        line
+    };
+
+    let mut out = fmt_row(header.to_vec());
+    for row in rows.iter() {
+        out.push_str(&fmt_row(row.iter().map(String::as_str).collect()));
+    }
+    out
+}
+
+pub fn format_results(
+    src: &Sources,
+    search: &SearchQuery,
+    res: &ResultResponse,
+    config: &SourceConfig,
+    format: OutputFormat,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let info = src.info(config);
+    let records = res
+        .items
+        .iter()
+        .map(|i| match multi::item_source(i) {
+            // Merged results use the categories of the source they came from
+            Some(origin) => ItemRecord::new(i, &origin.info(config)),
+            None => ItemRecord::new(i, &info),
+        })
+        .collect::<Vec<ItemRecord>>();
+    Ok(match format {
+        OutputFormat::Json => {
+            let mut out = serde_json::to_string_pretty(&SearchOutput {
+                source: src.to_string(),
+                page: search.page,
+                last_page: res.last_page,
+                total_results: res.total_results,
+                items: records,
+            })?;
+            out.push('\n');
+            out
+        }
+        OutputFormat::Csv => format_csv(&records),
+        OutputFormat::Table => format_plain_table(&res.items),
+    })
+}
+
+pub fn item_from_magnet(magnet: &str) -> Item {
+    let title = Url::parse(magnet)
+        .ok()
+        .and_then(|u| {
+            u.query_pairs()
+                .find(|(k, _)| k == "dn")
+                .map(|(_, v)| v.to_string())
+        })
+        .unwrap_or_else(|| magnet.to_owned());
+    let hash = get_hash(format!("{}&", magnet)).unwrap_or_else(|| magnet.to_owned());
+    Item {
+        id: hash,
+        title,
+        magnet_link: magnet.to_owned(),
+        ..Default::default()
+    }
+}
+
+/// Parse a torrent's post page, taking the first magnet and `.torrent` links
+/// found on the page.
+pub fn parse_post(url: &Url, html: &str) -> Result<Item, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+    let link_sel = sel!("a[href]")?;
+    let title_sel = sel!("meta[property=\"og:title\"]")?;
+
+    let links = doc
+        .select(&link_sel)
+        .filter_map(|e| e.value().attr("href"))
+        .collect::<Vec<&str>>();
+    let magnet_link = links
+        .iter()
+        .find(|l| l.starts_with("magnet:"))
+        .map(|l| l.to_string())
+        .unwrap_or_default();
+    let torrent_link = links
+        .iter()
+        .find(|l| !l.starts_with("magnet:") && l.contains(".torrent"))
+        .and_then(|l| url.join(l).ok())
+        .map(|l| l.to_string())
+        .unwrap_or_default();
+    if magnet_link.is_empty() && torrent_link.is_empty() {
+        return Err(format!("No magnet or torrent link found on \"{}\"", url).into());
+    }
+
+    let mut item = item_from_magnet(&magnet_link);
+    if let Some(title) = doc
+        .select(&title_sel)
+        .next()
+        .and_then(|e| e.value().attr("content"))
+    {
+        item.title = title.trim().to_owned();
+    }
+    if magnet_link.is_empty() {
+        item.id = url.to_string();
+        if item.title.is_empty() {
+            item.title = url.to_string();
+        }
+    }
+    item.file_name = Url::parse(&torrent_link)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut s| s.next_back().map(str::to_owned))
+        })
+        .unwrap_or_default();
+    item.torrent_link = torrent_link;
+    item.post_link = url.to_string();
+    Ok(item)
+}
+
+/// Resolve the items to download, then send them to the chosen client.
+pub async fn download(
+    args: &DownloadArgs,
+    config: &Config,
+) -> Result<(Profile, Option<Sources>, Vec<Item>, DownloadResult), Box<dyn Error + Send + Sync>> {
+    let client = match args.client.as_ref() {
+        Some(c) => find_client(c, &config.client)?,
+        None => config.download_client.clone(),
+    };
+    // Routing is skipped when a client is picked explicitly
+    let routing = match args.client {
+        Some(_) => vec![],
+        None => config.routing.clone().unwrap_or_default(),
+    };
+    let mut client_config = config.client.clone();
+    client.load_config(&mut client_config);
+
+    let target = args.query.query.clone().unwrap_or_default();
+    let jar = Arc::new(Jar::default());
+    let source_rq = request_client(&jar, config.timeout, config.request_proxy.clone())
+        .map_err(|e| e.to_string())?;
+    // Magnets and post urls aren't from a source, so they aren't resolved and
+    // only match routing rules without a source
+    let (src, items) = match DownloadTarget::from_arg(&target) {
+        DownloadTarget::Magnet(magnet) => (None, vec![item_from_magnet(&magnet)]),
+        DownloadTarget::Post(url) => {
+            let response = source_rq.get(url.clone()).send().await?;
+            if response.status() != StatusCode::OK {
+                let code = response.status().as_u16();
+                return Err(format!("{}\nInvalid response code: {}", url, code).into());
+            }
+            let html = response.text().await?;
+            (None, vec![parse_post(&url, &html)?])
+        }
+        DownloadTarget::Query => {
+            let (src, _, res) = search(&args.query, config).await?;
+            let items = res
+                .items
+                .into_iter()
+                .take(args.count.unwrap_or(1).max(1))
+                .collect();
+            (Some(src), items)
+        }
+    };
+    if items.is_empty() {
+        return Err(format!("No results found for \"{}\"", target).into());
+    }
+
+    let (resolved, errors) = match src.as_ref() {
+        Some(src) => {
+            src.resolve(&source_rq, items.clone(), &config.sources)
+                .await
+        }
+        None => (items.clone(), vec![]),
+    };
+    // Don't use proxy for clients
+    let rq = request_client(&jar, config.timeout, None).map_err(|e| e.to_string())?;
+    let mut res = routing::download(
+        &routing,
+        src.as_ref(),
+        items.len() > 1,
+        resolved.clone(),
+        &client,
+        &client_config,
+        rq,
+        source_rq,
+    )
+    .await;
+    res.errors.extend(errors);
+    res.resolved = resolved;
+    Ok((client, src, items, res))
+}
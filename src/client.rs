@@ -1,42 +1,154 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, error::Error, fmt::Display};
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    Deserialize, Serialize,
+};
 use strum::{Display, VariantArray};
 use tokio::task::JoinSet;
 
 use crate::{client::cmd::CmdClient, source::Item};
 
 use self::{
+    aria2::{Aria2Client, Aria2Config},
     cmd::CmdConfig,
     default_app::{DefaultAppClient, DefaultAppConfig},
+    deluge::{DelugeClient, DelugeConfig},
     download::{DownloadConfig, DownloadFileClient},
     qbit::{QbitClient, QbitConfig},
     rqbit::{RqbitClient, RqbitConfig},
+    rtorrent::{RtorrentClient, RtorrentConfig},
     transmission::{TransmissionClient, TransmissionConfig},
+    watch_folder::{WatchFolderClient, WatchFolderConfig},
+    webhook::{WebhookClient, WebhookConfig},
 };
 
+pub mod aria2;
 pub mod cmd;
 pub mod default_app;
+pub mod deluge;
 pub mod download;
 pub mod qbit;
 pub mod rqbit;
+pub mod rtorrent;
 pub mod transmission;
+pub mod watch_folder;
+pub mod webhook;
 
-pub struct DownloadError(String);
+pub struct DownloadError(pub String);
 
 pub trait DownloadClient {
+    /// `source_client` is the client used for the source of the items, which
+    /// is used for anything downloaded from the source, like .torrent files
     fn download(
         item: Item,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> impl std::future::Future<Output = DownloadResult> + std::marker::Send + 'static;
     fn batch_download(
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> impl std::future::Future<Output = DownloadResult> + std::marker::Send + 'static;
+    /// Get the status of the torrents in the client with one of the given info
+    /// hashes
+    fn list_torrents(
+        _hashes: Vec<String>,
+        _conf: ClientConfig,
+        _client: reqwest::Client,
+    ) -> impl std::future::Future<Output = Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>>>
+           + std::marker::Send
+           + 'static {
+        async { Err("This client can't show the status of torrents".into()) }
+    }
+    /// Apply an action to the torrents in the client with one of the given
+    /// info hashes
+    fn manage(
+        _hashes: Vec<String>,
+        _action: TorrentAction,
+        _conf: ClientConfig,
+        _client: reqwest::Client,
+    ) -> impl std::future::Future<Output = Result<(), Box<dyn Error + Send + Sync>>>
+           + std::marker::Send
+           + 'static {
+        async { Err("This client can't manage torrents".into()) }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TorrentAction {
+    Pause,
+    Resume,
+    Remove,
+    /// Remove the torrent and delete its downloaded files
+    RemoveData,
+    Recheck,
+    SetCategory(String),
+}
+
+impl TorrentAction {
+    pub fn success_msg(&self, name: &str) -> String {
+        match self {
+            Self::Pause => format!("Paused \"{}\"", name),
+            Self::Resume => format!("Resumed \"{}\"", name),
+            Self::Remove => format!("Removed \"{}\"", name),
+            Self::RemoveData => format!("Removed \"{}\" and deleted its files", name),
+            Self::Recheck => format!("Rechecking \"{}\"", name),
+            Self::SetCategory(c) => format!("Set category of \"{}\" to \"{}\"", name, c),
+        }
+    }
+}
+
+impl Display for TorrentAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self {
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Remove | Self::RemoveData => "remove",
+            Self::Recheck => "recheck",
+            Self::SetCategory(_) => "set category of",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+#[derive(Display, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum TorrentState {
+    Downloading,
+    Seeding,
+    Paused,
+    Stalled,
+    Queued,
+    Checking,
+    Metadata,
+    Error,
+    #[default]
+    Unknown,
+}
+
+/// A torrent in a download client, with speeds in bytes per second
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct TorrentStatus {
+    pub hash: String,
+    pub name: String,
+    /// Between 0 and 1
+    pub progress: f64,
+    pub size: u64,
+    pub download_speed: u64,
+    pub upload_speed: u64,
+    /// Seconds until done, if known
+    pub eta: Option<u64>,
+    pub state: TorrentState,
 }
 
+/// The torrents found in each client that was asked, or why it failed
+pub type StatusResult = Vec<(
+    Profile,
+    Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>>,
+)>;
+
 impl Display for DownloadError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -48,6 +160,12 @@ pub struct DownloadResult {
     pub success_ids: Vec<String>,
     pub batch: bool,
     pub errors: Vec<DownloadError>,
+    /// Items whose links were filled in by their source before downloading
+    pub resolved: Vec<Item>,
+    /// The profile each item was sent to by the routing rules, by id
+    pub routed: Vec<(String, Profile)>,
+    /// The ids of every item that was asked to be downloaded, including failures
+    pub requested: Vec<String>,
 }
 
 impl DownloadResult {
@@ -62,6 +180,9 @@ impl DownloadResult {
             success_ids,
             batch,
             errors,
+            resolved: vec![],
+            routed: vec![],
+            requested: vec![],
         }
     }
 
@@ -71,8 +192,16 @@ impl DownloadResult {
             success_ids: vec![],
             batch: false,
             errors: vec![error],
+            resolved: vec![],
+            routed: vec![],
+            requested: vec![],
         }
     }
+
+    /// The profile the item was routed to, if it went through the routing rules
+    pub fn routed_to(&self, id: &str) -> Option<&Profile> {
+        self.routed.iter().find(|(i, _)| i == id).map(|(_, p)| p)
+    }
 }
 
 #[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq)]
@@ -100,6 +229,70 @@ pub enum Client {
     #[serde(rename = "RunCommand")]
     #[strum(serialize = "Run Command")]
     Cmd = 5,
+
+    #[serde(rename = "Deluge")]
+    #[strum(serialize = "Deluge")]
+    Deluge = 6,
+
+    #[serde(rename = "aria2")]
+    #[strum(serialize = "aria2")]
+    Aria2 = 7,
+
+    #[serde(rename = "rTorrent")]
+    #[strum(serialize = "rTorrent")]
+    Rtorrent = 8,
+
+    #[serde(rename = "Webhook")]
+    #[strum(serialize = "Webhook")]
+    Webhook = 9,
+    #[serde(rename = "WatchFolder")]
+    #[strum(serialize = "Watch Folder")]
+    WatchFolder = 10,
+}
+
+/// The download client to send torrents to, either a built-in client using its
+/// own config table, or one of the named profiles in `[client.profiles]`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Profile {
+    Client(Client),
+    Named(String),
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Profile::Client(c) => write!(f, "{}", c),
+            Profile::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Profile {
+    /// Named profiles are only known if they're declared in `[client.profiles]`
+    pub fn check(&self, config: &ClientConfig) -> Result<(), String> {
+        match self {
+            Profile::Named(name) if !config.profiles.contains_key(name) => Err(format!(
+                "Unknown client \"{}\", it isn't declared in [client.profiles]",
+                name
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<Client> for Profile {
+    fn from(client: Client) -> Self {
+        Profile::Client(client)
+    }
+}
+
+/// Another instance of a client, with its options in the same table
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ProfileConfig {
+    pub client: Client,
+    #[serde(flatten)]
+    pub options: toml::Table,
 }
 
 #[derive(Default, Clone, Deserialize, Serialize)]
@@ -116,6 +309,29 @@ pub struct ClientConfig {
     pub download: Option<DownloadConfig>,
     #[serde(rename = "rqbit")]
     pub rqbit: Option<RqbitConfig>,
+    #[serde(rename = "deluge")]
+    pub deluge: Option<DelugeConfig>,
+    #[serde(rename = "aria2")]
+    pub aria2: Option<Aria2Config>,
+    #[serde(rename = "rtorrent")]
+    pub rtorrent: Option<RtorrentConfig>,
+    #[serde(rename = "webhook")]
+    pub webhook: Option<WebhookConfig>,
+    #[serde(rename = "watch_folder")]
+    pub watch_folder: Option<WatchFolderConfig>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+impl ClientConfig {
+    /// Every client that can be selected, built-in clients first
+    pub fn all_profiles(&self) -> Vec<Profile> {
+        Client::VARIANTS
+            .iter()
+            .map(|c| Profile::Client(*c))
+            .chain(self.profiles.keys().map(|k| Profile::Named(k.to_owned())))
+            .collect()
+    }
 }
 
 pub async fn multidownload<C: DownloadClient, F>(
@@ -123,6 +339,7 @@ pub async fn multidownload<C: DownloadClient, F>(
     items: &[Item],
     conf: &ClientConfig,
     client: &reqwest::Client,
+    source_client: &reqwest::Client,
 ) -> DownloadResult
 where
     F: Fn(usize) -> String,
@@ -130,7 +347,12 @@ where
     let mut set = JoinSet::new();
     for item in items.iter() {
         let item = item.to_owned();
-        set.spawn(C::download(item.clone(), conf.clone(), client.clone()));
+        set.spawn(C::download(
+            item.clone(),
+            conf.clone(),
+            client.clone(),
+            source_client.clone(),
+        ));
     }
     let mut results: Vec<DownloadResult> = vec![];
     while let Some(res) = set.join_next().await {
@@ -156,6 +378,113 @@ where
     DownloadResult::new(success_msg(success_ids.len()), success_ids, errors, true)
 }
 
+impl Profile {
+    /// The client behind this profile, if it exists
+    pub fn client(&self, conf: &ClientConfig) -> Option<Client> {
+        match self {
+            Profile::Client(c) => Some(*c),
+            Profile::Named(name) => conf.profiles.get(name).map(|p| p.client),
+        }
+    }
+
+    /// Get the client and the config to use with it, with the profile's options
+    /// in place of the client's own table
+    pub fn resolve(&self, conf: &ClientConfig) -> Result<(Client, ClientConfig), DownloadError> {
+        let mut conf = conf.to_owned();
+        match self {
+            Profile::Client(c) => {
+                c.load_config(&mut conf);
+                Ok((*c, conf))
+            }
+            Profile::Named(name) => {
+                let Some(profile) = conf.profiles.get(name).cloned() else {
+                    return Err(DownloadError(format!(
+                        "No client profile named \"{}\"",
+                        name
+                    )));
+                };
+                profile
+                    .client
+                    .load_profile(&mut conf, &profile.options)
+                    .map_err(|e| {
+                        DownloadError(format!("Invalid client profile \"{}\":\n{}", name, e))
+                    })?;
+                Ok((profile.client, conf))
+            }
+        }
+    }
+
+    pub fn load_config(&self, cfg: &mut ClientConfig) {
+        // Profiles are written by the user, so there are no defaults to add
+        if let Profile::Client(c) = self {
+            c.load_config(cfg);
+        }
+    }
+
+    pub async fn download(
+        &self,
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        match self.resolve(&conf) {
+            Ok((c, conf)) => c.download(item, conf, client, source_client).await,
+            Err(e) => DownloadResult::error(e),
+        }
+    }
+
+    pub async fn batch_download(
+        &self,
+        items: Vec<Item>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        match self.resolve(&conf) {
+            Ok((c, conf)) => c.batch_download(items, conf, client, source_client).await,
+            Err(e) => DownloadResult::error(e),
+        }
+    }
+
+    pub async fn list_torrents(
+        &self,
+        hashes: Vec<String>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+        let (c, conf) = self.resolve(&conf).map_err(|e| e.to_string())?;
+        c.list_torrents(hashes, conf, client).await
+    }
+
+    pub async fn manage(
+        &self,
+        hashes: Vec<String>,
+        action: TorrentAction,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let (c, conf) = self.resolve(&conf).map_err(|e| e.to_string())?;
+        c.manage(hashes, action, conf, client).await
+    }
+}
+
+fn parse_options<T: DeserializeOwned>(options: &toml::Table) -> Result<Option<T>, toml::de::Error> {
+    toml::Value::Table(options.to_owned()).try_into().map(Some)
+}
+
+fn override_options<T: Serialize + DeserializeOwned>(
+    conf: &Option<T>,
+    overrides: &toml::Table,
+) -> Result<Option<T>, toml::de::Error> {
+    let mut options = match conf {
+        Some(conf) => toml::Table::try_from(conf).map_err(toml::de::Error::custom)?,
+        None => toml::Table::new(),
+    };
+    options.extend(overrides.to_owned());
+    parse_options(&options)
+}
+
 impl Client {
     // pub async fn download(&self, item: Item, ctx: &mut Context) {
     //     let conf = ctx.config.client.to_owned();
@@ -180,14 +509,24 @@ impl Client {
         item: Item,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         match self {
-            Self::Cmd => CmdClient::download(item, conf, client).await,
-            Self::Qbit => QbitClient::download(item, conf, client).await,
-            Self::Transmission => TransmissionClient::download(item, conf, client).await,
-            Self::Rqbit => RqbitClient::download(item, conf, client).await,
-            Self::DefaultApp => DefaultAppClient::download(item, conf, client).await,
-            Self::Download => DownloadFileClient::download(item, conf, client).await,
+            Self::Cmd => CmdClient::download(item, conf, client, source_client).await,
+            Self::Qbit => QbitClient::download(item, conf, client, source_client).await,
+            Self::Transmission => {
+                TransmissionClient::download(item, conf, client, source_client).await
+            }
+            Self::Rqbit => RqbitClient::download(item, conf, client, source_client).await,
+            Self::DefaultApp => DefaultAppClient::download(item, conf, client, source_client).await,
+            Self::Download => DownloadFileClient::download(item, conf, client, source_client).await,
+            Self::Deluge => DelugeClient::download(item, conf, client, source_client).await,
+            Self::Aria2 => Aria2Client::download(item, conf, client, source_client).await,
+            Self::Rtorrent => RtorrentClient::download(item, conf, client, source_client).await,
+            Self::Webhook => WebhookClient::download(item, conf, client, source_client).await,
+            Self::WatchFolder => {
+                WatchFolderClient::download(item, conf, client, source_client).await
+            }
         }
     }
 
@@ -196,14 +535,34 @@ impl Client {
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         match self {
-            Client::Cmd => CmdClient::batch_download(items, conf, client).await,
-            Client::DefaultApp => DefaultAppClient::batch_download(items, conf, client).await,
-            Client::Download => DownloadFileClient::batch_download(items, conf, client).await,
-            Client::Rqbit => RqbitClient::batch_download(items, conf, client).await,
-            Client::Qbit => QbitClient::batch_download(items, conf, client).await,
-            Client::Transmission => TransmissionClient::batch_download(items, conf, client).await,
+            Client::Cmd => CmdClient::batch_download(items, conf, client, source_client).await,
+            Client::DefaultApp => {
+                DefaultAppClient::batch_download(items, conf, client, source_client).await
+            }
+            Client::Download => {
+                DownloadFileClient::batch_download(items, conf, client, source_client).await
+            }
+            Client::Rqbit => RqbitClient::batch_download(items, conf, client, source_client).await,
+            Client::Qbit => QbitClient::batch_download(items, conf, client, source_client).await,
+            Client::Transmission => {
+                TransmissionClient::batch_download(items, conf, client, source_client).await
+            }
+            Client::Deluge => {
+                DelugeClient::batch_download(items, conf, client, source_client).await
+            }
+            Client::Aria2 => Aria2Client::batch_download(items, conf, client, source_client).await,
+            Client::Rtorrent => {
+                RtorrentClient::batch_download(items, conf, client, source_client).await
+            }
+            Client::Webhook => {
+                WebhookClient::batch_download(items, conf, client, source_client).await
+            }
+            Client::WatchFolder => {
+                WatchFolderClient::batch_download(items, conf, client, source_client).await
+            }
         }
         // let conf = ctx.config.client.to_owned();
         // let timeout = ctx.config.timeout;
@@ -256,6 +615,49 @@ impl Client {
         // ctx.batch.retain(|i| !success_ids.contains(&i.id)); // Remove successes from batch
     }
 
+    pub async fn list_torrents(
+        self,
+        hashes: Vec<String>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+        match self {
+            Self::Cmd => CmdClient::list_torrents(hashes, conf, client).await,
+            Self::Qbit => QbitClient::list_torrents(hashes, conf, client).await,
+            Self::Transmission => TransmissionClient::list_torrents(hashes, conf, client).await,
+            Self::Rqbit => RqbitClient::list_torrents(hashes, conf, client).await,
+            Self::DefaultApp => DefaultAppClient::list_torrents(hashes, conf, client).await,
+            Self::Download => DownloadFileClient::list_torrents(hashes, conf, client).await,
+            Self::Deluge => DelugeClient::list_torrents(hashes, conf, client).await,
+            Self::Aria2 => Aria2Client::list_torrents(hashes, conf, client).await,
+            Self::Rtorrent => RtorrentClient::list_torrents(hashes, conf, client).await,
+            Self::Webhook => WebhookClient::list_torrents(hashes, conf, client).await,
+            Self::WatchFolder => WatchFolderClient::list_torrents(hashes, conf, client).await,
+        }
+    }
+
+    pub async fn manage(
+        self,
+        hashes: Vec<String>,
+        action: TorrentAction,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self {
+            Self::Cmd => CmdClient::manage(hashes, action, conf, client).await,
+            Self::Qbit => QbitClient::manage(hashes, action, conf, client).await,
+            Self::Transmission => TransmissionClient::manage(hashes, action, conf, client).await,
+            Self::Rqbit => RqbitClient::manage(hashes, action, conf, client).await,
+            Self::DefaultApp => DefaultAppClient::manage(hashes, action, conf, client).await,
+            Self::Download => DownloadFileClient::manage(hashes, action, conf, client).await,
+            Self::Deluge => DelugeClient::manage(hashes, action, conf, client).await,
+            Self::Aria2 => Aria2Client::manage(hashes, action, conf, client).await,
+            Self::Rtorrent => RtorrentClient::manage(hashes, action, conf, client).await,
+            Self::Webhook => WebhookClient::manage(hashes, action, conf, client).await,
+            Self::WatchFolder => WatchFolderClient::manage(hashes, action, conf, client).await,
+        }
+    }
+
     pub fn load_config(self, cfg: &mut ClientConfig) {
         match self {
             Self::Cmd => cmd::load_config(cfg),
@@ -264,6 +666,56 @@ impl Client {
             Self::Rqbit => rqbit::load_config(cfg),
             Self::DefaultApp => default_app::load_config(cfg),
             Self::Download => download::load_config(cfg),
+            Self::Deluge => deluge::load_config(cfg),
+            Self::Aria2 => aria2::load_config(cfg),
+            Self::Rtorrent => rtorrent::load_config(cfg),
+            Self::Webhook => webhook::load_config(cfg),
+            Self::WatchFolder => watch_folder::load_config(cfg),
+        };
+    }
+
+    pub fn load_profile(
+        self,
+        cfg: &mut ClientConfig,
+        options: &toml::Table,
+    ) -> Result<(), toml::de::Error> {
+        match self {
+            Self::Cmd => cfg.cmd = parse_options(options)?,
+            Self::Qbit => cfg.qbit = parse_options(options)?,
+            Self::Transmission => cfg.transmission = parse_options(options)?,
+            Self::Rqbit => cfg.rqbit = parse_options(options)?,
+            Self::DefaultApp => cfg.default_app = parse_options(options)?,
+            Self::Download => cfg.download = parse_options(options)?,
+            Self::Deluge => cfg.deluge = parse_options(options)?,
+            Self::Aria2 => cfg.aria2 = parse_options(options)?,
+            Self::Rtorrent => cfg.rtorrent = parse_options(options)?,
+            Self::Webhook => cfg.webhook = parse_options(options)?,
+            Self::WatchFolder => cfg.watch_folder = parse_options(options)?,
+        };
+        Ok(())
+    }
+
+    /// Replace some of the options in the client's config
+    pub fn apply_overrides(
+        self,
+        cfg: &mut ClientConfig,
+        overrides: &toml::Table,
+    ) -> Result<(), toml::de::Error> {
+        match self {
+            Self::Cmd => cfg.cmd = override_options(&cfg.cmd, overrides)?,
+            Self::Qbit => cfg.qbit = override_options(&cfg.qbit, overrides)?,
+            Self::Transmission => {
+                cfg.transmission = override_options(&cfg.transmission, overrides)?
+            }
+            Self::Rqbit => cfg.rqbit = override_options(&cfg.rqbit, overrides)?,
+            Self::DefaultApp => cfg.default_app = override_options(&cfg.default_app, overrides)?,
+            Self::Download => cfg.download = override_options(&cfg.download, overrides)?,
+            Self::Deluge => cfg.deluge = override_options(&cfg.deluge, overrides)?,
+            Self::Aria2 => cfg.aria2 = override_options(&cfg.aria2, overrides)?,
+            Self::Rtorrent => cfg.rtorrent = override_options(&cfg.rtorrent, overrides)?,
+            Self::Webhook => cfg.webhook = override_options(&cfg.webhook, overrides)?,
+            Self::WatchFolder => cfg.watch_folder = override_options(&cfg.watch_folder, overrides)?,
         };
+        Ok(())
     }
 }
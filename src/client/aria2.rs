@@ -0,0 +1,178 @@
+use std::{error::Error, fs};
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{source::Item, util::conv::add_protocol};
+
+use super::{ClientConfig, DownloadClient, DownloadError, DownloadResult};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Aria2Config {
+    pub base_url: String,
+    pub secret: Option<String>,
+    pub secret_file: Option<String>,
+    pub use_magnet: Option<bool>,
+    pub dir: Option<String>,
+    pub max_connection_per_server: Option<u32>,
+    pub seed_ratio: Option<f32>,
+}
+
+pub struct Aria2Client;
+
+impl Default for Aria2Config {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:6800/jsonrpc".to_owned(),
+            secret: None,
+            secret_file: None,
+            use_magnet: None,
+            dir: None,
+            max_connection_per_server: None,
+            seed_ratio: None,
+        }
+    }
+}
+
+impl Aria2Config {
+    // aria2 expects every option value as a string
+    fn to_options(&self) -> Value {
+        let mut options = json!({});
+        if let Some(dir) = self.dir.to_owned() {
+            options["dir"] = json!(dir);
+        }
+        if let Some(max) = self.max_connection_per_server {
+            options["max-connection-per-server"] = json!(max.to_string());
+        }
+        if let Some(ratio) = self.seed_ratio {
+            options["seed-ratio"] = json!(ratio.to_string());
+        }
+        options
+    }
+
+    fn token(&self) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.secret.as_ref() {
+            Some(secret) => Some(secret.to_owned()),
+            None => match self.secret_file.as_ref() {
+                Some(file) => {
+                    let contents = fs::read_to_string(file)?;
+                    let expand = shellexpand::full(contents.trim())?;
+                    Some(expand.to_string())
+                }
+                None => None,
+            },
+        }
+        .map(|s| format!("token:{}", s)))
+    }
+}
+
+/// Send every item in a single `system.multicall`, returning the result of
+/// each call in order
+async fn add_torrents(
+    conf: &Aria2Config,
+    items: &[Item],
+    client: &reqwest::Client,
+) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+    let url = add_protocol(conf.base_url.clone(), false)?;
+    let token = conf.token()?;
+    let options = conf.to_options();
+    let calls = items
+        .iter()
+        .map(|item| {
+            let link = match conf.use_magnet.unwrap_or(true) {
+                true => item.magnet_link.to_owned(),
+                false => item.torrent_link.to_owned(),
+            };
+            let mut params = vec![json!([link]), options.clone()];
+            if let Some(token) = token.to_owned() {
+                params.insert(0, json!(token));
+            }
+            json!({
+                "methodName": "aria2.addUri",
+                "params": params,
+            })
+        })
+        .collect::<Vec<Value>>();
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": "nyaa",
+        "method": "system.multicall",
+        "params": [calls],
+    });
+
+    let res = client.post(url).json(&body).send().await?;
+    let status = res.status();
+    let res: Value = res.json().await?;
+    if let Some(e) = res.get("error").filter(|e| !e.is_null()) {
+        let msg = e.get("message").and_then(Value::as_str).unwrap_or("???");
+        return Err(msg.into());
+    }
+    if status != StatusCode::OK {
+        return Err(format!("aria2 returned status code {}", status.as_u16()).into());
+    }
+    match res.get("result").and_then(Value::as_array) {
+        Some(results) => Ok(results.to_owned()),
+        None => Err("Invalid response from aria2".into()),
+    }
+}
+
+pub fn load_config(cfg: &mut ClientConfig) {
+    if cfg.aria2.is_none() {
+        cfg.aria2 = Some(Aria2Config::default());
+    }
+}
+
+impl DownloadClient for Aria2Client {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let mut res = Self::batch_download(vec![item], conf, client, source_client).await;
+        res.success_msg = Some("Successfully sent torrent to aria2".to_owned());
+        res.batch = false;
+        res
+    }
+
+    async fn batch_download(
+        items: Vec<Item>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let Some(aria2) = conf.aria2.to_owned() else {
+            return DownloadResult::error(DownloadError("Failed to get aria2 config".to_owned()));
+        };
+        let results = match add_torrents(&aria2, &items, &client).await {
+            Ok(results) => results,
+            Err(e) => {
+                return DownloadResult::error(DownloadError(format!(
+                    "Failed to send torrents to aria2:\n{}",
+                    e
+                )))
+            }
+        };
+
+        // Each call either returns `[gid]` or a fault struct
+        let mut success_ids = vec![];
+        let mut errors = vec![];
+        for (item, res) in items.into_iter().zip(results.iter()) {
+            match res.get("message").and_then(Value::as_str) {
+                Some(msg) => errors.push(DownloadError(format!(
+                    "Failed to add \"{}\":\n{}",
+                    item.title, msg
+                ))),
+                None => success_ids.push(item.id),
+            }
+        }
+        DownloadResult::new(
+            format!("Successfully sent {} torrents to aria2", success_ids.len()),
+            success_ids,
+            errors,
+            true,
+        )
+    }
+}
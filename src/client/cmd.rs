@@ -33,7 +33,12 @@ pub fn load_config(cfg: &mut ClientConfig) {
 }
 
 impl DownloadClient for CmdClient {
-    async fn download(item: Item, conf: ClientConfig, _: reqwest::Client) -> DownloadResult {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        _: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
         let cmd = match conf.cmd.to_owned() {
             Some(c) => c,
             None => {
@@ -64,12 +69,14 @@ impl DownloadClient for CmdClient {
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         multidownload::<CmdClient, _>(
             |s| format!("Successfully ran command on {} torrents", s),
             &items,
             &conf,
             &client,
+            &source_client,
         )
         .await
     }
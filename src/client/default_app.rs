@@ -20,7 +20,12 @@ pub fn load_config(cfg: &mut ClientConfig) {
 }
 
 impl DownloadClient for DefaultAppClient {
-    async fn download(item: Item, conf: ClientConfig, _: reqwest::Client) -> DownloadResult {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        _: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
         let conf = match conf.default_app.to_owned() {
             Some(c) => c,
             None => {
@@ -50,12 +55,14 @@ impl DownloadClient for DefaultAppClient {
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         multidownload::<DefaultAppClient, _>(
             |s| format!("Successfully opened {} links in default app", s),
             &items,
             &conf,
             &client,
+            &source_client,
         )
         .await
     }
@@ -0,0 +1,235 @@
+use std::{error::Error, fs};
+
+use reqwest::{header::COOKIE, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{source::Item, util::conv::add_protocol};
+
+use super::{ClientConfig, DownloadClient, DownloadError, DownloadResult};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DelugeConfig {
+    pub base_url: String,
+    pub password: Option<String>,
+    pub password_file: Option<String>,
+    pub use_magnet: Option<bool>,
+    pub download_location: Option<String>,
+    pub label: Option<String>,
+    pub paused: Option<bool>,
+    pub move_completed_path: Option<String>,
+}
+
+pub struct DelugeClient;
+
+impl Default for DelugeConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:8112".to_owned(),
+            password: None,
+            password_file: None,
+            use_magnet: None,
+            download_location: None,
+            label: None,
+            paused: None,
+            move_completed_path: None,
+        }
+    }
+}
+
+impl DelugeConfig {
+    fn to_options(&self) -> Value {
+        let mut options = json!({});
+        if let Some(location) = self.download_location.to_owned() {
+            options["download_location"] = json!(location);
+        }
+        if let Some(paused) = self.paused {
+            options["add_paused"] = json!(paused);
+        }
+        if let Some(path) = self.move_completed_path.to_owned() {
+            options["move_completed"] = json!(true);
+            options["move_completed_path"] = json!(path);
+        }
+        options
+    }
+}
+
+/// A logged in connection to the Deluge WebUI
+struct DelugeSession<'a> {
+    url: Url,
+    client: &'a reqwest::Client,
+    cookie: Option<String>,
+    id: usize,
+}
+
+impl DelugeSession<'_> {
+    async fn call(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self.id += 1;
+        let body = json!({
+            "method": method,
+            "params": params,
+            "id": self.id,
+        });
+        let mut request = self.client.post(self.url.clone()).json(&body);
+        if let Some(cookie) = self.cookie.as_ref() {
+            request = request.header(COOKIE, cookie);
+        }
+        let res = request.send().await?;
+        if res.status() != StatusCode::OK {
+            return Err(format!("Deluge returned status code {}", res.status().as_u16()).into());
+        }
+        // The session is only returned on login, but may be refreshed at any time
+        if let Some(session) = res
+            .cookies()
+            .find(|c| c.name() == "_session_id")
+            .map(|c| format!("_session_id={}", c.value()))
+        {
+            self.cookie = Some(session);
+        }
+
+        let res: Value = res.json().await?;
+        match res.get("error").filter(|e| !e.is_null()) {
+            Some(e) => Err(format!(
+                "{} failed:\n{}",
+                method,
+                e.get("message").and_then(Value::as_str).unwrap_or("???")
+            )
+            .into()),
+            None => Ok(res.get("result").cloned().unwrap_or_default()),
+        }
+    }
+}
+
+async fn login<'a>(
+    conf: &DelugeConfig,
+    client: &'a reqwest::Client,
+) -> Result<DelugeSession<'a>, Box<dyn Error + Send + Sync>> {
+    let pass = match conf.password.as_ref() {
+        Some(pass) => pass.to_owned(),
+        None => match conf.password_file.as_ref() {
+            Some(file) => {
+                let contents = fs::read_to_string(file)?;
+                let expand = shellexpand::full(contents.trim())?;
+                expand.to_string()
+            }
+            // Default password for the Deluge WebUI
+            None => "deluge".to_owned(),
+        },
+    };
+    let base_url = add_protocol(conf.base_url.clone(), false)?;
+    let mut session = DelugeSession {
+        url: base_url.join("json")?,
+        client,
+        cookie: None,
+        id: 0,
+    };
+    if session.call("auth.login", json!([pass])).await? != json!(true) {
+        return Err("Incorrect password".into());
+    }
+
+    // The WebUI may not be connected to a daemon yet, so pick the first one
+    if session.call("web.connected", json!([])).await? != json!(true) {
+        let hosts = session.call("web.get_hosts", json!([])).await?;
+        let Some(host) = hosts.get(0).and_then(|h| h.get(0)).cloned() else {
+            return Err("Deluge WebUI has no daemons to connect to".into());
+        };
+        session.call("web.connect", json!([host])).await?;
+    }
+    Ok(session)
+}
+
+async fn add_torrent(
+    conf: &DelugeConfig,
+    session: &mut DelugeSession<'_>,
+    item: &Item,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let options = conf.to_options();
+    let id = match conf.use_magnet.unwrap_or(true) {
+        true => {
+            session
+                .call(
+                    "core.add_torrent_magnet",
+                    json!([item.magnet_link, options]),
+                )
+                .await?
+        }
+        false => {
+            session
+                .call("core.add_torrent_url", json!([item.torrent_link, options]))
+                .await?
+        }
+    };
+
+    if let (Some(label), Some(id)) = (conf.label.as_ref(), id.as_str()) {
+        // Labels must exist before they can be used, and adding an existing one fails
+        let _ = session.call("label.add", json!([label])).await;
+        session
+            .call("label.set_torrent", json!([id, label]))
+            .await
+            .map_err(|e| format!("Failed to set label, is the Label plugin enabled?\n{}", e))?;
+    }
+    Ok(())
+}
+
+pub fn load_config(cfg: &mut ClientConfig) {
+    if cfg.deluge.is_none() {
+        cfg.deluge = Some(DelugeConfig::default());
+    }
+}
+
+impl DownloadClient for DelugeClient {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let mut res = Self::batch_download(vec![item], conf, client, source_client).await;
+        res.success_msg = Some("Successfully sent torrent to Deluge".to_owned());
+        res.batch = false;
+        res
+    }
+
+    async fn batch_download(
+        items: Vec<Item>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let Some(deluge) = conf.deluge.to_owned() else {
+            return DownloadResult::error(DownloadError("Failed to get Deluge config".to_owned()));
+        };
+        let mut session = match login(&deluge, &client).await {
+            Ok(session) => session,
+            Err(e) => {
+                return DownloadResult::error(DownloadError(format!(
+                    "Failed to login to Deluge:\n{}",
+                    e
+                )))
+            }
+        };
+
+        let mut success_ids = vec![];
+        let mut errors = vec![];
+        for item in items.into_iter() {
+            match add_torrent(&deluge, &mut session, &item).await {
+                Ok(()) => success_ids.push(item.id),
+                Err(e) => errors.push(DownloadError(format!(
+                    "Failed to add \"{}\":\n{}",
+                    item.title, e
+                ))),
+            }
+        }
+        DownloadResult::new(
+            format!("Successfully sent {} torrents to Deluge", success_ids.len()),
+            success_ids,
+            errors,
+            true,
+        )
+    }
+}
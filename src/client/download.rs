@@ -74,7 +74,12 @@ async fn download_torrent(
 }
 
 impl DownloadClient for DownloadFileClient {
-    async fn download(item: Item, conf: ClientConfig, client: reqwest::Client) -> DownloadResult {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        _: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
         let conf = match conf.download.to_owned() {
             Some(c) => c,
             None => {
@@ -108,7 +113,7 @@ impl DownloadClient for DownloadFileClient {
             conf.save_dir.clone(),
             conf.create_root_folder,
             conf.overwrite,
-            client,
+            source_client,
         )
         .await
         {
@@ -137,6 +142,7 @@ impl DownloadClient for DownloadFileClient {
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         let save_dir = conf.download.clone().unwrap_or_default().save_dir.clone();
         multidownload::<DownloadFileClient, _>(
@@ -144,6 +150,7 @@ impl DownloadClient for DownloadFileClient {
             &items,
             &conf,
             &client,
+            &source_client,
         )
         .await
     }
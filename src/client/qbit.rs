@@ -1,11 +1,18 @@
 use std::{collections::HashMap, error::Error, fs};
 
-use reqwest::{Response, StatusCode};
+use reqwest::{
+    multipart::{Form, Part},
+    Response, StatusCode,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{source::Item, util::conv::add_protocol};
 
-use super::{ClientConfig, DownloadClient, DownloadError, DownloadResult};
+use super::{
+    ClientConfig, DownloadClient, DownloadError, DownloadResult, TorrentAction, TorrentState,
+    TorrentStatus,
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
@@ -160,6 +167,170 @@ async fn add_torrent(
     Ok(client.post(url).form(&qbit.to_form(links)).send().await?)
 }
 
+/// Download a .torrent file here, since the qBittorrent server may not be able
+/// to reach the source itself
+async fn fetch_torrent(
+    item: &Item,
+    client: &reqwest::Client,
+) -> Result<(String, Vec<u8>), Box<dyn Error + Send + Sync>> {
+    let res = client.get(item.torrent_link.to_owned()).send().await?;
+    if res.status() != StatusCode::OK {
+        return Err(format!(
+            "{}\nInvalid response code: {}",
+            item.torrent_link,
+            res.status().as_u16()
+        )
+        .into());
+    }
+    let name = match item.file_name.ends_with(".torrent") {
+        true => item.file_name.to_owned(),
+        false => format!("{}.torrent", item.id),
+    };
+    Ok((name, res.bytes().await?.to_vec()))
+}
+
+async fn upload_torrents(
+    qbit: &QbitConfig,
+    files: Vec<(String, Vec<u8>)>,
+    client: &reqwest::Client,
+) -> Result<Response, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(qbit.base_url.clone(), false)?;
+    let url = base_url.join("/api/v2/torrents/add")?;
+
+    // Send the same options as when adding by URL, without the URLs
+    let mut form = Form::new();
+    if let Value::Object(fields) = serde_json::to_value(qbit.to_form(String::new()))? {
+        for (k, v) in fields.into_iter().filter(|(k, _)| k != "urls") {
+            form = match v {
+                Value::Null => form,
+                Value::String(s) => form.text(k, s),
+                v => form.text(k, v.to_string()),
+            };
+        }
+    }
+    for (name, content) in files.into_iter() {
+        let part = Part::bytes(content)
+            .file_name(name)
+            .mime_str("application/x-bittorrent")?;
+        form = form.part("torrents", part);
+    }
+    Ok(client.post(url).multipart(form).send().await?)
+}
+
+#[derive(Deserialize)]
+struct QbitTorrent {
+    hash: String,
+    name: String,
+    progress: f64,
+    size: i64,
+    dlspeed: i64,
+    upspeed: i64,
+    eta: i64,
+    state: String,
+}
+
+impl From<QbitTorrent> for TorrentStatus {
+    fn from(t: QbitTorrent) -> Self {
+        let state = match t.state.as_str() {
+            "downloading" | "forcedDL" => TorrentState::Downloading,
+            "uploading" | "stalledUP" | "forcedUP" => TorrentState::Seeding,
+            "pausedDL" | "pausedUP" | "stoppedDL" | "stoppedUP" => TorrentState::Paused,
+            "stalledDL" => TorrentState::Stalled,
+            "queuedDL" | "queuedUP" => TorrentState::Queued,
+            "checkingDL" | "checkingUP" | "checkingResumeData" | "moving" => TorrentState::Checking,
+            "metaDL" | "forcedMetaDL" => TorrentState::Metadata,
+            "error" | "missingFiles" => TorrentState::Error,
+            _ => TorrentState::Unknown,
+        };
+        TorrentStatus {
+            hash: t.hash.to_lowercase(),
+            name: t.name,
+            progress: t.progress,
+            size: t.size.max(0) as u64,
+            download_speed: t.dlspeed.max(0) as u64,
+            upload_speed: t.upspeed.max(0) as u64,
+            // qBittorrent uses 8640000 (100 days) for an infinite ETA
+            eta: Some(t.eta)
+                .filter(|e| (0..8640000).contains(e))
+                .map(|e| e as u64),
+            state,
+        }
+    }
+}
+
+async fn get_torrents(
+    qbit: &QbitConfig,
+    hashes: &[String],
+    client: &reqwest::Client,
+) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(qbit.base_url.clone(), false)?;
+    let mut url = base_url.join("/api/v2/torrents/info")?;
+    url.set_query(Some(&format!("hashes={}", hashes.join("|"))));
+    let res = client.get(url).send().await?;
+    if res.status() != StatusCode::OK {
+        return Err(format!("qBittorrent returned status code {}", res.status().as_u16()).into());
+    }
+    let torrents: Vec<QbitTorrent> = res.json().await?;
+    Ok(torrents.into_iter().map(TorrentStatus::from).collect())
+}
+
+async fn post_hashes(
+    qbit: &QbitConfig,
+    path: &str,
+    hashes: &[String],
+    params: &[(&str, &str)],
+    client: &reqwest::Client,
+) -> Result<StatusCode, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(qbit.base_url.clone(), false)?;
+    let url = base_url.join(&format!("/api/v2/torrents/{}", path))?;
+    let hashes = hashes.join("|");
+    let mut form = vec![("hashes", hashes.as_str())];
+    form.extend_from_slice(params);
+    Ok(client.post(url).form(&form).send().await?.status())
+}
+
+async fn apply_action(
+    qbit: &QbitConfig,
+    hashes: &[String],
+    action: &TorrentAction,
+    client: &reqwest::Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let status = match action {
+        // qBittorrent 5 renamed pause and resume to stop and start
+        TorrentAction::Pause => match post_hashes(qbit, "pause", hashes, &[], client).await? {
+            StatusCode::NOT_FOUND => post_hashes(qbit, "stop", hashes, &[], client).await?,
+            status => status,
+        },
+        TorrentAction::Resume => match post_hashes(qbit, "resume", hashes, &[], client).await? {
+            StatusCode::NOT_FOUND => post_hashes(qbit, "start", hashes, &[], client).await?,
+            status => status,
+        },
+        TorrentAction::Remove => {
+            post_hashes(qbit, "delete", hashes, &[("deleteFiles", "false")], client).await?
+        }
+        TorrentAction::RemoveData => {
+            post_hashes(qbit, "delete", hashes, &[("deleteFiles", "true")], client).await?
+        }
+        TorrentAction::Recheck => post_hashes(qbit, "recheck", hashes, &[], client).await?,
+        TorrentAction::SetCategory(category) => {
+            // Categories must exist before they can be used, and adding an existing one fails
+            let base_url = add_protocol(qbit.base_url.clone(), false)?;
+            let url = base_url.join("/api/v2/torrents/createCategory")?;
+            let _ = client
+                .post(url)
+                .form(&[("category", category)])
+                .send()
+                .await;
+            let params = [("category", category.as_str())];
+            post_hashes(qbit, "setCategory", hashes, &params, client).await?
+        }
+    };
+    if status != StatusCode::OK {
+        return Err(format!("qBittorrent returned status code {}", status.as_u16()).into());
+    }
+    Ok(())
+}
+
 pub fn load_config(cfg: &mut ClientConfig) {
     if cfg.qbit.is_none() {
         cfg.qbit = Some(QbitConfig::default());
@@ -167,17 +338,23 @@ pub fn load_config(cfg: &mut ClientConfig) {
 }
 
 impl DownloadClient for QbitClient {
-    async fn download(item: Item, conf: ClientConfig, client: reqwest::Client) -> DownloadResult {
-        let mut res = Self::batch_download(vec![item], conf, client).await;
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let mut res = Self::batch_download(vec![item], conf, client, source_client).await;
         res.success_msg = Some("Successfully sent torrent to qBittorrent".to_string());
         res.batch = false;
         res
     }
 
     async fn batch_download(
-        items: Vec<Item>,
+        mut items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         // return DownloadResult::error(DownloadError("Failed to login :\\"));
         let Some(qbit) = conf.qbit.to_owned() else {
@@ -196,41 +373,102 @@ impl DownloadClient for QbitClient {
         if let Err(e) = login(&qbit, &client).await {
             return DownloadResult::error(DownloadError(format!("Failed to get SID:\n{}", e)));
         }
-        let links = match qbit.use_magnet.unwrap_or(true) {
-            true => items
-                .iter()
-                .map(|i| i.magnet_link.to_owned())
-                .collect::<Vec<String>>()
-                .join("\n"),
-            false => items
-                .iter()
-                .map(|i| i.torrent_link.to_owned())
-                .collect::<Vec<String>>()
-                .join("\n"),
-        };
-        let res = match add_torrent(&qbit, links, &client).await {
-            Ok(res) => res,
-            Err(e) => {
-                return DownloadResult::error(DownloadError(format!(
-                    "Failed to get response:\n{}",
-                    e
-                )))
+        let mut errors = vec![];
+        let res = match qbit.use_magnet.unwrap_or(true) {
+            true => {
+                let links = items
+                    .iter()
+                    .map(|i| i.magnet_link.to_owned())
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                add_torrent(&qbit, links, &client).await
+            }
+            false => {
+                let mut files = vec![];
+                let mut fetched = vec![];
+                for item in items.into_iter() {
+                    match fetch_torrent(&item, &source_client).await {
+                        Ok(file) => {
+                            files.push(file);
+                            fetched.push(item);
+                        }
+                        Err(e) => errors.push(DownloadError(format!(
+                            "Failed to download \"{}\":\n{}",
+                            item.title, e
+                        ))),
+                    }
+                }
+                items = fetched;
+                if files.is_empty() {
+                    let _ = logout(&qbit, &client).await;
+                    return DownloadResult::new("".to_owned(), vec![], errors, true);
+                }
+                upload_torrents(&qbit, files, &client).await
             }
         };
-        if res.status() != StatusCode::OK {
-            return DownloadResult::error(DownloadError(format!(
+        let sent = match res {
+            Err(e) => Err(format!("Failed to get response:\n{}", e)),
+            Ok(res) if res.status() != StatusCode::OK => Err(format!(
                 "qBittorrent returned status code {}",
                 res.status().as_u16()
-            )));
-        }
+            )),
+            // qBittorrent responds with "Fails." if none of the torrents were valid
+            Ok(res) => match res.text().await.is_ok_and(|t| t.trim() == "Fails.") {
+                true => Err("qBittorrent failed to add the torrents".to_owned()),
+                false => Ok(()),
+            },
+        };
 
         let _ = logout(&qbit, &client).await;
 
+        // Keep the errors of the torrents that couldn't be fetched
+        if let Err(e) = sent {
+            errors.push(DownloadError(e));
+            return DownloadResult::new(None, vec![], errors, true);
+        }
+
         DownloadResult::new(
             format!("Successfully sent {} torrents to qBittorrent", items.len()),
             items.into_iter().map(|i| i.id).collect(),
-            vec![],
+            errors,
             true,
         )
     }
+
+    async fn list_torrents(
+        hashes: Vec<String>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+        let Some(qbit) = conf.qbit.to_owned() else {
+            return Err("Failed to get qBittorrent config".into());
+        };
+        // An empty filter would return every torrent
+        if hashes.is_empty() {
+            return Ok(vec![]);
+        }
+        login(&qbit, &client)
+            .await
+            .map_err(|e| format!("Failed to get SID:\n{}", e))?;
+        let res = get_torrents(&qbit, &hashes, &client).await;
+        let _ = logout(&qbit, &client).await;
+        res
+    }
+
+    async fn manage(
+        hashes: Vec<String>,
+        action: TorrentAction,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(qbit) = conf.qbit.to_owned() else {
+            return Err("Failed to get qBittorrent config".into());
+        };
+        login(&qbit, &client)
+            .await
+            .map_err(|e| format!("Failed to get SID:\n{}", e))?;
+        let res = apply_action(&qbit, &hashes, &action, &client).await;
+        let _ = logout(&qbit, &client).await;
+        res
+    }
 }
@@ -1,12 +1,15 @@
 use std::error::Error;
 
-use reqwest::{Response, StatusCode};
+use reqwest::{Response, StatusCode, Url};
 use serde::{Deserialize, Serialize};
 use urlencoding::encode;
 
 use crate::{source::Item, util::conv::add_protocol};
 
-use super::{multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult};
+use super::{
+    multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult, TorrentAction,
+    TorrentState, TorrentStatus,
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(default)]
@@ -58,6 +61,146 @@ async fn add_torrent(
     }
 }
 
+#[derive(Deserialize)]
+struct RqbitTorrents {
+    torrents: Vec<RqbitTorrent>,
+}
+
+#[derive(Deserialize)]
+struct RqbitTorrent {
+    id: usize,
+    info_hash: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RqbitStats {
+    state: String,
+    progress_bytes: u64,
+    total_bytes: u64,
+    finished: bool,
+    live: Option<RqbitLive>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RqbitLive {
+    download_speed: RqbitSpeed,
+    upload_speed: RqbitSpeed,
+    time_remaining: Option<RqbitRemaining>,
+}
+
+/// Speeds are in MiB/s
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RqbitSpeed {
+    mbps: f64,
+}
+
+#[derive(Deserialize)]
+struct RqbitRemaining {
+    duration: Option<RqbitDuration>,
+}
+
+#[derive(Deserialize)]
+struct RqbitDuration {
+    secs: u64,
+}
+
+fn to_status(torrent: RqbitTorrent, stats: RqbitStats) -> TorrentStatus {
+    let state = match stats.state.as_str() {
+        "initializing" => TorrentState::Checking,
+        "paused" => TorrentState::Paused,
+        "error" => TorrentState::Error,
+        "live" if stats.finished => TorrentState::Seeding,
+        "live" => TorrentState::Downloading,
+        _ => TorrentState::Unknown,
+    };
+    let live = stats.live.unwrap_or_default();
+    let progress = match stats.total_bytes {
+        0 => 0.0,
+        total => stats.progress_bytes as f64 / total as f64,
+    };
+    TorrentStatus {
+        hash: torrent.info_hash.to_lowercase(),
+        name: torrent.name.unwrap_or_default(),
+        progress,
+        size: stats.total_bytes,
+        download_speed: (live.download_speed.mbps * 1024.0 * 1024.0) as u64,
+        upload_speed: (live.upload_speed.mbps * 1024.0 * 1024.0) as u64,
+        eta: live.time_remaining.and_then(|t| t.duration).map(|d| d.secs),
+        state,
+    }
+}
+
+async fn list(
+    base_url: &Url,
+    client: &reqwest::Client,
+) -> Result<Vec<RqbitTorrent>, Box<dyn Error + Send + Sync>> {
+    let res = client.get(base_url.join("/torrents")?).send().await?;
+    if res.status() != StatusCode::OK {
+        return Err(format!("rqbit returned status code {}", res.status().as_u16()).into());
+    }
+    let list: RqbitTorrents = res.json().await?;
+    Ok(list.torrents)
+}
+
+async fn get_torrents(
+    conf: &RqbitConfig,
+    hashes: &[String],
+    client: &reqwest::Client,
+) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(conf.base_url.clone(), false)?;
+
+    // Stats are only available for one torrent at a time
+    let mut torrents = vec![];
+    for torrent in list(&base_url, client)
+        .await?
+        .into_iter()
+        .filter(|t| hashes.contains(&t.info_hash.to_lowercase()))
+    {
+        let url = base_url.join(&format!("/torrents/{}/stats/v1", torrent.id))?;
+        let res = client.get(url).send().await?;
+        if res.status() != StatusCode::OK {
+            return Err(format!("rqbit returned status code {}", res.status().as_u16()).into());
+        }
+        let stats: RqbitStats = res.json().await?;
+        torrents.push(to_status(torrent, stats));
+    }
+    Ok(torrents)
+}
+
+async fn apply_action(
+    conf: &RqbitConfig,
+    hashes: &[String],
+    action: &TorrentAction,
+    client: &reqwest::Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let path = match action {
+        TorrentAction::Pause => "pause",
+        TorrentAction::Resume => "start",
+        TorrentAction::Remove => "forget",
+        TorrentAction::RemoveData => "delete",
+        TorrentAction::Recheck | TorrentAction::SetCategory(_) => {
+            return Err(format!("rqbit can't {} torrents", action).into())
+        }
+    };
+    let base_url = add_protocol(conf.base_url.clone(), false)?;
+    for torrent in list(&base_url, client)
+        .await?
+        .into_iter()
+        .filter(|t| hashes.contains(&t.info_hash.to_lowercase()))
+    {
+        let url = base_url.join(&format!("/torrents/{}/{}", torrent.id, path))?;
+        let res = client.post(url).send().await?;
+        if res.status() != StatusCode::OK {
+            return Err(format!("rqbit returned status code {}", res.status().as_u16()).into());
+        }
+    }
+    Ok(())
+}
+
 pub fn load_config(cfg: &mut ClientConfig) {
     if cfg.rqbit.is_none() {
         cfg.rqbit = Some(RqbitConfig::default());
@@ -65,7 +208,12 @@ pub fn load_config(cfg: &mut ClientConfig) {
 }
 
 impl DownloadClient for RqbitClient {
-    async fn download(item: Item, conf: ClientConfig, client: reqwest::Client) -> DownloadResult {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
         let conf = match conf.rqbit.clone() {
             Some(q) => q,
             None => {
@@ -104,13 +252,40 @@ impl DownloadClient for RqbitClient {
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         multidownload::<RqbitClient, _>(
             |s| format!("Successfully sent {} torrents to rqbit", s),
             &items,
             &conf,
             &client,
+            &source_client,
         )
         .await
     }
+
+    async fn list_torrents(
+        hashes: Vec<String>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+        let Some(conf) = conf.rqbit.clone() else {
+            return Err("Failed to get rqbit config".into());
+        };
+        get_torrents(&conf, &hashes, &client)
+            .await
+            .map_err(|e| format!("Failed to get torrents from rqbit\n{}", e).into())
+    }
+
+    async fn manage(
+        hashes: Vec<String>,
+        action: TorrentAction,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(conf) = conf.rqbit.clone() else {
+            return Err("Failed to get rqbit config".into());
+        };
+        apply_action(&conf, &hashes, &action, &client).await
+    }
 }
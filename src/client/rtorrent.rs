@@ -0,0 +1,196 @@
+use std::{error::Error, fs};
+
+use reqwest::{header::CONTENT_TYPE, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{source::Item, util::conv::add_protocol};
+
+use super::{multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RtorrentConfig {
+    pub base_url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub password_file: Option<String>,
+    pub use_magnet: Option<bool>,
+    pub directory: Option<String>,
+    pub label: Option<String>,
+    pub paused: Option<bool>,
+}
+
+pub struct RtorrentClient;
+
+impl Default for RtorrentConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost/RPC2".to_owned(),
+            username: None,
+            password: None,
+            password_file: None,
+            use_magnet: None,
+            directory: None,
+            label: None,
+            paused: None,
+        }
+    }
+}
+
+impl RtorrentConfig {
+    /// The XML-RPC endpoint, pointing ruTorrent installs at the httprpc plugin
+    fn endpoint(&self) -> Result<Url, Box<dyn Error + Send + Sync>> {
+        let url = add_protocol(self.base_url.clone(), false)?;
+        let path = url.path().trim_end_matches('/');
+        if path.ends_with("/plugins/httprpc") {
+            return Ok(url.join(&format!("{}/action.php", path))?);
+        }
+        Ok(url)
+    }
+
+    /// Commands run on the torrent once it's loaded
+    fn commands(&self) -> Vec<String> {
+        let mut commands = vec![];
+        if let Some(dir) = self.directory.as_ref() {
+            commands.push(format!("d.directory.set=\"{}\"", dir));
+        }
+        // ruTorrent shows custom1 as the label
+        if let Some(label) = self.label.as_ref() {
+            commands.push(format!("d.custom1.set=\"{}\"", label));
+        }
+        commands
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Build an XML-RPC method call where every parameter is a string
+fn method_call(method: &str, params: &[String]) -> String {
+    let params = params
+        .iter()
+        .map(|p| {
+            format!(
+                "<param><value><string>{}</string></value></param>",
+                xml_escape(p)
+            )
+        })
+        .collect::<String>();
+    format!(
+        "<?xml version=\"1.0\"?><methodCall><methodName>{}</methodName><params>{}</params></methodCall>",
+        method, params
+    )
+}
+
+/// Get the message of a `<fault>` response, if any
+fn fault_string(body: &str) -> Option<String> {
+    if !body.contains("<fault>") {
+        return None;
+    }
+    let msg = body
+        .split_once("<name>faultString</name>")
+        .and_then(|(_, rest)| rest.split_once("<string>"))
+        .and_then(|(_, rest)| rest.split_once("</string>"))
+        .map(|(msg, _)| msg.to_owned())
+        .unwrap_or("Unknown fault".to_owned());
+    Some(msg)
+}
+
+async fn add_torrent(
+    conf: &RtorrentConfig,
+    link: String,
+    client: &reqwest::Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let pass = match conf.password.as_ref() {
+        Some(pass) => Some(pass.to_owned()),
+        None => match conf.password_file.as_ref() {
+            Some(file) => {
+                let contents = fs::read_to_string(file)?;
+                let expand = shellexpand::full(contents.trim())?;
+                Some(expand.to_string())
+            }
+            None => None,
+        },
+    };
+    let method = match conf.paused.unwrap_or(false) {
+        true => "load.normal",
+        false => "load.start",
+    };
+    // The first parameter is the target, which is always empty for `load.*`
+    let mut params = vec!["".to_owned(), link];
+    params.extend(conf.commands());
+
+    let mut request = client
+        .post(conf.endpoint()?)
+        .header(CONTENT_TYPE, "text/xml")
+        .body(method_call(method, &params));
+    if let Some(user) = conf.username.as_ref() {
+        request = request.basic_auth(user, pass);
+    }
+    let res = request.send().await?;
+    if res.status() != StatusCode::OK {
+        return Err(format!("rTorrent returned status code {}", res.status().as_u16()).into());
+    }
+    let body = res.text().await?;
+    if let Some(fault) = fault_string(&body) {
+        return Err(fault.into());
+    }
+    Ok(())
+}
+
+pub fn load_config(cfg: &mut ClientConfig) {
+    if cfg.rtorrent.is_none() {
+        cfg.rtorrent = Some(RtorrentConfig::default());
+    }
+}
+
+impl DownloadClient for RtorrentClient {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let Some(conf) = conf.rtorrent.clone() else {
+            return DownloadResult::error(DownloadError(
+                "Failed to get rTorrent config".to_owned(),
+            ));
+        };
+        let link = match conf.use_magnet.unwrap_or(true) {
+            true => item.magnet_link.to_owned(),
+            false => item.torrent_link.to_owned(),
+        };
+        if let Err(e) = add_torrent(&conf, link, &client).await {
+            return DownloadResult::error(DownloadError(format!(
+                "Failed to send torrent to rTorrent:\n{}",
+                e
+            )));
+        }
+        DownloadResult::new(
+            "Successfully sent torrent to rTorrent".to_owned(),
+            vec![item.id],
+            vec![],
+            false,
+        )
+    }
+
+    async fn batch_download(
+        items: Vec<Item>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        multidownload::<RtorrentClient, _>(
+            |s| format!("Successfully sent {} torrents to rTorrent", s),
+            &items,
+            &conf,
+            &client,
+            &source_client,
+        )
+        .await
+    }
+}
@@ -2,13 +2,19 @@ use std::{error::Error, fs};
 
 use serde::{Deserialize, Serialize};
 use transmission_rpc::{
-    types::{BasicAuth, TorrentAddArgs},
+    types::{
+        BasicAuth, ErrorType, Id, Torrent, TorrentAction as TransAction, TorrentAddArgs,
+        TorrentGetField, TorrentSetArgs, TorrentStatus as TransStatus,
+    },
     TransClient,
 };
 
 use crate::{source::Item, util::conv::add_protocol};
 
-use super::{multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult};
+use super::{
+    multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult, TorrentAction,
+    TorrentState, TorrentStatus,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i8)]
@@ -66,11 +72,10 @@ impl TransmissionConfig {
     }
 }
 
-async fn add_torrent(
+fn connect(
     conf: &TransmissionConfig,
-    link: String,
     client: reqwest::Client,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
+) -> Result<TransClient, Box<dyn Error + Send + Sync>> {
     let base_url = add_protocol(conf.base_url.clone(), false)?;
     let mut client = TransClient::new_with_client(base_url, client);
 
@@ -91,6 +96,15 @@ async fn add_torrent(
             password: password.clone(),
         });
     }
+    Ok(client)
+}
+
+async fn add_torrent(
+    conf: &TransmissionConfig,
+    link: String,
+    client: reqwest::Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut client = connect(conf, client)?;
     let add = conf.clone().to_form(link);
     client
         .torrent_add(add)
@@ -99,6 +113,31 @@ async fn add_torrent(
     Ok(())
 }
 
+fn to_status(t: Torrent) -> TorrentStatus {
+    let state = match (t.error, t.status) {
+        (Some(ErrorType::TrackerError | ErrorType::LocalError), _) => TorrentState::Error,
+        (_, Some(TransStatus::Stopped)) => TorrentState::Paused,
+        (_, Some(TransStatus::QueuedToVerify | TransStatus::Verifying)) => TorrentState::Checking,
+        (_, Some(TransStatus::QueuedToDownload | TransStatus::QueuedToSeed)) => {
+            TorrentState::Queued
+        }
+        (_, Some(TransStatus::Downloading)) => TorrentState::Downloading,
+        (_, Some(TransStatus::Seeding)) => TorrentState::Seeding,
+        (_, None) => TorrentState::Unknown,
+    };
+    TorrentStatus {
+        hash: t.hash_string.unwrap_or_default().to_lowercase(),
+        name: t.name.unwrap_or_default(),
+        progress: t.percent_done.unwrap_or_default() as f64,
+        size: t.total_size.unwrap_or_default().max(0) as u64,
+        download_speed: t.rate_download.unwrap_or_default().max(0) as u64,
+        upload_speed: t.rate_upload.unwrap_or_default().max(0) as u64,
+        // Negative values mean the ETA is unknown or unavailable
+        eta: t.eta.filter(|e| *e >= 0).map(|e| e as u64),
+        state,
+    }
+}
+
 pub fn load_config(cfg: &mut ClientConfig) {
     if cfg.transmission.is_none() {
         cfg.transmission = Some(TransmissionConfig::default());
@@ -106,7 +145,12 @@ pub fn load_config(cfg: &mut ClientConfig) {
 }
 
 impl DownloadClient for TransmissionClient {
-    async fn download(item: Item, conf: ClientConfig, client: reqwest::Client) -> DownloadResult {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
         let Some(conf) = conf.transmission.clone() else {
             return DownloadResult::error(DownloadError(
                 "Failed to get configuration for transmission".to_owned(),
@@ -142,13 +186,83 @@ impl DownloadClient for TransmissionClient {
         items: Vec<Item>,
         conf: ClientConfig,
         client: reqwest::Client,
+        source_client: reqwest::Client,
     ) -> DownloadResult {
         multidownload::<TransmissionClient, _>(
             |s| format!("Successfully sent {} torrents to Transmission", s),
             &items,
             &conf,
             &client,
+            &source_client,
         )
         .await
     }
+
+    async fn list_torrents(
+        hashes: Vec<String>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<Vec<TorrentStatus>, Box<dyn Error + Send + Sync>> {
+        let Some(conf) = conf.transmission.clone() else {
+            return Err("Failed to get configuration for transmission".into());
+        };
+        // No ids would return every torrent
+        if hashes.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut client = connect(&conf, client)?;
+        let fields = vec![
+            TorrentGetField::HashString,
+            TorrentGetField::Name,
+            TorrentGetField::PercentDone,
+            TorrentGetField::TotalSize,
+            TorrentGetField::RateDownload,
+            TorrentGetField::RateUpload,
+            TorrentGetField::Eta,
+            TorrentGetField::Status,
+            TorrentGetField::Error,
+        ];
+        let ids = hashes.into_iter().map(Id::Hash).collect();
+        let res = client
+            .torrent_get(Some(fields), Some(ids))
+            .await
+            .map_err(|e| format!("Failed to get torrents:\n{}", e))?;
+        if !res.is_ok() {
+            return Err(format!("Failed to get torrents:\n{}", res.result).into());
+        }
+        Ok(res.arguments.torrents.into_iter().map(to_status).collect())
+    }
+
+    async fn manage(
+        hashes: Vec<String>,
+        action: TorrentAction,
+        conf: ClientConfig,
+        client: reqwest::Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(conf) = conf.transmission.clone() else {
+            return Err("Failed to get configuration for transmission".into());
+        };
+        let mut client = connect(&conf, client)?;
+        let ids: Vec<Id> = hashes.into_iter().map(Id::Hash).collect();
+        let res = match action {
+            TorrentAction::Pause => client.torrent_action(TransAction::Stop, ids).await,
+            TorrentAction::Resume => client.torrent_action(TransAction::Start, ids).await,
+            TorrentAction::Remove => client.torrent_remove(ids, false).await,
+            TorrentAction::RemoveData => client.torrent_remove(ids, true).await,
+            TorrentAction::Recheck => client.torrent_action(TransAction::Verify, ids).await,
+            // Transmission has no categories, so labels are used instead
+            TorrentAction::SetCategory(category) => {
+                let args = TorrentSetArgs {
+                    labels: Some(vec![category]),
+                    ..Default::default()
+                };
+                client.torrent_set(args, Some(ids)).await
+            }
+        }
+        .map_err(|e| e.to_string())?;
+        if !res.is_ok() {
+            return Err(res.result.into());
+        }
+        Ok(())
+    }
 }
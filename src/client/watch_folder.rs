@@ -0,0 +1,186 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    source::{Item, SourceConfig, Sources},
+    util::conv::item_hash,
+};
+
+use super::{multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum WatchFolderFormat {
+    Torrent,
+    Magnet,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WatchFolderConfig {
+    pub watch_dir: String,
+    pub format: WatchFolderFormat,
+    pub categories: BTreeMap<String, String>,
+    pub create_dirs: bool,
+    pub overwrite: bool,
+}
+
+pub struct WatchFolderClient;
+
+impl Default for WatchFolderConfig {
+    fn default() -> Self {
+        Self {
+            watch_dir: "~/watch".to_owned(),
+            format: WatchFolderFormat::Torrent,
+            categories: BTreeMap::new(),
+            create_dirs: true,
+            overwrite: false,
+        }
+    }
+}
+
+impl WatchFolderConfig {
+    /// The folder for an item, using the subfolder of its category if one is set
+    fn folder(&self, item: &Item) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+        let root = PathBuf::from(shellexpand::full(&self.watch_dir)?.to_string());
+        let sub = category_cfg(item).and_then(|cfg| {
+            self.categories
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(&cfg))
+                .map(|(_, sub)| sub.to_owned())
+        });
+        Ok(match sub {
+            Some(sub) => root.join(sub),
+            None => root,
+        })
+    }
+}
+
+/// Find the config name of an item's category. Ids are shared between
+/// sources, so the icon label is used to tell them apart.
+fn category_cfg(item: &Item) -> Option<String> {
+    Sources::BUILTIN
+        .iter()
+        .flat_map(|src| src.info(&SourceConfig::default()).cats)
+        .flat_map(|cat| cat.entries)
+        .find(|ent| ent.id == item.category && ent.icon.label == item.icon.label)
+        .map(|ent| ent.cfg)
+}
+
+/// Write to a hidden temporary file first, so the watching daemon never
+/// picks up a partially written file
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.part", name));
+    fs::write(&tmp, content)?;
+    if let Err(e) = fs::rename(&tmp, path) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+async fn save_item(
+    conf: &WatchFolderConfig,
+    item: &Item,
+    client: &reqwest::Client,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let folder = conf.folder(item)?;
+    let basename = match item.file_name.split_once(".torrent") {
+        Some((name, _)) if !name.is_empty() => name.to_owned(),
+        _ => item_hash(item).unwrap_or(item.id.to_owned()),
+    };
+    // Keep titles and ids from escaping the watch folder
+    let basename = basename.replace(['/', '\\'], "_");
+    let (filename, content) = match conf.format {
+        WatchFolderFormat::Torrent => {
+            let res = client.get(item.torrent_link.to_owned()).send().await?;
+            if res.status() != StatusCode::OK {
+                return Err(format!(
+                    "{}\nInvalid response code: {}",
+                    item.torrent_link,
+                    res.status().as_u16()
+                )
+                .into());
+            }
+            (format!("{}.torrent", basename), res.bytes().await?.to_vec())
+        }
+        WatchFolderFormat::Magnet => (
+            format!("{}.magnet", basename),
+            format!("{}\n", item.magnet_link).into_bytes(),
+        ),
+    };
+
+    let path = folder.join(filename);
+    if !conf.overwrite && path.exists() {
+        return Err(format!(
+            "{} already exists.\nEnable \"overwrite\" to overwrite files",
+            path.to_string_lossy()
+        )
+        .into());
+    }
+    if conf.create_dirs && !folder.exists() {
+        fs::create_dir_all(&folder)?;
+    }
+    write_atomic(&path, &content)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+pub fn load_config(cfg: &mut ClientConfig) {
+    if cfg.watch_folder.is_none() {
+        cfg.watch_folder = Some(WatchFolderConfig::default());
+    }
+}
+
+impl DownloadClient for WatchFolderClient {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        _: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let Some(conf) = conf.watch_folder.clone() else {
+            return DownloadResult::error(DownloadError(
+                "Failed to get watch folder config".to_owned(),
+            ));
+        };
+        match save_item(&conf, &item, &source_client).await {
+            Ok(path) => DownloadResult::new(
+                format!("Saved to \"{}\"", path),
+                vec![item.id],
+                vec![],
+                false,
+            ),
+            Err(e) => DownloadResult::error(DownloadError(format!(
+                "Failed to save \"{}\" to watch folder:\n{}",
+                item.title, e
+            ))),
+        }
+    }
+
+    async fn batch_download(
+        items: Vec<Item>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let watch_dir = conf.watch_folder.clone().unwrap_or_default().watch_dir;
+        multidownload::<WatchFolderClient, _>(
+            |s| format!("Saved {} torrents to watch folder {}", s, watch_dir),
+            &items,
+            &conf,
+            &client,
+            &source_client,
+        )
+        .await
+    }
+}
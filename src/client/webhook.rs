@@ -0,0 +1,186 @@
+use std::{collections::BTreeMap, error::Error};
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use urlencoding::encode;
+
+use crate::{source::Item, util::conv::get_hash};
+
+use super::{multidownload, ClientConfig, DownloadClient, DownloadError, DownloadResult};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    Json,
+    Form,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub method: String,
+    pub headers: BTreeMap<String, String>,
+    pub format: WebhookFormat,
+    pub body: Option<Value>,
+    pub success_codes: Option<Vec<u16>>,
+}
+
+pub struct WebhookClient;
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:5678/webhook/nyaa".to_owned(),
+            method: "POST".to_owned(),
+            headers: BTreeMap::new(),
+            format: WebhookFormat::Json,
+            body: None,
+            success_codes: None,
+        }
+    }
+}
+
+/// Replaces the placeholders in each part of the request with the item's values
+struct Template {
+    subs: Vec<(&'static str, String)>,
+}
+
+impl Template {
+    fn new(item: &Item) -> Self {
+        let hash = item
+            .extra
+            .get("hash")
+            .cloned()
+            .or(get_hash(format!("{}&", item.magnet_link)))
+            .unwrap_or_default()
+            .to_lowercase();
+        Self {
+            subs: vec![
+                ("{magnet}", item.magnet_link.to_owned()),
+                ("{torrent}", item.torrent_link.to_owned()),
+                ("{title}", item.title.to_owned()),
+                ("{hash}", hash),
+                ("{id}", item.id.to_owned()),
+                ("{file}", item.file_name.to_owned()),
+            ],
+        }
+    }
+
+    fn sub(&self, s: &str) -> String {
+        self.subs
+            .iter()
+            .fold(s.to_owned(), |s, (pattern, sub)| s.replace(pattern, sub))
+    }
+
+    /// Values are percent-encoded, so links can be used in the query string
+    fn sub_url(&self, s: &str) -> String {
+        self.subs.iter().fold(s.to_owned(), |s, (pattern, sub)| {
+            s.replace(pattern, &encode(sub))
+        })
+    }
+
+    fn sub_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.sub(s)),
+            Value::Array(a) => Value::Array(a.iter().map(|v| self.sub_value(v)).collect()),
+            Value::Object(o) => Value::Object(
+                o.iter()
+                    .map(|(k, v)| (k.to_owned(), self.sub_value(v)))
+                    .collect(),
+            ),
+            v => v.to_owned(),
+        }
+    }
+}
+
+async fn send_webhook(
+    conf: &WebhookConfig,
+    item: &Item,
+    client: &reqwest::Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let template = Template::new(item);
+    let method = Method::from_bytes(conf.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid method \"{}\"", conf.method))?;
+    let mut request = client.request(method, template.sub_url(&conf.url));
+    for (name, value) in conf.headers.iter() {
+        request = request.header(name, template.sub(value));
+    }
+    if let Some(body) = conf.body.as_ref().map(|b| template.sub_value(b)) {
+        request = match conf.format {
+            WebhookFormat::Json => request.json(&body),
+            WebhookFormat::Form => {
+                let Some(fields) = body.as_object() else {
+                    return Err("Form body must be a table".into());
+                };
+                // Forms can't be nested, so only plain values are sent as-is
+                let form = fields
+                    .iter()
+                    .map(|(k, v)| match v {
+                        Value::String(s) => (k.to_owned(), s.to_owned()),
+                        v => (k.to_owned(), v.to_string()),
+                    })
+                    .collect::<Vec<(String, String)>>();
+                request.form(&form)
+            }
+        };
+    }
+
+    let res = request.send().await?;
+    let status = res.status();
+    let success = match conf.success_codes.as_ref() {
+        Some(codes) => codes.contains(&status.as_u16()),
+        None => status.is_success(),
+    };
+    if !success {
+        return Err(format!("Webhook returned status code {}", status.as_u16()).into());
+    }
+    Ok(())
+}
+
+pub fn load_config(cfg: &mut ClientConfig) {
+    if cfg.webhook.is_none() {
+        cfg.webhook = Some(WebhookConfig::default());
+    }
+}
+
+impl DownloadClient for WebhookClient {
+    async fn download(
+        item: Item,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        _source_client: reqwest::Client,
+    ) -> DownloadResult {
+        let Some(conf) = conf.webhook.clone() else {
+            return DownloadResult::error(DownloadError("Failed to get webhook config".to_owned()));
+        };
+        if let Err(e) = send_webhook(&conf, &item, &client).await {
+            return DownloadResult::error(DownloadError(format!(
+                "Failed to send webhook for \"{}\":\n{}",
+                item.title, e
+            )));
+        }
+        DownloadResult::new(
+            "Successfully sent webhook".to_owned(),
+            vec![item.id],
+            vec![],
+            false,
+        )
+    }
+
+    async fn batch_download(
+        items: Vec<Item>,
+        conf: ClientConfig,
+        client: reqwest::Client,
+        source_client: reqwest::Client,
+    ) -> DownloadResult {
+        multidownload::<WebhookClient, _>(
+            |s| format!("Successfully sent {} webhooks", s),
+            &items,
+            &conf,
+            &client,
+            &source_client,
+        )
+        .await
+    }
+}
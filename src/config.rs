@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     error::Error,
     fs::{self, File, OpenOptions},
     io::{ErrorKind, Read, Write as _},
@@ -8,10 +9,13 @@ use std::{
 
 use crate::{
     app::{Context, Widgets, APP_NAME},
-    client::{Client, ClientConfig},
+    client::{Client, ClientConfig, Profile},
     clip::ClipboardConfig,
-    source::{SourceConfig, Sources},
+    keybinds::KeybindConfig,
+    routing::RoutingRule,
+    source::{plugin::PLUGINS_PATH, SourceConfig, Sources},
     theme::{self, Theme},
+    watch::Watch,
     widget::notifications::NotificationConfig,
 };
 use directories::ProjectDirs;
@@ -49,7 +53,7 @@ pub struct Config {
     pub theme: String,
     #[serde(rename = "default_source")]
     pub source: Sources,
-    pub download_client: Client,
+    pub download_client: Profile,
     pub date_format: Option<String>,
     pub request_proxy: Option<String>,
     pub timeout: u64,
@@ -65,13 +69,19 @@ pub struct Config {
     pub client: ClientConfig,
     #[serde(rename = "source")]
     pub sources: SourceConfig,
+    #[serde(rename = "watch")]
+    pub watches: Option<Vec<Watch>>,
+    #[serde(rename = "routing")]
+    pub routing: Option<Vec<RoutingRule>>,
+    #[serde(rename = "keybinds")]
+    pub keybinds: Option<KeybindConfig>,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             source: Sources::Nyaa,
-            download_client: Client::Cmd,
+            download_client: Profile::Client(Client::Cmd),
             theme: Theme::default().name,
             date_format: None,
             request_proxy: None,
@@ -84,13 +94,20 @@ impl Default for Config {
             clipboard: None,
             client: ClientConfig::default(),
             sources: SourceConfig::default(),
+            watches: None,
+            routing: None,
+            keybinds: None,
         }
     }
 }
 
 impl ConfigManager for AppConfig {
     fn load(&self) -> Result<Config, Box<dyn Error>> {
-        load_path(&self.config_path.join(CONFIG_FILE))
+        let mut cfg: Config = load_path(&self.config_path.join(CONFIG_FILE))?;
+        cfg.sources.plugins_path = Some(self.config_path.join(PLUGINS_PATH));
+        cfg.validate()
+            .map_err(|e| format!("{:?}\n{}", self.config_path.join(CONFIG_FILE), e))?;
+        Ok(cfg)
     }
     fn store(&self, cfg: &Config) -> Result<(), Box<dyn Error>> {
         store_path(&self.config_path.join(CONFIG_FILE), cfg)
@@ -101,6 +118,35 @@ impl ConfigManager for AppConfig {
 }
 
 impl Config {
+    /// Check that every source and client named in the config is declared, and
+    /// that no two watches share the same seen items
+    pub fn validate(&self) -> Result<(), String> {
+        let mut keys = HashSet::new();
+        if let Some(dup) = self
+            .watches
+            .iter()
+            .flatten()
+            .find(|w| !keys.insert(w.key()))
+        {
+            return Err(format!(
+                "There is more than one watch named \"{}\", give each of them a different name",
+                dup.key()
+            ));
+        }
+        let multi = self.sources.multi.iter().flat_map(|m| m.sources.iter());
+        let watches = self.watches.iter().flatten();
+        let routing = self.routing.iter().flatten();
+        std::iter::once(&self.source)
+            .chain(multi)
+            .chain(watches.clone().map(|w| &w.source))
+            .chain(routing.clone().filter_map(|r| r.source.as_ref()))
+            .try_for_each(|src| src.check(&self.sources))?;
+        std::iter::once(&self.download_client)
+            .chain(watches.filter_map(|w| w.client.as_ref()))
+            .chain(routing.filter_map(|r| r.client.as_ref()))
+            .try_for_each(|client| client.check(&self.client))
+    }
+
     pub fn full_apply(
         &self,
         path: PathBuf,
@@ -113,18 +159,18 @@ impl Config {
         self.partial_apply(ctx, w)?;
 
         // Set download client
-        ctx.client = ctx.config.download_client;
+        ctx.client = ctx.config.download_client.clone();
         // Set source
-        ctx.src = ctx.config.source;
+        ctx.src = ctx.config.source.clone();
         // Set source info (categories, etc.)
-        ctx.src_info = ctx.src.info();
+        ctx.src_info = ctx.src.info(&ctx.config.sources);
 
-        ctx.src.apply(ctx, w);
+        ctx.src.clone().apply(ctx, w);
         if let Some(conf) = ctx.config.notifications {
             w.notification.load_config(&conf);
         }
 
-        w.clients.table.select(ctx.client as usize);
+        w.clients.select(&ctx.client);
 
         // Load defaults for default source
         Ok(())
@@ -133,6 +179,9 @@ impl Config {
     pub fn partial_apply(&self, ctx: &mut Context, w: &mut Widgets) -> Result<(), Box<dyn Error>> {
         ctx.config = self.clone();
 
+        // Load keybinds on top of the defaults
+        ctx.keys = self.keybinds.clone().unwrap_or_default().build()?;
+
         // Set selected theme
         if let Some((i, _, theme)) = ctx.themes.get_full(&self.theme) {
             w.theme.selected = i;
@@ -142,6 +191,8 @@ impl Config {
 
         // Load download client config
         ctx.client.load_config(&mut ctx.config.client);
+        w.clients.load_profiles(&ctx.config.client);
+        w.sources.load_sources(&ctx.config.sources);
 
         // Load current source config
         ctx.src.load_config(&mut ctx.config.sources);
@@ -176,7 +227,7 @@ pub fn load_path<T: Serialize + DeserializeOwned + Default>(
     }
 }
 
-fn store_path(path: impl AsRef<Path>, cfg: impl Serialize) -> Result<(), Box<dyn Error>> {
+pub fn store_path(path: impl AsRef<Path>, cfg: impl Serialize) -> Result<(), Box<dyn Error>> {
     let path = path.as_ref();
     let config_dir = path
         .parent()
@@ -0,0 +1,400 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    path::Path,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::{self, QueryArgs},
+    client::{Client, DownloadResult, Profile},
+    config,
+    source::{Item, SourceConfig, SourceInfo, Sources},
+    sync::SearchQuery,
+    util::conv::item_hash,
+    widget::sort::SortDir,
+};
+
+pub static HISTORY_FILE: &str = "history.toml";
+pub static DOWNLOADS_FILE: &str = "downloads.toml";
+
+// Only remember this many searches, oldest are forgotten first
+static MAX_HISTORY: usize = 100;
+static MAX_DOWNLOADS: usize = 1000;
+
+/// A search stored by name instead of by index, so it still applies after the
+/// categories, filters or sorts of a source change order.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SavedSearch {
+    pub name: String,
+    pub source: Sources,
+    pub query: String,
+    pub category: String,
+    pub filter: String,
+    pub sort: String,
+    pub sort_dir: SortDir,
+    pub user: Option<String>,
+}
+
+impl Default for SavedSearch {
+    fn default() -> Self {
+        Self {
+            name: "".to_owned(),
+            source: Sources::Nyaa,
+            query: "".to_owned(),
+            category: "".to_owned(),
+            filter: "".to_owned(),
+            sort: "".to_owned(),
+            sort_dir: SortDir::Desc,
+            user: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct History {
+    pub saved: Vec<SavedSearch>,
+    pub recent: Vec<SavedSearch>,
+}
+
+impl SavedSearch {
+    pub fn from_search(src: Sources, search: &SearchQuery, info: &SourceInfo) -> Self {
+        Self {
+            name: "".to_owned(),
+            source: src,
+            query: search.query.to_owned(),
+            category: info.clone().entry_from_id(search.category).cfg,
+            filter: info.filters.get(search.filter).cloned().unwrap_or_default(),
+            sort: info
+                .sorts
+                .get(search.sort.sort)
+                .cloned()
+                .unwrap_or_default(),
+            sort_dir: search.sort.dir,
+            user: search.user.to_owned(),
+        }
+    }
+
+    /// Whether both would run the same search, ignoring the name
+    pub fn same_search(&self, other: &SavedSearch) -> bool {
+        Self {
+            name: other.name.to_owned(),
+            ..self.clone()
+        } == *other
+    }
+
+    pub fn title(&self) -> String {
+        match (self.name.is_empty(), self.query.is_empty()) {
+            (false, _) => self.name.to_owned(),
+            (true, false) => self.query.to_owned(),
+            (true, true) => format!("{} {}", self.source, self.category),
+        }
+    }
+
+    /// Build the search query, using the source defaults for anything that
+    /// no longer exists
+    pub fn to_query(&self, config: &SourceConfig) -> Result<SearchQuery, String> {
+        let non_empty = |s: &String| Some(s.to_owned()).filter(|s| !s.is_empty());
+        cli::build_query(
+            &self.source,
+            &QueryArgs {
+                query: Some(self.query.to_owned()),
+                category: non_empty(&self.category),
+                filter: non_empty(&self.filter),
+                sort: non_empty(&self.sort),
+                sort_dir: Some(self.sort_dir),
+                user: self.user.to_owned(),
+                ..Default::default()
+            },
+            config,
+        )
+    }
+}
+
+impl History {
+    /// Move the search to the top of the recent searches
+    pub fn push(&mut self, search: SavedSearch) {
+        self.recent.retain(|s| !s.same_search(&search));
+        self.recent.insert(0, search);
+        self.recent.truncate(MAX_HISTORY);
+    }
+
+    /// Save a search under `name`, replacing any saved search with that name
+    pub fn save(&mut self, name: String, search: &SavedSearch) {
+        let search = SavedSearch {
+            name,
+            ..search.clone()
+        };
+        match self.saved.iter_mut().find(|s| s.name == search.name) {
+            Some(s) => *s = search,
+            None => self.saved.push(search),
+        }
+    }
+
+    /// Saved searches come first, followed by the recent searches
+    pub fn get(&self, idx: usize) -> Option<&SavedSearch> {
+        self.saved
+            .get(idx)
+            .or_else(|| self.recent.get(idx.checked_sub(self.saved.len())?))
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Option<SavedSearch> {
+        match idx < self.saved.len() {
+            true => Some(self.saved.remove(idx)),
+            false => {
+                let idx = idx - self.saved.len();
+                (idx < self.recent.len()).then(|| self.recent.remove(idx))
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.saved.len() + self.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub fn load_history(path: impl AsRef<Path>) -> Result<History, Box<dyn Error + Send + Sync>> {
+    config::load_path(path.as_ref().join(HISTORY_FILE)).map_err(|e| e.to_string().into())
+}
+
+pub fn store_history(
+    path: impl AsRef<Path>,
+    history: &History,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    config::store_path(path.as_ref().join(HISTORY_FILE), history).map_err(|e| e.to_string().into())
+}
+
+/// An item that was successfully sent to a download client
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DownloadEntry {
+    pub id: String,
+    pub hash: String,
+    pub title: String,
+    pub date: String,
+    pub client: Profile,
+    pub source: Option<Sources>,
+    pub size: String,
+    pub torrent_link: String,
+    pub magnet_link: String,
+    pub post_link: String,
+    pub file_name: String,
+}
+
+impl Default for DownloadEntry {
+    fn default() -> Self {
+        Self {
+            id: "".to_owned(),
+            hash: "".to_owned(),
+            title: "".to_owned(),
+            date: "".to_owned(),
+            client: Profile::Client(Client::Cmd),
+            source: None,
+            size: "".to_owned(),
+            torrent_link: "".to_owned(),
+            magnet_link: "".to_owned(),
+            post_link: "".to_owned(),
+            file_name: "".to_owned(),
+        }
+    }
+}
+
+impl DownloadEntry {
+    pub fn new(item: &Item, client: Profile, source: Option<Sources>) -> Self {
+        Self {
+            id: item.id.to_owned(),
+            hash: item_hash(item).unwrap_or_default(),
+            title: item.title.to_owned(),
+            date: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            client,
+            source,
+            size: item.size.to_owned(),
+            torrent_link: item.torrent_link.to_owned(),
+            magnet_link: item.magnet_link.to_owned(),
+            post_link: item.post_link.to_owned(),
+            file_name: item.file_name.to_owned(),
+        }
+    }
+
+    /// Whether the entry refers to `item`. The info hash is compared first,
+    /// since the same torrent can be found on several sources, otherwise the
+    /// id must match on the same source.
+    pub fn matches(&self, item: &Item, source: &Sources) -> bool {
+        if !self.hash.is_empty() && item_hash(item).is_some_and(|h| h == self.hash) {
+            return true;
+        }
+        self.id == item.id && self.source.as_ref() == Some(source)
+    }
+
+    /// Build an item that can be sent to a download client again
+    pub fn to_item(&self) -> Item {
+        Item {
+            id: self.id.to_owned(),
+            title: self.title.to_owned(),
+            size: self.size.to_owned(),
+            torrent_link: self.torrent_link.to_owned(),
+            magnet_link: self.magnet_link.to_owned(),
+            post_link: self.post_link.to_owned(),
+            file_name: self.file_name.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A change to the download history, which is applied to the file again when
+/// saving so downloads saved by another instance of nyaa aren't lost
+#[derive(Clone)]
+pub enum DownloadChange {
+    Add(DownloadEntry),
+    Remove(DownloadEntry),
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, from = "StoredDownloads")]
+pub struct DownloadHistory {
+    pub downloads: Vec<DownloadEntry>,
+    /// Lookups for `contains`, which runs for every visible result while drawing
+    #[serde(skip)]
+    hashes: HashSet<String>,
+    #[serde(skip)]
+    ids: HashMap<Sources, HashSet<String>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StoredDownloads {
+    downloads: Vec<DownloadEntry>,
+}
+
+impl From<StoredDownloads> for DownloadHistory {
+    fn from(stored: StoredDownloads) -> Self {
+        let mut history = DownloadHistory {
+            downloads: stored.downloads,
+            ..Default::default()
+        };
+        history.reindex();
+        history
+    }
+}
+
+impl DownloadHistory {
+    /// Remember every item in `items` that was downloaded successfully, newest
+    /// first. The changes made are returned so they can be saved.
+    pub fn record(
+        &mut self,
+        items: &[Item],
+        client: impl Into<Profile>,
+        source: Option<Sources>,
+        res: &DownloadResult,
+    ) -> Vec<DownloadChange> {
+        let client = client.into();
+        let mut changes = vec![];
+        for item in items.iter().filter(|i| res.success_ids.contains(&i.id)) {
+            // Prefer the links filled in by the source, if it had to resolve them
+            let item = res
+                .resolved
+                .iter()
+                .find(|r| r.id == item.id)
+                .unwrap_or(item);
+            // Routing may have sent the item somewhere other than `client`
+            let client = res.routed_to(&item.id).unwrap_or(&client);
+            let entry = DownloadEntry::new(item, client.clone(), source.clone());
+            changes.push(DownloadChange::Add(entry));
+        }
+        self.apply(&changes);
+        changes
+    }
+
+    pub fn apply(&mut self, changes: &[DownloadChange]) {
+        for change in changes.iter() {
+            match change {
+                DownloadChange::Add(entry) => {
+                    self.downloads
+                        .retain(|d| !(d.id == entry.id && d.source == entry.source));
+                    self.downloads.insert(0, entry.clone());
+                }
+                DownloadChange::Remove(entry) => {
+                    self.downloads
+                        .retain(|d| !(d.id == entry.id && d.source == entry.source));
+                }
+            }
+        }
+        self.downloads.truncate(MAX_DOWNLOADS);
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.hashes.clear();
+        self.ids.clear();
+        for d in self.downloads.iter() {
+            if !d.hash.is_empty() {
+                self.hashes.insert(d.hash.to_owned());
+            }
+            if let Some(source) = d.source.as_ref() {
+                self.ids
+                    .entry(source.clone())
+                    .or_default()
+                    .insert(d.id.to_owned());
+            }
+        }
+    }
+
+    /// Same as checking [`DownloadEntry::matches`] for every entry
+    pub fn contains(&self, item: &Item, source: &Sources) -> bool {
+        item_hash(item).is_some_and(|h| self.hashes.contains(&h))
+            || self
+                .ids
+                .get(source)
+                .is_some_and(|ids| ids.contains(&item.id))
+    }
+}
+
+pub fn load_downloads(
+    path: impl AsRef<Path>,
+) -> Result<DownloadHistory, Box<dyn Error + Send + Sync>> {
+    config::load_path(path.as_ref().join(DOWNLOADS_FILE)).map_err(|e| e.to_string().into())
+}
+
+pub fn store_downloads(
+    path: impl AsRef<Path>,
+    downloads: &DownloadHistory,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    config::store_path(path.as_ref().join(DOWNLOADS_FILE), downloads)
+        .map_err(|e| e.to_string().into())
+}
+
+/// Load the download history in the config folder again, apply the changes,
+/// and save it
+pub fn update_downloads(
+    path: impl AsRef<Path>,
+    changes: &[DownloadChange],
+) -> Result<DownloadHistory, Box<dyn Error + Send + Sync>> {
+    let mut downloads = load_downloads(path.as_ref())?;
+    downloads.apply(changes);
+    store_downloads(path, &downloads)?;
+    Ok(downloads)
+}
+
+/// Add the successful downloads to the download history in the config folder
+pub fn record_downloads(
+    path: impl AsRef<Path>,
+    items: &[Item],
+    client: impl Into<Profile>,
+    source: Option<Sources>,
+    res: &DownloadResult,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if res.success_ids.is_empty() {
+        return Ok(());
+    }
+    let mut downloads = load_downloads(path.as_ref())?;
+    downloads.record(items, client, source, res);
+    store_downloads(path, &downloads)
+}
@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::Mode,
+    util::conv::{key_to_string, string_to_key},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    /// Unbind a key that is bound by default
+    None,
+    Quit,
+    Help,
+    Suspend,
+    Confirm,
+    Close,
+    Up,
+    Down,
+    JumpUp,
+    JumpDown,
+    Top,
+    Bottom,
+    NextTab,
+    PrevTab,
+    Search,
+    Categories,
+    Filters,
+    Sort,
+    SortReversed,
+    Themes,
+    User,
+    Sources,
+    Clients,
+    GotoPage,
+    PrevPage,
+    NextPage,
+    FirstPage,
+    LastPage,
+    Reload,
+    Download,
+    DownloadAll,
+    ClearBatch,
+    ToggleBatch,
+    SwitchPane,
+    OpenInBrowser,
+    Details,
+    Preview,
+    Watch,
+    Copy,
+    VisualToggle,
+    VisualSelect,
+    Dismiss,
+    History,
+    SaveSearch,
+    Delete,
+    Downloads,
+    Status,
+    Pause,
+    Resume,
+    Remove,
+    RemoveData,
+    Recheck,
+    SetCategory,
+}
+
+/// Maps keys, in the format produced by `key_to_string`, to actions
+#[derive(Clone, Default)]
+pub struct KeyMap(IndexMap<String, Action>);
+
+impl KeyMap {
+    fn new(binds: &[(&[&str], Action)]) -> Self {
+        Self(
+            binds
+                .iter()
+                .flat_map(|(keys, action)| keys.iter().map(|k| (k.to_string(), *action)))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, code: &KeyCode, modifiers: &KeyModifiers) -> Option<Action> {
+        // Some terminals send BackTab without the shift modifier
+        let modifiers = match code {
+            KeyCode::BackTab => KeyModifiers::SHIFT,
+            _ => *modifiers,
+        };
+        self.0
+            .get(&key_to_string(*code, modifiers))
+            .copied()
+            .filter(|a| *a != Action::None)
+    }
+
+    /// All keys bound to `action`, in the order they were bound
+    pub fn keys(&self, action: Action) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(k, _)| k.as_str())
+            .collect()
+    }
+
+    /// Help entries for each action with at least one bound key
+    pub fn help(&self, actions: &[(Action, &'static str)]) -> Vec<(String, &'static str)> {
+        actions
+            .iter()
+            .filter_map(|(action, desc)| {
+                let keys = self.keys(*action);
+                (!keys.is_empty()).then(|| (keys.join(", "), *desc))
+            })
+            .collect()
+    }
+
+    fn extend(&mut self, mode: &str, binds: &BTreeMap<String, Action>) -> Result<(), String> {
+        for (key, action) in binds.iter() {
+            let Some((code, modifiers)) = string_to_key(key) else {
+                return Err(format!("Invalid key \"{}\" in [keybinds.{}]", key, mode));
+            };
+            // Store keys the same way they are looked up
+            let key = key_to_string(code, modifiers);
+            self.0.shift_remove(&key);
+            self.0.insert(key, *action);
+        }
+        Ok(())
+    }
+}
+
+macro_rules! keybinds {
+    ($($mode:ident),+ $(,)?) => {
+        #[derive(Serialize, Deserialize, Clone, Default)]
+        #[serde(default)]
+        pub struct KeybindConfig {
+            $(pub $mode: Option<BTreeMap<String, Action>>,)+
+        }
+
+        #[derive(Clone)]
+        pub struct Keybinds {
+            $(pub $mode: KeyMap,)+
+        }
+
+        impl KeybindConfig {
+            /// Apply the configured keys on top of the default keybinds
+            pub fn build(&self) -> Result<Keybinds, String> {
+                let mut keys = Keybinds::default();
+                $(
+                    if let Some(binds) = self.$mode.as_ref() {
+                        keys.$mode.extend(stringify!($mode), binds)?;
+                    }
+                )+
+                Ok(keys)
+            }
+        }
+    };
+}
+
+keybinds! {
+    global,
+    normal,
+    batch,
+    search,
+    category,
+    sort,
+    filter,
+    theme,
+    sources,
+    clients,
+    page,
+    user,
+    help,
+    details,
+    history,
+    downloads,
+    status,
+    captcha,
+}
+
+impl Keybinds {
+    pub fn for_mode(&self, mode: &Mode) -> &KeyMap {
+        match mode {
+            Mode::Normal | Mode::KeyCombo(_) | Mode::Loading(_) => &self.normal,
+            Mode::Batch => &self.batch,
+            Mode::Search => &self.search,
+            Mode::Category => &self.category,
+            Mode::Sort(_) => &self.sort,
+            Mode::Filter => &self.filter,
+            Mode::Theme => &self.theme,
+            Mode::Sources => &self.sources,
+            Mode::Clients => &self.clients,
+            Mode::Page => &self.page,
+            Mode::User => &self.user,
+            Mode::Help => &self.help,
+            Mode::Details => &self.details,
+            Mode::History => &self.history,
+            Mode::Downloads => &self.downloads,
+            Mode::Status => &self.status,
+            Mode::Captcha => &self.captcha,
+        }
+    }
+}
+
+impl Default for Keybinds {
+    fn default() -> Self {
+        use Action::*;
+
+        let list = |close: &'static str| {
+            KeyMap::new(&[
+                (&["<CR>"], Confirm),
+                (&["<Esc>", close, "q"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["g"], Top),
+                (&["G"], Bottom),
+            ])
+        };
+        let input = || KeyMap::new(&[(&["<CR>"], Confirm), (&["<Esc>"], Close)]);
+
+        Keybinds {
+            global: KeyMap::new(&[(&["?", "<F1>"], Help), (&["<C-z>"], Suspend)]),
+            normal: KeyMap::new(&[
+                (&["<CR>"], Download),
+                (&["<Esc>"], Dismiss),
+                (&["q"], Quit),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["k", "<Up>"], Up),
+                (&["j", "<Down>"], Down),
+                (&["K"], JumpUp),
+                (&["J"], JumpDown),
+                (&["n", "l", "<Right>"], NextPage),
+                (&["p", "h", "<Left>"], PrevPage),
+                (&["N", "L"], LastPage),
+                (&["P", "H"], FirstPage),
+                (&["r"], Reload),
+                (&["o"], OpenInBrowser),
+                (&["I"], Details),
+                (&["T"], Preview),
+                (&["W"], Watch),
+                (&["y"], Copy),
+                (&["<Space>"], ToggleBatch),
+                (&["<C-Space>", "v"], VisualToggle),
+                (&["V"], VisualSelect),
+                (&["<Tab>", "<S-Tab>"], SwitchPane),
+                (&["/", "i"], Search),
+                (&["c"], Categories),
+                (&["f"], Filters),
+                (&["s"], Sort),
+                (&["S"], SortReversed),
+                (&["t"], Themes),
+                (&["u"], User),
+                (&["d"], Clients),
+                (&["<C-p>"], GotoPage),
+                (&["<C-s>"], Sources),
+                (&["R", "<C-r>"], History),
+                (&["D"], Downloads),
+                (&["a"], Status),
+            ]),
+            batch: KeyMap::new(&[
+                (&["<C-a>"], DownloadAll),
+                (&["<C-x>"], ClearBatch),
+                (&["<Esc>", "<Tab>", "<S-Tab>"], SwitchPane),
+                (&["q"], Quit),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["k", "<Up>"], Up),
+                (&["j", "<Down>"], Down),
+                (&["K"], JumpUp),
+                (&["J"], JumpDown),
+                (&["<Space>"], ToggleBatch),
+            ]),
+            search: input(),
+            category: KeyMap::new(&[
+                (&["<CR>"], Confirm),
+                (&["<Esc>", "c", "q"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["<Tab>", "J"], NextTab),
+                (&["<S-Tab>", "K"], PrevTab),
+            ]),
+            sort: list("s"),
+            filter: list("f"),
+            theme: list("t"),
+            sources: list("s"),
+            clients: list("d"),
+            page: input(),
+            user: input(),
+            help: KeyMap::new(&[
+                (&["<Esc>", "?", "<F1>", "q"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["g"], Top),
+                (&["G"], Bottom),
+            ]),
+            details: KeyMap::new(&[
+                (&["<CR>"], Download),
+                (&["<Esc>", "q", "I", "T"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["J", "<PgDown>"], JumpDown),
+                (&["K", "<PgUp>"], JumpUp),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["o"], OpenInBrowser),
+            ]),
+            history: KeyMap::new(&[
+                (&["<CR>"], Confirm),
+                (&["<Esc>", "R", "q"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["s"], SaveSearch),
+                (&["x", "<Del>"], Delete),
+            ]),
+            downloads: KeyMap::new(&[
+                (&["<CR>"], Confirm),
+                (&["<Esc>", "D", "q"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["/"], Search),
+                (&["o"], OpenInBrowser),
+                (&["x", "<Del>"], Delete),
+            ]),
+            status: KeyMap::new(&[
+                (&["<CR>"], Confirm),
+                (&["<Esc>", "a", "q"], Close),
+                (&["j", "<Down>"], Down),
+                (&["k", "<Up>"], Up),
+                (&["g"], Top),
+                (&["G"], Bottom),
+                (&["r"], Reload),
+                (&["p"], Pause),
+                (&["s"], Resume),
+                (&["x", "<Del>"], Remove),
+                (&["X"], RemoveData),
+                (&["v"], Recheck),
+                (&["c"], SetCategory),
+            ]),
+            captcha: input(),
+        }
+    }
+}
@@ -1,11 +1,17 @@
 pub mod app;
+pub mod cli;
 pub mod client;
 pub mod clip;
 pub mod config;
+pub mod history;
+pub mod keybinds;
 pub mod macros;
 pub mod results;
+pub mod routing;
 pub mod source;
 pub mod sync;
 pub mod theme;
+pub mod torrent;
 pub mod util;
+pub mod watch;
 pub mod widget;
@@ -45,14 +45,15 @@ macro_rules! widgets {
 
             }
 
-            fn get_help(&self, mode: &$crate::app::Mode) -> Option<Vec<(&'static str, &'static str)>> {
+            fn get_help(&self, mode: &$crate::app::Mode, keys: &$crate::keybinds::Keybinds) -> Option<Vec<(String, &'static str)>> {
+                let keys = keys.for_mode(mode);
                 match mode {
                     $(
-                        $($mode => $struc::get_help(),)?
+                        $($mode => $struc::get_help(keys),)?
                     )+
                     $(
                         $(#[$docs])*
-                        $($pmode => $pstruc::get_help(),)?
+                        $($pmode => $pstruc::get_help(keys),)?
                     )+
                     _ => None,
                 }
@@ -1,30 +1,68 @@
 use std::{error::Error, io::stdout};
 
 use app::App;
+use cli::{Command, DownloadArgs, QueryArgs, SearchArgs};
 use config::{AppConfig, ConfigManager};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use sync::AppSync;
+use widget::sort::SortDir;
 
 pub mod app;
+pub mod cli;
 pub mod client;
 pub mod clip;
 pub mod config;
+pub mod history;
+pub mod keybinds;
 pub mod macros;
 pub mod results;
+pub mod routing;
 pub mod source;
 pub mod sync;
 pub mod theme;
+pub mod torrent;
 pub mod util;
+pub mod watch;
 pub mod widget;
 
+static USAGE: &str = "Usage:
+  nyaa [-v|-V|--version] [-c|--config=/path/to/config/folder]
+  nyaa search [OPTIONS] [QUERY]
+  nyaa download [OPTIONS] <QUERY|POST-URL|MAGNET>
+  nyaa watch [--once]
+
+Search options (also used by download when given a query):
+  -s, --source=NAME      Source to search (defaults to default_source)
+      --category=NAME    Category config name (e.g. AnimeEnglishTranslated)
+      --filter=NAME      Filter name (e.g. NoRemakes)
+      --sort=NAME        Sort name (e.g. Seeders)
+      --sort-dir=DIR     Sort direction, asc or desc
+  -u, --user=NAME        Only show results from the given user
+  -p, --page=N           Page of results to show
+  -f, --format=FORMAT    Output format, one of json, csv, or table (default)
+
+Download options:
+  -d, --client=NAME      Download client to use (defaults to download_client)
+  -n, --count=N          Number of results to download for a query (default 1)
+
+Watch options:
+      --once             Check every watch once and exit, instead of running forever";
+
 struct Args {
     config_path: Option<String>,
+    command: Option<Command>,
 }
 
 fn parse_args() -> Result<Args, Box<dyn Error>> {
     use lexopt::prelude::*;
 
     let mut config_path = None;
+    let mut command = None;
+    let mut query = QueryArgs::default();
+    let mut format = None;
+    let mut client = None;
+    let mut count = None;
+    let mut once = false;
     let mut parser = lexopt::Parser::from_env();
     while let Some(arg) = parser.next()? {
         match arg {
@@ -35,19 +73,148 @@ fn parse_args() -> Result<Args, Box<dyn Error>> {
                 println!("nyaa v{}", env!("CARGO_PKG_VERSION"));
                 std::process::exit(0);
             }
-            Long("help") => {
-                println!("Usage: nyaa [-v|-V|--version] [-c|--config=/path/to/config/folder]");
+            Long("help") | Short('h') => {
+                println!("{}", USAGE);
                 std::process::exit(0);
             }
+            Value(val) if command.is_none() => {
+                command = Some(val.string()?);
+            }
+            Short('s') | Long("source") if command.is_some() => {
+                query.source = Some(parser.value()?.string()?);
+            }
+            Long("category") if command.is_some() => {
+                query.category = Some(parser.value()?.string()?);
+            }
+            Long("filter") if command.is_some() => {
+                query.filter = Some(parser.value()?.string()?);
+            }
+            Long("sort") if command.is_some() => {
+                query.sort = Some(parser.value()?.string()?);
+            }
+            Long("sort-dir") if command.is_some() => {
+                query.sort_dir = Some(match parser.value()?.string()?.to_lowercase().as_str() {
+                    "asc" => SortDir::Asc,
+                    "desc" => SortDir::Desc,
+                    other => return Err(format!("Unknown sort direction \"{}\"", other).into()),
+                });
+            }
+            Short('u') | Long("user") if command.is_some() => {
+                query.user = Some(parser.value()?.string()?);
+            }
+            Short('p') | Long("page") if command.is_some() => {
+                query.page = Some(parser.value()?.parse()?);
+            }
+            Short('f') | Long("format") if command.as_deref() == Some("search") => {
+                format = Some(parser.value()?.string()?.parse()?);
+            }
+            Short('d') | Long("client") if command.as_deref() == Some("download") => {
+                client = Some(parser.value()?.string()?);
+            }
+            Short('n') | Long("count") if command.as_deref() == Some("download") => {
+                count = Some(parser.value()?.parse()?);
+            }
+            Long("once") if command.as_deref() == Some("watch") => {
+                once = true;
+            }
+            Value(val) if command.is_some() => {
+                let val = val.string()?;
+                query.query = Some(match query.query {
+                    Some(q) => format!("{} {}", q, val),
+                    None => val,
+                });
+            }
             _ => return Err(arg.unexpected().into()),
         }
     }
 
-    Ok(Args { config_path })
+    let command = match command.as_deref() {
+        None => None,
+        Some("search") => Some(Command::Search(SearchArgs {
+            query,
+            format: format.unwrap_or_default(),
+        })),
+        Some("download") => Some(Command::Download(DownloadArgs {
+            query,
+            client,
+            count,
+        })),
+        Some("watch") => Some(Command::Watch { once }),
+        Some(other) => return Err(format!("Unknown command \"{}\"\n\n{}", other, USAGE).into()),
+    };
+
+    Ok(Args {
+        config_path,
+        command,
+    })
+}
+
+/// Run a non-interactive command, returning the exit code
+async fn run_command(
+    command: Command,
+    config_manager: AppConfig,
+) -> Result<i32, Box<dyn Error + Send + Sync>> {
+    let config = config_manager.load().map_err(|e| e.to_string())?;
+    match command {
+        Command::Search(args) => {
+            let (src, search, res) = cli::search(&args.query, &config).await?;
+            print!(
+                "{}",
+                cli::format_results(&src, &search, &res, &config.sources, args.format)?
+            );
+            Ok(0)
+        }
+        Command::Download(args) => {
+            if args.query.query.is_none() {
+                return Err(format!("Missing query, post url or magnet link\n\n{}", USAGE).into());
+            }
+            let (client, src, items, res) = cli::download(&args, &config).await?;
+            if let Err(e) =
+                history::record_downloads(config_manager.path(), &items, client.clone(), src, &res)
+            {
+                eprintln!("nyaa: Failed to save download history: {}", e);
+            }
+            for item in items.iter().filter(|i| res.success_ids.contains(&i.id)) {
+                println!("{}", item.title);
+            }
+            if !res.success_ids.is_empty() {
+                if let Some(msg) = res.success_msg.as_ref() {
+                    println!("{}", msg);
+                }
+            }
+            for e in res.errors.iter() {
+                eprintln!("nyaa: {}: {}", client, e);
+            }
+            Ok(match (res.success_ids.is_empty(), res.errors.is_empty()) {
+                (_, true) => 0,
+                (true, false) => 1,
+                (false, false) => 2,
+            })
+        }
+        Command::Watch { once } => {
+            watch::run(config_manager, once).await?;
+            Ok(0)
+        }
+    }
 }
 
 #[tokio::main()]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = parse_args()?;
+    let config = match args.config_path {
+        Some(path) => AppConfig::from_path(path),
+        None => AppConfig::new(),
+    }?;
+    if let Some(command) = args.command {
+        match run_command(command, config).await {
+            Ok(code) => std::process::exit(code),
+            Err(e) => {
+                eprintln!("nyaa: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     let default_panic = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
         // Try to reset terminal on panic
@@ -56,16 +223,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }));
 
-    let args = parse_args()?;
     util::term::setup_terminal()?;
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::default();
-    let config = match args.config_path {
-        Some(path) => AppConfig::from_path(path),
-        None => AppConfig::new(),
-    }?;
     let sync = AppSync::new(config.path());
 
     app.run_app::<_, _, AppConfig, false>(&mut terminal, sync, config)
@@ -0,0 +1,219 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::{ClientConfig, DownloadError, DownloadResult, Profile},
+    source::{multi, Item, Sources},
+    util::conv::to_bytes,
+};
+
+/// Sends matching items to another client, or the same client with some of its
+/// options changed. Every condition that is set must match.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RoutingRule {
+    pub source: Option<Sources>,
+    pub categories: Vec<usize>,
+    pub title: Option<String>,
+    pub min_size: Option<String>,
+    pub max_size: Option<String>,
+    pub uploader: Option<String>,
+    pub client: Option<Profile>,
+    pub overrides: toml::Table,
+}
+
+impl RoutingRule {
+    pub fn title_regex(&self) -> Result<Option<Regex>, String> {
+        self.title
+            .as_ref()
+            .map(|title| {
+                Regex::new(title).map_err(|e| format!("Invalid title regex \"{}\":\n{}", title, e))
+            })
+            .transpose()
+    }
+
+    /// `title` is the rule's compiled [`RoutingRule::title_regex`]
+    /// `src` is `None` for items that weren't found on a source, like magnet
+    /// links, which never match a rule with a `source`
+    pub fn matches(&self, item: &Item, src: Option<&Sources>, title: Option<&Regex>) -> bool {
+        // Results from Multi remember the source they came from
+        let src = multi::item_source(item).or(src.cloned());
+        if self.source.is_some() && self.source != src {
+            return false;
+        }
+        if !self.categories.is_empty() && !self.categories.contains(&item.category) {
+            return false;
+        }
+        if title.is_some_and(|re| !re.is_match(&item.title)) {
+            return false;
+        }
+        if self
+            .min_size
+            .as_ref()
+            .is_some_and(|min| item.bytes < to_bytes(min))
+        {
+            return false;
+        }
+        if self
+            .max_size
+            .as_ref()
+            .is_some_and(|max| item.bytes > to_bytes(max))
+        {
+            return false;
+        }
+        if let Some(uploader) = self.uploader.as_ref() {
+            // Only some sources list the uploader with the results
+            let matched = item
+                .extra
+                .get("uploader")
+                .is_some_and(|u| u.eq_ignore_ascii_case(uploader));
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The index of a rule, and the items that matched it first
+pub type Route = (Option<usize>, Vec<Item>);
+
+/// Group the items by the first rule they match, keeping the order they were
+/// given in. Items matching no rule are grouped under `None`.
+pub fn route(
+    rules: &[RoutingRule],
+    src: Option<&Sources>,
+    items: Vec<Item>,
+) -> Result<Vec<Route>, String> {
+    let titles = rules
+        .iter()
+        .map(RoutingRule::title_regex)
+        .collect::<Result<Vec<Option<Regex>>, String>>()?;
+    let mut groups: Vec<Route> = vec![];
+    for item in items.into_iter() {
+        let rule = rules
+            .iter()
+            .zip(titles.iter())
+            .position(|(r, title)| r.matches(&item, src, title.as_ref()));
+        match groups.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((rule, vec![item])),
+        }
+    }
+    Ok(groups)
+}
+
+fn rule_profile<'a>(rule: Option<&'a RoutingRule>, default: &'a Profile) -> &'a Profile {
+    rule.and_then(|r| r.client.as_ref()).unwrap_or(default)
+}
+
+/// The profiles the items will be sent to, without duplicates
+pub fn profiles(
+    rules: &[RoutingRule],
+    src: Option<&Sources>,
+    items: &[Item],
+    default: &Profile,
+) -> Vec<Profile> {
+    let Ok(groups) = route(rules, src, items.to_vec()) else {
+        return vec![default.clone()];
+    };
+    let mut profiles: Vec<Profile> = vec![];
+    for (rule, _) in groups.iter() {
+        let profile = rule_profile(rule.and_then(|r| rules.get(r)), default);
+        if !profiles.contains(profile) {
+            profiles.push(profile.clone());
+        }
+    }
+    profiles
+}
+
+async fn send(
+    rule: Option<&RoutingRule>,
+    profile: &Profile,
+    items: Vec<Item>,
+    batch: bool,
+    conf: &ClientConfig,
+    rq: reqwest::Client,
+    source_rq: reqwest::Client,
+) -> DownloadResult {
+    let (client, mut conf) = match profile.resolve(conf) {
+        Ok(res) => res,
+        Err(e) => return DownloadResult::error(e),
+    };
+    if let Some(rule) = rule.filter(|r| !r.overrides.is_empty()) {
+        if let Err(e) = client.apply_overrides(&mut conf, &rule.overrides) {
+            return DownloadResult::error(DownloadError(format!(
+                "Invalid routing overrides for {}:\n{}",
+                profile, e
+            )));
+        }
+    }
+    match batch {
+        true => client.batch_download(items, conf, rq, source_rq).await,
+        false => client.download(items[0].clone(), conf, rq, source_rq).await,
+    }
+}
+
+/// Send each item to the client picked by the routing rules, or to `default`
+/// if no rule matches. `rq` is used to reach the download clients, and
+/// `source_rq` to reach the source.
+#[allow(clippy::too_many_arguments)]
+pub async fn download(
+    rules: &[RoutingRule],
+    src: Option<&Sources>,
+    batch: bool,
+    items: Vec<Item>,
+    default: &Profile,
+    conf: &ClientConfig,
+    rq: reqwest::Client,
+    source_rq: reqwest::Client,
+) -> DownloadResult {
+    let groups = match route(rules, src, items) {
+        Ok(groups) => groups,
+        Err(e) => return DownloadResult::error(DownloadError(e)),
+    };
+    let mut results = vec![];
+    for (rule, items) in groups.into_iter() {
+        let rule = rule.and_then(|r| rules.get(r));
+        let profile = rule_profile(rule, default);
+        let routed = items
+            .iter()
+            .map(|i| (i.id.to_owned(), profile.clone()))
+            .collect::<Vec<_>>();
+        let mut res = send(
+            rule,
+            profile,
+            items,
+            batch,
+            conf,
+            rq.clone(),
+            source_rq.clone(),
+        )
+        .await;
+        res.routed = routed;
+        results.push(res);
+    }
+    if results.len() == 1 {
+        return results.remove(0);
+    }
+
+    let success_msg = results
+        .iter()
+        .filter(|r| !r.success_ids.is_empty())
+        .filter_map(|r| r.success_msg.to_owned())
+        .collect::<Vec<String>>()
+        .join("\n");
+    let success_msg = (!success_msg.is_empty()).then_some(success_msg);
+    let (success_ids, errors, routed) = results.into_iter().fold(
+        (vec![], vec![], vec![]),
+        |(mut ids, mut errors, mut routed), r| {
+            ids.extend(r.success_ids);
+            errors.extend(r.errors);
+            routed.extend(r.routed);
+            (ids, errors, routed)
+        },
+    );
+    let mut res = DownloadResult::new(success_msg, success_ids, errors, batch);
+    res.routed = routed;
+    res
+}
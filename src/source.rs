@@ -1,14 +1,26 @@
-use std::{collections::HashMap, error::Error, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::Display,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
+use anidex::AniDexTheme;
+use animetosho::AnimeToshoTheme;
 use nyaa_html::NyaaTheme;
 use reqwest::{cookie::Jar, Proxy};
 use serde::{Deserialize, Serialize};
-use strum::{Display, VariantArray};
 use sukebei_nyaa::SukebeiTheme;
+use tokyotosho::TokyoToshoTheme;
 use torrent_galaxy::TgxTheme;
+use torznab::TorznabTheme;
+use x1337::X1337Theme;
 
 use crate::{
     app::{Context, LoadType, Widgets},
+    client::DownloadError,
     results::{ResultResponse, ResultTable, Results},
     sync::SearchQuery,
     theme::Theme,
@@ -20,18 +32,34 @@ use crate::{
 };
 
 use self::{
+    anidex::{AniDexConfig, AniDexSource},
+    animetosho::{AnimeToshoConfig, AnimeToshoSource},
+    custom::CustomConfig,
+    multi::{MultiConfig, MultiSource},
     nyaa_html::{NyaaConfig, NyaaHtmlSource},
+    plugin::PluginConfig,
     sukebei_nyaa::{SukebeiHtmlSource, SukebeiNyaaConfig},
+    tokyotosho::{TokyoToshoConfig, TokyoToshoSource},
     torrent_galaxy::{TgxConfig, TorrentGalaxyHtmlSource},
+    torznab::{TorznabConfig, TorznabSource},
+    x1337::{X1337Config, X1337Source},
 };
 
 #[cfg(feature = "captcha")]
 use ratatui_image::protocol::StatefulProtocol;
 
+pub mod anidex;
+pub mod animetosho;
+pub mod custom;
+pub mod multi;
 pub mod nyaa_html;
 pub mod nyaa_rss;
+pub mod plugin;
 pub mod sukebei_nyaa;
+pub mod tokyotosho;
 pub mod torrent_galaxy;
+pub mod torznab;
+pub mod x1337;
 
 #[derive(Clone)]
 pub enum SourceResults {
@@ -55,6 +83,16 @@ pub struct SourceTheme {
     pub sukebei: SukebeiTheme,
     #[serde(default, rename = "torrentgalaxy")]
     pub tgx: TgxTheme,
+    #[serde(default)]
+    pub torznab: TorznabTheme,
+    #[serde(default)]
+    pub animetosho: AnimeToshoTheme,
+    #[serde(default)]
+    pub tokyotosho: TokyoToshoTheme,
+    #[serde(default)]
+    pub anidex: AniDexTheme,
+    #[serde(default, rename = "1337x")]
+    pub x1337: X1337Theme,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -65,6 +103,20 @@ pub struct SourceConfig {
     pub sukebei: Option<SukebeiNyaaConfig>,
     #[serde(rename = "torrentgalaxy")]
     pub tgx: Option<TgxConfig>,
+    pub torznab: Option<TorznabConfig>,
+    pub multi: Option<MultiConfig>,
+    pub animetosho: Option<AnimeToshoConfig>,
+    pub tokyotosho: Option<TokyoToshoConfig>,
+    pub anidex: Option<AniDexConfig>,
+    #[serde(rename = "1337x")]
+    pub x1337: Option<X1337Config>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, CustomConfig>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugin: BTreeMap<String, PluginConfig>,
+    /// The folder that plugin commands are relative to, set when loading the config
+    #[serde(skip)]
+    pub plugins_path: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -140,6 +192,8 @@ pub enum ItemType {
 pub struct Item {
     pub id: String,
     pub date: String,
+    /// Upload time as a unix timestamp, used to sort results from several sources
+    pub timestamp: i64,
     pub seeders: u32,
     pub leechers: u32,
     pub downloads: u32,
@@ -156,14 +210,94 @@ pub struct Item {
     pub extra: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq)]
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ItemFile {
+    pub path: String,
+    pub size: String,
+}
+
+/// Extra information about an item, found on its post page
+#[derive(Clone, Default, Debug)]
+pub struct ItemDetails {
+    pub title: String,
+    pub submitter: String,
+    pub date: String,
+    pub size: String,
+    pub info_hash: String,
+    pub description: String,
+    pub files: Vec<ItemFile>,
+    pub comments: usize,
+}
+
+/// A source to search, either built-in, one of the scrapers declared by the
+/// user in `[source.custom]`, or a plugin in `[source.plugin]`. Plugins are
+/// written as `plugin:<name>` in the config, everything else as its name.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
 pub enum Sources {
-    #[strum(serialize = "Nyaa")]
-    Nyaa = 0,
-    #[strum(serialize = "Sukebei")]
-    SukebeiNyaa = 1,
-    #[strum(serialize = "TorrentGalaxy")]
-    TorrentGalaxy = 2,
+    Nyaa,
+    SukebeiNyaa,
+    TorrentGalaxy,
+    Torznab,
+    Multi,
+    AnimeTosho,
+    TokyoTosho,
+    AniDex,
+    X1337,
+    Custom(String),
+    Plugin(String),
+}
+
+impl TryFrom<String> for Sources {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Ok(match name.as_str() {
+            "Nyaa" => Sources::Nyaa,
+            "SukebeiNyaa" | "Sukebei" => Sources::SukebeiNyaa,
+            "TorrentGalaxy" => Sources::TorrentGalaxy,
+            "Torznab" => Sources::Torznab,
+            "Multi" => Sources::Multi,
+            "AnimeTosho" => Sources::AnimeTosho,
+            "TokyoTosho" => Sources::TokyoTosho,
+            "AniDex" => Sources::AniDex,
+            "1337x" => Sources::X1337,
+            "" | "plugin:" => return Err("Source name can't be empty".to_owned()),
+            _ => match name.strip_prefix("plugin:") {
+                Some(plugin) => Sources::Plugin(plugin.to_owned()),
+                None => Sources::Custom(name),
+            },
+        })
+    }
+}
+
+impl From<Sources> for String {
+    fn from(src: Sources) -> Self {
+        match src {
+            Sources::SukebeiNyaa => "SukebeiNyaa".to_owned(),
+            Sources::Custom(name) => name,
+            Sources::Plugin(name) => format!("plugin:{}", name),
+            src => src.to_string(),
+        }
+    }
+}
+
+impl Display for Sources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Sources::Nyaa => "Nyaa",
+            Sources::SukebeiNyaa => "Sukebei",
+            Sources::TorrentGalaxy => "TorrentGalaxy",
+            Sources::Torznab => "Torznab",
+            Sources::Multi => "Multi",
+            Sources::AnimeTosho => "AnimeTosho",
+            Sources::TokyoTosho => "TokyoTosho",
+            Sources::AniDex => "AniDex",
+            Sources::X1337 => "1337x",
+            Sources::Custom(name) | Sources::Plugin(name) => name,
+        };
+        write!(f, "{}", name)
+    }
 }
 
 pub trait Source {
@@ -198,6 +332,20 @@ pub trait Source {
         config: &SourceConfig,
         date_format: Option<String>,
     ) -> impl std::future::Future<Output = Result<SourceResponse, Box<dyn Error + Send + Sync>>> + Send;
+    fn details(
+        client: &reqwest::Client,
+        item: &Item,
+        config: &SourceConfig,
+    ) -> impl std::future::Future<Output = Result<ItemDetails, Box<dyn Error + Send + Sync>>> + Send;
+    /// Fill in the links of an item right before it's sent to a download
+    /// client, for sources that only show them on the item's own page
+    fn resolve(
+        _client: &reqwest::Client,
+        item: Item,
+        _config: &SourceConfig,
+    ) -> impl std::future::Future<Output = Result<Item, Box<dyn Error + Send + Sync>>> + Send {
+        async move { Ok(item) }
+    }
     fn info() -> SourceInfo;
     fn load_config(config: &mut SourceConfig);
 
@@ -215,6 +363,43 @@ pub trait Source {
 }
 
 impl Sources {
+    pub const BUILTIN: &'static [Sources] = &[
+        Sources::Nyaa,
+        Sources::SukebeiNyaa,
+        Sources::TorrentGalaxy,
+        Sources::Torznab,
+        Sources::Multi,
+        Sources::AnimeTosho,
+        Sources::TokyoTosho,
+        Sources::AniDex,
+        Sources::X1337,
+    ];
+
+    /// Every source that can be selected, built-in sources first
+    pub fn all(config: &SourceConfig) -> Vec<Sources> {
+        Sources::BUILTIN
+            .iter()
+            .cloned()
+            .chain(config.custom.keys().map(|k| Sources::Custom(k.to_owned())))
+            .chain(config.plugin.keys().map(|k| Sources::Plugin(k.to_owned())))
+            .collect()
+    }
+
+    /// Custom sources and plugins are only known if they're declared in the config
+    pub fn check(&self, config: &SourceConfig) -> Result<(), String> {
+        match self {
+            Sources::Custom(name) if !config.custom.contains_key(name) => Err(format!(
+                "Unknown source \"{}\", it isn't declared in [source.custom]",
+                name
+            )),
+            Sources::Plugin(name) if !config.plugin.contains_key(name) => Err(format!(
+                "Unknown plugin \"{}\", it isn't declared in [source.plugin]",
+                name
+            )),
+            _ => Ok(()),
+        }
+    }
+
     pub async fn load(
         &self,
         load_type: LoadType,
@@ -240,7 +425,12 @@ impl Sources {
                 LoadType::SolvingCaptcha(solution) => {
                     NyaaHtmlSource::solve(solution, client, search, config, date_format).await
                 }
-                LoadType::Downloading | LoadType::Batching => unreachable!(),
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
             },
             Sources::SukebeiNyaa => match load_type {
                 LoadType::Searching | LoadType::Sourcing => {
@@ -258,7 +448,12 @@ impl Sources {
                 LoadType::SolvingCaptcha(solution) => {
                     SukebeiHtmlSource::solve(solution, client, search, config, date_format).await
                 }
-                LoadType::Downloading | LoadType::Batching => unreachable!(),
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
             },
             Sources::TorrentGalaxy => match load_type {
                 LoadType::Searching | LoadType::Sourcing => {
@@ -277,13 +472,283 @@ impl Sources {
                     TorrentGalaxyHtmlSource::solve(solution, client, search, config, date_format)
                         .await
                 }
-                LoadType::Downloading | LoadType::Batching => unreachable!(),
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            Sources::Torznab => match load_type {
+                LoadType::Searching | LoadType::Sourcing => {
+                    TorznabSource::search(client, search, config, date_format).await
+                }
+                LoadType::Sorting => TorznabSource::sort(client, search, config, date_format).await,
+                LoadType::Filtering => {
+                    TorznabSource::filter(client, search, config, date_format).await
+                }
+                LoadType::Categorizing => {
+                    TorznabSource::categorize(client, search, config, date_format).await
+                }
+                LoadType::SolvingCaptcha(solution) => {
+                    TorznabSource::solve(solution, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            Sources::Multi => match load_type {
+                LoadType::Searching | LoadType::Sourcing => {
+                    MultiSource::search(client, search, config, date_format).await
+                }
+                LoadType::Sorting => MultiSource::sort(client, search, config, date_format).await,
+                LoadType::Filtering => {
+                    MultiSource::filter(client, search, config, date_format).await
+                }
+                LoadType::Categorizing => {
+                    MultiSource::categorize(client, search, config, date_format).await
+                }
+                LoadType::SolvingCaptcha(solution) => {
+                    MultiSource::solve(solution, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            Sources::AnimeTosho => match load_type {
+                LoadType::Searching | LoadType::Sourcing => {
+                    AnimeToshoSource::search(client, search, config, date_format).await
+                }
+                LoadType::Sorting => {
+                    AnimeToshoSource::sort(client, search, config, date_format).await
+                }
+                LoadType::Filtering => {
+                    AnimeToshoSource::filter(client, search, config, date_format).await
+                }
+                LoadType::Categorizing => {
+                    AnimeToshoSource::categorize(client, search, config, date_format).await
+                }
+                LoadType::SolvingCaptcha(solution) => {
+                    AnimeToshoSource::solve(solution, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            Sources::TokyoTosho => match load_type {
+                LoadType::Searching | LoadType::Sourcing => {
+                    TokyoToshoSource::search(client, search, config, date_format).await
+                }
+                LoadType::Sorting => {
+                    TokyoToshoSource::sort(client, search, config, date_format).await
+                }
+                LoadType::Filtering => {
+                    TokyoToshoSource::filter(client, search, config, date_format).await
+                }
+                LoadType::Categorizing => {
+                    TokyoToshoSource::categorize(client, search, config, date_format).await
+                }
+                LoadType::SolvingCaptcha(solution) => {
+                    TokyoToshoSource::solve(solution, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            Sources::AniDex => match load_type {
+                LoadType::Searching | LoadType::Sourcing => {
+                    AniDexSource::search(client, search, config, date_format).await
+                }
+                LoadType::Sorting => AniDexSource::sort(client, search, config, date_format).await,
+                LoadType::Filtering => {
+                    AniDexSource::filter(client, search, config, date_format).await
+                }
+                LoadType::Categorizing => {
+                    AniDexSource::categorize(client, search, config, date_format).await
+                }
+                LoadType::SolvingCaptcha(solution) => {
+                    AniDexSource::solve(solution, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
             },
+            Sources::X1337 => match load_type {
+                LoadType::Searching | LoadType::Sourcing => {
+                    X1337Source::search(client, search, config, date_format).await
+                }
+                LoadType::Sorting => X1337Source::sort(client, search, config, date_format).await,
+                LoadType::Filtering => {
+                    X1337Source::filter(client, search, config, date_format).await
+                }
+                LoadType::Categorizing => {
+                    X1337Source::categorize(client, search, config, date_format).await
+                }
+                LoadType::SolvingCaptcha(solution) => {
+                    X1337Source::solve(solution, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            // Custom sources do everything with a single search
+            Sources::Custom(name) => match load_type {
+                LoadType::Searching
+                | LoadType::Sourcing
+                | LoadType::Sorting
+                | LoadType::Filtering
+                | LoadType::Categorizing
+                | LoadType::SolvingCaptcha(_) => {
+                    custom::search(name, client, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+            Sources::Plugin(name) => match load_type {
+                LoadType::Searching
+                | LoadType::Sourcing
+                | LoadType::Sorting
+                | LoadType::Filtering
+                | LoadType::Categorizing => plugin::search(name, search, config, date_format).await,
+                LoadType::SolvingCaptcha(solution) => {
+                    plugin::solve(name, solution, search, config, date_format).await
+                }
+                LoadType::Downloading
+                | LoadType::Batching
+                | LoadType::Details
+                | LoadType::Preview
+                | LoadType::Restoring
+                | LoadType::Redownloading => unreachable!(),
+            },
+        }
+    }
+
+    /// Search a single source. Used by `MultiSource`, which can't call `load`
+    /// for each of its sources without the future containing itself.
+    pub async fn search(
+        &self,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        match self {
+            Sources::Nyaa => NyaaHtmlSource::search(client, search, config, date_format).await,
+            Sources::SukebeiNyaa => {
+                SukebeiHtmlSource::search(client, search, config, date_format).await
+            }
+            Sources::TorrentGalaxy => {
+                TorrentGalaxyHtmlSource::search(client, search, config, date_format).await
+            }
+            Sources::Torznab => TorznabSource::search(client, search, config, date_format).await,
+            Sources::AnimeTosho => {
+                AnimeToshoSource::search(client, search, config, date_format).await
+            }
+            Sources::TokyoTosho => {
+                TokyoToshoSource::search(client, search, config, date_format).await
+            }
+            Sources::AniDex => AniDexSource::search(client, search, config, date_format).await,
+            Sources::X1337 => X1337Source::search(client, search, config, date_format).await,
+            Sources::Custom(name) => {
+                custom::search(name, client, search, config, date_format).await
+            }
+            Sources::Plugin(name) => plugin::search(name, search, config, date_format).await,
+            Sources::Multi => Err("Multi can't include itself".into()),
+        }
+    }
+
+    pub async fn details(
+        &self,
+        client: &reqwest::Client,
+        item: &Item,
+        config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        // Merged results remember the source they came from
+        let src = match self {
+            Sources::Multi => multi::item_source(item).unwrap_or(Sources::Multi),
+            _ => self.clone(),
+        };
+        match src {
+            Sources::Nyaa => NyaaHtmlSource::details(client, item, config).await,
+            Sources::SukebeiNyaa => SukebeiHtmlSource::details(client, item, config).await,
+            Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::details(client, item, config).await,
+            Sources::Torznab => TorznabSource::details(client, item, config).await,
+            Sources::Multi => MultiSource::details(client, item, config).await,
+            Sources::X1337 => X1337Source::details(client, item, config).await,
+            Sources::AniDex => AniDexSource::details(client, item, config).await,
+            Sources::TokyoTosho => TokyoToshoSource::details(client, item, config).await,
+            Sources::AnimeTosho => AnimeToshoSource::details(client, item, config).await,
+            Sources::Custom(name) | Sources::Plugin(name) => {
+                Err(format!("Details are not supported for {}", name).into())
+            }
         }
     }
 
-    pub fn apply(self, ctx: &mut Context, w: &mut Widgets) {
-        ctx.src_info = self.info();
+    /// Resolve the links of each item before downloading it. Items that can't
+    /// be resolved are left out and returned as errors instead.
+    pub async fn resolve(
+        &self,
+        client: &reqwest::Client,
+        items: Vec<Item>,
+        config: &SourceConfig,
+    ) -> (Vec<Item>, Vec<DownloadError>) {
+        let mut resolved = vec![];
+        let mut errors = vec![];
+        for item in items.into_iter() {
+            let src = match self {
+                Sources::Multi => multi::item_source(&item).unwrap_or(Sources::Multi),
+                _ => self.clone(),
+            };
+            let title = item.title.to_owned();
+            let res = match src {
+                Sources::Nyaa => NyaaHtmlSource::resolve(client, item, config).await,
+                Sources::SukebeiNyaa => SukebeiHtmlSource::resolve(client, item, config).await,
+                Sources::TorrentGalaxy => {
+                    TorrentGalaxyHtmlSource::resolve(client, item, config).await
+                }
+                Sources::Torznab => TorznabSource::resolve(client, item, config).await,
+                Sources::Multi => MultiSource::resolve(client, item, config).await,
+                Sources::AniDex => AniDexSource::resolve(client, item, config).await,
+                Sources::TokyoTosho => TokyoToshoSource::resolve(client, item, config).await,
+                Sources::AnimeTosho => AnimeToshoSource::resolve(client, item, config).await,
+                Sources::X1337 => X1337Source::resolve(client, item, config).await,
+                Sources::Custom(_) | Sources::Plugin(_) => Ok(item),
+            };
+            match res {
+                Ok(item) => resolved.push(item),
+                Err(e) => errors.push(DownloadError(format!(
+                    "Failed to get the download links for \"{}\":\n{}",
+                    title, e
+                ))),
+            }
+        }
+        (resolved, errors)
+    }
+
+    pub fn apply(&self, ctx: &mut Context, w: &mut Widgets) {
+        ctx.src_info = self.info(&ctx.config.sources);
         w.category.selected = self.default_category(&ctx.config.sources);
 
         let (major, minor) = ctx.src_info.get_major_minor(w.category.selected);
@@ -305,56 +770,124 @@ impl Sources {
         ctx.page = 1;
     }
 
-    pub fn info(self) -> SourceInfo {
+    pub fn info(&self, config: &SourceConfig) -> SourceInfo {
         match self {
             Sources::Nyaa => NyaaHtmlSource::info(),
             Sources::SukebeiNyaa => SukebeiHtmlSource::info(),
             Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::info(),
+            Sources::Torznab => TorznabSource::info(),
+            Sources::Multi => MultiSource::info(),
+            Sources::X1337 => X1337Source::info(),
+            Sources::AniDex => AniDexSource::info(),
+            Sources::TokyoTosho => TokyoToshoSource::info(),
+            Sources::AnimeTosho => AnimeToshoSource::info(),
+            Sources::Custom(name) => custom::info(name, config),
+            Sources::Plugin(name) => plugin::info(name, config),
         }
     }
 
-    pub fn load_config(self, config: &mut SourceConfig) {
+    /// Whether `info` is ready without asking the source first
+    pub fn info_loaded(&self, config: &SourceConfig) -> bool {
+        match self {
+            Sources::Plugin(name) => plugin::info_loaded(name, config),
+            _ => true,
+        }
+    }
+
+    /// Ask the source for its info, which is needed before `info` or any of
+    /// the defaults are correct
+    pub async fn load_info(
+        &self,
+        config: &SourceConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self {
+            Sources::Plugin(name) => Ok(plugin::load_info(name, config).await?),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn load_config(&self, config: &mut SourceConfig) {
         match self {
             Sources::Nyaa => NyaaHtmlSource::load_config(config),
             Sources::SukebeiNyaa => SukebeiHtmlSource::load_config(config),
             Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::load_config(config),
+            Sources::Torznab => TorznabSource::load_config(config),
+            Sources::Multi => MultiSource::load_config(config),
+            Sources::X1337 => X1337Source::load_config(config),
+            Sources::AniDex => AniDexSource::load_config(config),
+            Sources::TokyoTosho => TokyoToshoSource::load_config(config),
+            Sources::AnimeTosho => AnimeToshoSource::load_config(config),
+            // Custom sources and plugins only exist if they are in the config already
+            Sources::Custom(_) | Sources::Plugin(_) => {}
         };
     }
 
-    pub fn default_category(self, config: &SourceConfig) -> usize {
+    pub fn default_category(&self, config: &SourceConfig) -> usize {
         match self {
             Sources::Nyaa => NyaaHtmlSource::default_category(config),
             Sources::SukebeiNyaa => SukebeiHtmlSource::default_category(config),
             Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::default_category(config),
+            Sources::Torznab => TorznabSource::default_category(config),
+            Sources::Multi => MultiSource::default_category(config),
+            Sources::X1337 => X1337Source::default_category(config),
+            Sources::AniDex => AniDexSource::default_category(config),
+            Sources::TokyoTosho => TokyoToshoSource::default_category(config),
+            Sources::AnimeTosho => AnimeToshoSource::default_category(config),
+            Sources::Custom(name) => custom::default_category(name, config),
+            Sources::Plugin(name) => plugin::default_category(name, config),
         }
     }
 
-    pub fn default_sort(self, config: &SourceConfig) -> SelectedSort {
+    pub fn default_sort(&self, config: &SourceConfig) -> SelectedSort {
         match self {
             Sources::Nyaa => NyaaHtmlSource::default_sort(config),
             Sources::SukebeiNyaa => SukebeiHtmlSource::default_sort(config),
             Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::default_sort(config),
+            Sources::Torznab => TorznabSource::default_sort(config),
+            Sources::Multi => MultiSource::default_sort(config),
+            Sources::X1337 => X1337Source::default_sort(config),
+            Sources::AniDex => AniDexSource::default_sort(config),
+            Sources::TokyoTosho => TokyoToshoSource::default_sort(config),
+            Sources::AnimeTosho => AnimeToshoSource::default_sort(config),
+            Sources::Custom(name) => custom::default_sort(name, config),
+            Sources::Plugin(name) => plugin::default_sort(name, config),
         }
     }
 
-    pub fn default_filter(self, config: &SourceConfig) -> usize {
+    pub fn default_filter(&self, config: &SourceConfig) -> usize {
         match self {
             Sources::Nyaa => NyaaHtmlSource::default_filter(config),
             Sources::SukebeiNyaa => SukebeiHtmlSource::default_filter(config),
             Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::default_filter(config),
+            Sources::Torznab => TorznabSource::default_filter(config),
+            Sources::Multi => MultiSource::default_filter(config),
+            Sources::X1337 => X1337Source::default_filter(config),
+            Sources::AniDex => AniDexSource::default_filter(config),
+            Sources::TokyoTosho => TokyoToshoSource::default_filter(config),
+            Sources::AnimeTosho => AnimeToshoSource::default_filter(config),
+            Sources::Custom(name) => custom::default_filter(name, config),
+            Sources::Plugin(name) => plugin::default_filter(name, config),
         }
     }
 
-    pub fn default_search(self, config: &SourceConfig) -> String {
+    pub fn default_search(&self, config: &SourceConfig) -> String {
         match self {
             Sources::Nyaa => NyaaHtmlSource::default_search(config),
             Sources::SukebeiNyaa => SukebeiHtmlSource::default_search(config),
             Sources::TorrentGalaxy => TorrentGalaxyHtmlSource::default_search(config),
+            Sources::Torznab => TorznabSource::default_search(config),
+            Sources::Multi => MultiSource::default_search(config),
+            Sources::X1337 => X1337Source::default_search(config),
+            Sources::AniDex => AniDexSource::default_search(config),
+            Sources::TokyoTosho => TokyoToshoSource::default_search(config),
+            Sources::AnimeTosho => AnimeToshoSource::default_search(config),
+            Sources::Custom(name) => custom::default_search(name, config),
+            Sources::Plugin(name) => plugin::default_search(name, config),
         }
     }
 
     pub fn format_table(
-        self,
+        &self,
         items: &[Item],
         search: &SearchQuery,
         config: &SourceConfig,
@@ -366,6 +899,14 @@ impl Sources {
             Sources::TorrentGalaxy => {
                 TorrentGalaxyHtmlSource::format_table(items, search, config, theme)
             }
+            Sources::Torznab => TorznabSource::format_table(items, search, config, theme),
+            Sources::Multi => MultiSource::format_table(items, search, config, theme),
+            Sources::X1337 => X1337Source::format_table(items, search, config, theme),
+            Sources::AniDex => AniDexSource::format_table(items, search, config, theme),
+            Sources::TokyoTosho => TokyoToshoSource::format_table(items, search, config, theme),
+            Sources::AnimeTosho => AnimeToshoSource::format_table(items, search, config, theme),
+            Sources::Custom(name) => custom::format_table(name, items, search, config, theme),
+            Sources::Plugin(name) => plugin::format_table(name, items, search, config, theme),
         }
     }
 }
@@ -0,0 +1,438 @@
+use std::{error::Error, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use ratatui::style::Color;
+use reqwest::{StatusCode, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use strum::{Display, FromRepr, VariantArray};
+use urlencoding::encode;
+
+use crate::{
+    cats,
+    results::{ResultResponse, ResultTable},
+    sel,
+    sync::SearchQuery,
+    theme::Theme,
+    util::{
+        conv::{get_hash, to_bytes},
+        html::{as_type, attr, inner},
+    },
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    add_protocol,
+    nyaa_html::{nyaa_table, NyaaColumns},
+    Item, ItemDetails, ItemType, Source, SourceConfig, SourceInfo, SourceResponse,
+};
+
+/// The number of results on each page
+const PAGE_SIZE: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct AniDexTheme {
+    #[serde(rename = "categories")]
+    pub cat: AniDexCategoryTheme,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct AniDexCategoryTheme {
+    #[serde(with = "color_to_tui")]
+    pub anime_sub: Color,
+    #[serde(with = "color_to_tui")]
+    pub anime_raw: Color,
+    #[serde(with = "color_to_tui")]
+    pub anime_dub: Color,
+    #[serde(with = "color_to_tui")]
+    pub live_action: Color,
+    #[serde(with = "color_to_tui")]
+    pub light_novel: Color,
+    #[serde(with = "color_to_tui")]
+    pub manga: Color,
+    #[serde(with = "color_to_tui")]
+    pub music: Color,
+    #[serde(with = "color_to_tui")]
+    pub software: Color,
+    #[serde(with = "color_to_tui")]
+    pub pictures: Color,
+    #[serde(with = "color_to_tui")]
+    pub adult: Color,
+    #[serde(with = "color_to_tui")]
+    pub other: Color,
+}
+
+impl Default for AniDexCategoryTheme {
+    fn default() -> Self {
+        use Color::*;
+        Self {
+            anime_sub: LightMagenta,
+            anime_raw: Gray,
+            anime_dub: LightCyan,
+            live_action: Magenta,
+            light_novel: LightGreen,
+            manga: LightYellow,
+            music: Yellow,
+            software: Blue,
+            pictures: LightBlue,
+            adult: Red,
+            other: DarkGray,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AniDexConfig {
+    pub base_url: String,
+    pub default_sort: AniDexSort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: AniDexFilter,
+    pub default_category: String,
+    pub default_search: String,
+    pub timeout: Option<u64>,
+    pub columns: Option<NyaaColumns>,
+}
+
+impl Default for AniDexConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://anidex.info/".to_owned(),
+            default_sort: AniDexSort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: AniDexFilter::NoFilter,
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            timeout: None,
+            columns: None,
+        }
+    }
+}
+
+// Discriminants line up with `NyaaSort` so results can share `nyaa_table`
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum AniDexSort {
+    #[strum(serialize = "Date")]
+    Date = 0,
+    #[strum(serialize = "Completed")]
+    Completed = 1,
+    #[strum(serialize = "Seeders")]
+    Seeders = 2,
+    #[strum(serialize = "Leechers")]
+    Leechers = 3,
+    #[strum(serialize = "Size")]
+    Size = 4,
+}
+
+impl AniDexSort {
+    pub fn to_url(self) -> &'static str {
+        match self {
+            AniDexSort::Date => "upload_timestamp",
+            AniDexSort::Completed => "completed",
+            AniDexSort::Seeders => "seeders",
+            AniDexSort::Leechers => "leechers",
+            AniDexSort::Size => "size",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum AniDexFilter {
+    #[allow(clippy::enum_variant_names)]
+    #[strum(serialize = "No Filter")]
+    NoFilter = 0,
+    #[strum(serialize = "Authorized Only")]
+    AuthorizedOnly = 1,
+    #[strum(serialize = "Batches Only")]
+    BatchesOnly = 2,
+}
+
+pub struct AniDexSource;
+
+fn get_url(base_url: String, search: &SearchQuery) -> Result<Url, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(base_url, true)?;
+    let sort = AniDexSort::from_repr(search.sort.sort).unwrap_or(AniDexSort::Date);
+    let dir = match search.sort.dir {
+        SortDir::Desc => "desc",
+        SortDir::Asc => "asc",
+    };
+    let mut query = format!(
+        "page=search&q={}&s={}&o={}&offset={}",
+        encode(&search.query),
+        sort.to_url(),
+        dir,
+        search.page.saturating_sub(1) * PAGE_SIZE
+    );
+    if search.category != 0 {
+        query.push_str(&format!("&id={}", search.category));
+    }
+    match AniDexFilter::from_repr(search.filter) {
+        Some(AniDexFilter::AuthorizedOnly) => query.push_str("&a=1"),
+        Some(AniDexFilter::BatchesOnly) => query.push_str("&b=1"),
+        _ => {}
+    }
+    let mut url = base_url.clone();
+    url.set_query(Some(&query));
+    Ok(url)
+}
+
+/// Parse an AniDex search page into items.
+pub fn parse_anidex<S: Source>(
+    html: &str,
+    base_url: &Url,
+    date_format: Option<String>,
+    page: usize,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+
+    let item_sel = &sel!("div#content table tbody tr")?;
+    let cat_sel = &sel!("td:nth-of-type(1) a")?;
+    let title_sel = &sel!("td:nth-of-type(3) span[title]")?;
+    let post_sel = &sel!("td:nth-of-type(3) a")?;
+    let torrent_sel = &sel!("a[href^=\"/dl/\"]")?;
+    let magnet_sel = &sel!("a[href^=\"magnet:\"]")?;
+    let size_sel = &sel!("td:nth-of-type(7)")?;
+    let date_sel = &sel!("td:nth-of-type(8)")?;
+    let seed_sel = &sel!("td:nth-of-type(9)")?;
+    let leech_sel = &sel!("td:nth-of-type(10)")?;
+    let dl_sel = &sel!("td:nth-of-type(11)")?;
+
+    let info = S::info();
+    let items = doc
+        .select(item_sel)
+        .filter_map(|e| {
+            let post_link = base_url.join(&attr(e, post_sel, "href")).ok()?;
+            let id = post_link.path_segments()?.next_back()?.to_owned();
+            id.parse::<usize>().ok()?;
+
+            let cat_id = attr(e, cat_sel, "href")
+                .rsplit_once("id=")
+                .and_then(|(_, c)| c.parse::<usize>().ok())
+                .unwrap_or(16);
+            let cat = info.clone().entry_from_id(cat_id);
+
+            // The full date is in the title, the text is relative like "2 hours ago"
+            let date = attr(e, date_sel, "title");
+            let naive =
+                NaiveDateTime::parse_from_str(date.trim_end_matches(" UTC"), "%Y-%m-%d %H:%M:%S")
+                    .unwrap_or_default();
+            let date: DateTime<Local> = Local.from_utc_datetime(&naive);
+            let date_format = date_format
+                .to_owned()
+                .unwrap_or("%Y-%m-%d %H:%M".to_owned());
+
+            let size = inner(e, size_sel, "0 B").trim().replace('i', "");
+            let torrent_link = base_url
+                .join(&attr(e, torrent_sel, "href"))
+                .map(Into::into)
+                .unwrap_or_default();
+            let magnet_link = attr(e, magnet_sel, "href");
+
+            let mut item = Item {
+                id: format!("anidex-{}", id),
+                date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders: as_type(inner(e, seed_sel, "0")).unwrap_or_default(),
+                leechers: as_type(inner(e, leech_sel, "0")).unwrap_or_default(),
+                downloads: as_type(inner(e, dl_sel, "0")).unwrap_or_default(),
+                bytes: to_bytes(&size),
+                size,
+                title: attr(e, title_sel, "title"),
+                torrent_link,
+                post_link: post_link.to_string(),
+                file_name: format!("{}.torrent", id),
+                category: cat.id,
+                icon: cat.icon,
+                item_type: ItemType::None,
+                ..Default::default()
+            };
+            if let Some(hash) = get_hash(magnet_link.clone()) {
+                item.extra.insert("hash".to_owned(), hash.to_lowercase());
+            }
+            item.magnet_link = magnet_link;
+            Some(item)
+        })
+        .collect::<Vec<Item>>();
+
+    // Only full pages can be followed by another
+    let last_page = match items.len() >= PAGE_SIZE {
+        true => page + 1,
+        false => page.max(1),
+    };
+    Ok(ResultResponse {
+        total_results: (page.max(1) - 1) * PAGE_SIZE + items.len(),
+        items,
+        last_page,
+    })
+}
+
+impl Source for AniDexSource {
+    async fn search(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        let anidex = config.anidex.to_owned().unwrap_or_default();
+        let url = get_url(anidex.base_url, search)?;
+
+        let mut request = client.get(url.to_owned());
+        if let Some(timeout) = anidex.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", url, code).into());
+        }
+        let content = response.text().await?;
+        Ok(SourceResponse::Results(parse_anidex::<Self>(
+            &content,
+            &url,
+            date_format,
+            search.page,
+        )?))
+    }
+    async fn sort(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AniDexSource::search(client, search, config, date_format).await
+    }
+    async fn filter(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AniDexSource::search(client, search, config, date_format).await
+    }
+    async fn categorize(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AniDexSource::search(client, search, config, date_format).await
+    }
+    async fn solve(
+        _solution: String,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AniDexSource::search(client, search, config, date_format).await
+    }
+
+    async fn details(
+        _client: &reqwest::Client,
+        _item: &Item,
+        _config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        Err("Details are not supported for AniDex".into())
+    }
+
+    fn info() -> SourceInfo {
+        let cats = cats! {
+            "All Categories" => {
+                0 => ("---", "All Categories", "AllCategories", fg);
+            }
+            "Anime" => {
+                1 => ("Sub", "Sub", "AnimeSub", source.anidex.cat.anime_sub);
+                2 => ("Raw", "Raw", "AnimeRaw", source.anidex.cat.anime_raw);
+                3 => ("Dub", "Dub", "AnimeDub", source.anidex.cat.anime_dub);
+            }
+            "Live Action" => {
+                4 => ("LSb", "Sub", "LiveActionSub", source.anidex.cat.live_action);
+                5 => ("LRw", "Raw", "LiveActionRaw", source.anidex.cat.live_action);
+            }
+            "Literature" => {
+                6 => ("LN ", "Light Novel", "LightNovel", source.anidex.cat.light_novel);
+                7 => ("Mng", "Manga (Translated)", "MangaTranslated", source.anidex.cat.manga);
+                8 => ("MRw", "Manga (Raw)", "MangaRaw", source.anidex.cat.manga);
+            }
+            "Music" => {
+                9 => ("Lsy", "Lossy", "MusicLossy", source.anidex.cat.music);
+                10 => ("Lsl", "Lossless", "MusicLossless", source.anidex.cat.music);
+                11 => ("MV ", "Video", "MusicVideo", source.anidex.cat.music);
+            }
+            "Software" => {
+                12 => ("Gam", "Games", "Games", source.anidex.cat.software);
+                13 => ("App", "Applications", "Applications", source.anidex.cat.software);
+            }
+            "Other" => {
+                14 => ("Pic", "Pictures", "Pictures", source.anidex.cat.pictures);
+                15 => ("XXX", "Adult Video", "AdultVideo", source.anidex.cat.adult);
+                16 => ("Oth", "Other", "Other", source.anidex.cat.other);
+            }
+        };
+        SourceInfo {
+            cats,
+            filters: AniDexFilter::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sorts: AniDexSort::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn load_config(config: &mut SourceConfig) {
+        if config.anidex.is_none() {
+            config.anidex = Some(AniDexConfig::default());
+        }
+    }
+
+    fn default_category(cfg: &SourceConfig) -> usize {
+        let default = cfg
+            .anidex
+            .as_ref()
+            .map(|c| c.default_category.to_owned())
+            .unwrap_or_default();
+        Self::info().entry_from_cfg(&default).id
+    }
+
+    fn default_sort(cfg: &SourceConfig) -> SelectedSort {
+        cfg.anidex
+            .as_ref()
+            .map(|c| SelectedSort {
+                sort: c.default_sort as usize,
+                dir: c.default_sort_dir,
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_filter(cfg: &SourceConfig) -> usize {
+        cfg.anidex
+            .as_ref()
+            .map(|c| c.default_filter as usize)
+            .unwrap_or_default()
+    }
+
+    fn default_search(cfg: &SourceConfig) -> String {
+        cfg.anidex
+            .as_ref()
+            .map(|c| c.default_search.to_owned())
+            .unwrap_or_default()
+    }
+
+    fn format_table(
+        items: &[Item],
+        search: &SearchQuery,
+        config: &SourceConfig,
+        theme: &Theme,
+    ) -> ResultTable {
+        let anidex = config.anidex.to_owned().unwrap_or_default();
+        nyaa_table(items.into(), theme, &search.sort, &anidex.columns)
+    }
+}
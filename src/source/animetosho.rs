@@ -0,0 +1,616 @@
+use std::{
+    cmp::{max, Ordering},
+    collections::HashMap,
+    error::Error,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use human_bytes::human_bytes;
+use ratatui::{
+    layout::{Alignment, Constraint},
+    style::{Color, Stylize as _},
+};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use strum::{Display, FromRepr, VariantArray};
+use urlencoding::encode;
+
+use crate::{
+    cats, cond_vec,
+    results::{ResultColumn, ResultHeader, ResultResponse, ResultRow, ResultTable},
+    sync::SearchQuery,
+    theme::Theme,
+    util::conv::shorten_number,
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    add_protocol, Item, ItemDetails, ItemFile, ItemType, Source, SourceConfig, SourceInfo,
+    SourceResponse,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct AnimeToshoTheme {
+    #[serde(rename = "categories")]
+    pub cat: AnimeToshoCategoryTheme,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct AnimeToshoCategoryTheme {
+    #[serde(with = "color_to_tui")]
+    pub nyaa: Color,
+    #[serde(with = "color_to_tui")]
+    pub tokyotosho: Color,
+    #[serde(with = "color_to_tui")]
+    pub anidex: Color,
+    #[serde(with = "color_to_tui")]
+    pub other: Color,
+}
+
+impl Default for AnimeToshoCategoryTheme {
+    fn default() -> Self {
+        use Color::*;
+        Self {
+            nyaa: LightMagenta,
+            tokyotosho: LightCyan,
+            anidex: LightGreen,
+            other: Gray,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AnimeToshoConfig {
+    pub base_url: String,
+    pub default_sort: AnimeToshoSort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: AnimeToshoFilter,
+    pub default_category: String,
+    pub default_search: String,
+    pub timeout: Option<u64>,
+    pub columns: Option<AnimeToshoColumns>,
+}
+
+impl Default for AnimeToshoConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://feed.animetosho.org/".to_owned(),
+            default_sort: AnimeToshoSort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: AnimeToshoFilter::NoFilter,
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            timeout: None,
+            columns: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Default)]
+pub struct AnimeToshoColumns {
+    category: Option<bool>,
+    title: Option<bool>,
+    nyaa: Option<bool>,
+    files: Option<bool>,
+    size: Option<bool>,
+    date: Option<bool>,
+    seeders: Option<bool>,
+    leechers: Option<bool>,
+    downloads: Option<bool>,
+}
+
+impl AnimeToshoColumns {
+    fn array(self) -> [bool; 9] {
+        [
+            self.category.unwrap_or(true),
+            self.title.unwrap_or(true),
+            self.nyaa.unwrap_or(true),
+            self.files.unwrap_or(true),
+            self.size.unwrap_or(true),
+            self.date.unwrap_or(true),
+            self.seeders.unwrap_or(true),
+            self.leechers.unwrap_or(true),
+            self.downloads.unwrap_or(true),
+        ]
+    }
+}
+
+// Discriminants line up with `NyaaSort` so the table headers match the other sources
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum AnimeToshoSort {
+    #[strum(serialize = "Date")]
+    Date = 0,
+    #[strum(serialize = "Downloads")]
+    Downloads = 1,
+    #[strum(serialize = "Seeders")]
+    Seeders = 2,
+    #[strum(serialize = "Leechers")]
+    Leechers = 3,
+    #[strum(serialize = "Size")]
+    Size = 4,
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum AnimeToshoFilter {
+    #[allow(clippy::enum_variant_names)]
+    #[strum(serialize = "No Filter")]
+    NoFilter = 0,
+    #[strum(serialize = "No Dead Torrents")]
+    NoDead = 1,
+    #[strum(serialize = "NZB Only")]
+    NzbOnly = 2,
+}
+
+pub struct AnimeToshoSource;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct AnimeToshoItem {
+    id: u64,
+    title: String,
+    link: String,
+    timestamp: i64,
+    nyaa_id: Option<u64>,
+    tosho_id: Option<u64>,
+    anidex_id: Option<u64>,
+    torrent_url: Option<String>,
+    torrent_name: Option<String>,
+    info_hash: Option<String>,
+    magnet_uri: Option<String>,
+    seeders: Option<u32>,
+    leechers: Option<u32>,
+    torrent_downloaded_count: Option<u32>,
+    nzb_url: Option<String>,
+    total_size: usize,
+    num_files: usize,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct AnimeToshoTorrent {
+    title: String,
+    link: String,
+    timestamp: i64,
+    nyaa_id: Option<u64>,
+    info_hash: Option<String>,
+    nzb_url: Option<String>,
+    torrent_url: Option<String>,
+    total_size: usize,
+    files: Vec<AnimeToshoFile>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct AnimeToshoFile {
+    filename: String,
+    size: usize,
+    /// Subtitles, fonts and chapters extracted from the file
+    attachments: Vec<serde_json::Value>,
+}
+
+fn size_str(bytes: usize) -> String {
+    human_bytes(bytes as f64).replace('i', "")
+}
+
+fn nyaa_link(id: u64) -> String {
+    format!("https://nyaa.si/view/{}", id)
+}
+
+fn json_url(base_url: String) -> Result<Url, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(base_url, false)?;
+    // Allow pointing directly at the `json` endpoint
+    if base_url.path().trim_end_matches('/').ends_with("/json") {
+        return Ok(base_url);
+    }
+    Ok(base_url.join("json")?)
+}
+
+/// Parse the body of an AnimeTosho `json` feed response into items. The feed
+/// doesn't return a total, so there is always another page until one is empty.
+pub fn parse_animetosho<S: Source>(
+    bytes: &[u8],
+    date_format: Option<String>,
+    page: usize,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    let res: Vec<AnimeToshoItem> = serde_json::from_slice(bytes)?;
+
+    let info = S::info();
+    let items: Vec<Item> = res
+        .into_iter()
+        .map(|item| {
+            // AnimeTosho mirrors several trackers, so the category is where the torrent came from
+            let cat_id = match (item.nyaa_id, item.tosho_id, item.anidex_id) {
+                (Some(_), _, _) => 1,
+                (None, Some(_), _) => 2,
+                (None, None, Some(_)) => 3,
+                _ => 4,
+            };
+            let cat = info.clone().entry_from_id(cat_id);
+
+            let date = DateTime::from_timestamp(item.timestamp, 0).unwrap_or_default();
+            let date = date.with_timezone(&Local);
+            let date_format = date_format
+                .to_owned()
+                .unwrap_or("%Y-%m-%d %H:%M".to_owned());
+
+            let hash = item
+                .info_hash
+                .filter(|h| !h.is_empty())
+                .map(|h| h.to_lowercase());
+            let magnet_link = match (item.magnet_uri, hash.as_ref()) {
+                (Some(magnet), _) => magnet,
+                (None, Some(hash)) => {
+                    format!("magnet:?xt=urn:btih:{}&dn={}", hash, encode(&item.title))
+                }
+                (None, None) => String::new(),
+            };
+            let file_name = item
+                .torrent_name
+                .filter(|n| n.ends_with(".torrent"))
+                .unwrap_or(format!(
+                    "{}.torrent",
+                    hash.clone().unwrap_or(item.id.to_string())
+                ));
+
+            let mut extra: HashMap<String, String> = HashMap::new();
+            extra.insert("files".to_owned(), item.num_files.to_string());
+            if let Some(hash) = hash {
+                extra.insert("hash".to_owned(), hash);
+            }
+            if let Some(nyaa_id) = item.nyaa_id {
+                extra.insert("nyaa_id".to_owned(), nyaa_id.to_string());
+                extra.insert("nyaa_link".to_owned(), nyaa_link(nyaa_id));
+            }
+            if let Some(nzb) = item.nzb_url {
+                extra.insert("nzb".to_owned(), nzb);
+            }
+
+            Item {
+                id: format!("animetosho-{}", item.id),
+                date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders: item.seeders.unwrap_or_default(),
+                leechers: item.leechers.unwrap_or_default(),
+                downloads: item.torrent_downloaded_count.unwrap_or_default(),
+                size: size_str(item.total_size),
+                bytes: item.total_size,
+                title: item.title,
+                torrent_link: item.torrent_url.unwrap_or_default(),
+                magnet_link,
+                post_link: item.link,
+                file_name,
+                category: cat.id,
+                icon: cat.icon,
+                item_type: ItemType::None,
+                extra,
+            }
+        })
+        .collect();
+
+    let last_page = match items.is_empty() {
+        true => page.max(1),
+        false => page + 1,
+    };
+    Ok(ResultResponse {
+        total_results: items.len(),
+        items,
+        last_page,
+    })
+}
+
+/// Parse the body of an AnimeTosho `show=torrent` response into details.
+pub fn parse_animetosho_details(
+    bytes: &[u8],
+    date_format: Option<String>,
+) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+    let torrent: AnimeToshoTorrent = serde_json::from_slice(bytes)?;
+
+    let date = DateTime::from_timestamp(torrent.timestamp, 0).unwrap_or_default();
+    let date_format = date_format.unwrap_or("%Y-%m-%d %H:%M".to_owned());
+
+    let attachments: usize = torrent.files.iter().map(|f| f.attachments.len()).sum();
+    let links = [
+        ("AnimeTosho", Some(torrent.link)),
+        ("Nyaa", torrent.nyaa_id.map(nyaa_link)),
+        ("Torrent", torrent.torrent_url),
+        ("NZB", torrent.nzb_url),
+    ];
+    let mut description = links
+        .into_iter()
+        .filter_map(|(name, link)| Some(format!("{}: {}", name, link.filter(|l| !l.is_empty())?)))
+        .collect::<Vec<String>>();
+    if attachments > 0 {
+        description.push(format!("Attachments: {}", attachments));
+    }
+
+    Ok(ItemDetails {
+        title: torrent.title,
+        submitter: String::new(),
+        date: date.with_timezone(&Local).format(&date_format).to_string(),
+        size: size_str(torrent.total_size),
+        info_hash: torrent.info_hash.unwrap_or_default().to_lowercase(),
+        description: description.join("\n"),
+        files: torrent
+            .files
+            .into_iter()
+            .map(|f| ItemFile {
+                path: f.filename,
+                size: size_str(f.size),
+            })
+            .collect(),
+        comments: 0,
+    })
+}
+
+fn sort_items(items: &mut [Item], sort: SelectedSort) {
+    let f: fn(&Item, &Item) -> Ordering = match AnimeToshoSort::from_repr(sort.sort) {
+        Some(AnimeToshoSort::Downloads) => |a, b| b.downloads.cmp(&a.downloads),
+        Some(AnimeToshoSort::Seeders) => |a, b| b.seeders.cmp(&a.seeders),
+        Some(AnimeToshoSort::Leechers) => |a, b| b.leechers.cmp(&a.leechers),
+        Some(AnimeToshoSort::Size) => |a, b| b.bytes.cmp(&a.bytes),
+        _ => |a, b| b.timestamp.cmp(&a.timestamp),
+    };
+    items.sort_by(f);
+    if sort.dir == SortDir::Asc {
+        items.reverse();
+    }
+}
+
+fn filter_items(items: &mut Vec<Item>, filter: usize, category: usize) {
+    if category != 0 {
+        items.retain(|i| i.category == category);
+    }
+    match AnimeToshoFilter::from_repr(filter) {
+        Some(AnimeToshoFilter::NoDead) => items.retain(|i| i.seeders > 0),
+        Some(AnimeToshoFilter::NzbOnly) => items.retain(|i| i.extra.contains_key("nzb")),
+        _ => {}
+    }
+}
+
+impl Source for AnimeToshoSource {
+    async fn search(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        let at = config.animetosho.to_owned().unwrap_or_default();
+        let mut url = json_url(at.base_url)?;
+        let query = format!("q={}&page={}", encode(&search.query), search.page.max(1));
+        url.set_query(Some(&query));
+
+        let mut request = client.get(url.to_owned());
+        if let Some(timeout) = at.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", url, code).into());
+        }
+        let bytes = response.bytes().await?;
+
+        let mut res = parse_animetosho::<Self>(&bytes[..], date_format, search.page)?;
+        // The feed can't filter or sort, so both are applied to the page locally
+        filter_items(&mut res.items, search.filter, search.category);
+        sort_items(&mut res.items, search.sort);
+        Ok(SourceResponse::Results(res))
+    }
+    async fn sort(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AnimeToshoSource::search(client, search, config, date_format).await
+    }
+    async fn filter(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AnimeToshoSource::search(client, search, config, date_format).await
+    }
+    async fn categorize(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AnimeToshoSource::search(client, search, config, date_format).await
+    }
+    async fn solve(
+        _solution: String,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        AnimeToshoSource::search(client, search, config, date_format).await
+    }
+
+    async fn details(
+        client: &reqwest::Client,
+        item: &Item,
+        config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        let at = config.animetosho.to_owned().unwrap_or_default();
+        let Some(id) = item.id.strip_prefix("animetosho-") else {
+            return Err(format!("\"{}\" is not an AnimeTosho item", item.title).into());
+        };
+        let mut url = json_url(at.base_url)?;
+        url.set_query(Some(&format!("show=torrent&id={}", id)));
+
+        let mut request = client.get(url.to_owned());
+        if let Some(timeout) = at.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", url, code).into());
+        }
+        parse_animetosho_details(&response.bytes().await?, None)
+    }
+
+    fn info() -> SourceInfo {
+        let cats = cats! {
+            "All Categories" => {
+                0 => ("---", "All Categories", "AllCategories", fg);
+            }
+            "Mirrored From" => {
+                1 => ("Nya", "Nyaa", "Nyaa", source.animetosho.cat.nyaa);
+                2 => ("TT ", "TokyoTosho", "TokyoTosho", source.animetosho.cat.tokyotosho);
+                3 => ("ADx", "AniDex", "AniDex", source.animetosho.cat.anidex);
+                4 => ("Oth", "Other", "Other", source.animetosho.cat.other);
+            }
+        };
+        SourceInfo {
+            cats,
+            filters: AnimeToshoFilter::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sorts: AnimeToshoSort::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn load_config(config: &mut SourceConfig) {
+        if config.animetosho.is_none() {
+            config.animetosho = Some(AnimeToshoConfig::default());
+        }
+    }
+
+    fn default_category(cfg: &SourceConfig) -> usize {
+        let default = cfg
+            .animetosho
+            .as_ref()
+            .map(|c| c.default_category.to_owned())
+            .unwrap_or_default();
+        Self::info().entry_from_cfg(&default).id
+    }
+
+    fn default_sort(cfg: &SourceConfig) -> SelectedSort {
+        cfg.animetosho
+            .as_ref()
+            .map(|c| SelectedSort {
+                sort: c.default_sort as usize,
+                dir: c.default_sort_dir,
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_filter(cfg: &SourceConfig) -> usize {
+        cfg.animetosho
+            .as_ref()
+            .map(|c| c.default_filter as usize)
+            .unwrap_or_default()
+    }
+
+    fn default_search(cfg: &SourceConfig) -> String {
+        cfg.animetosho
+            .as_ref()
+            .map(|c| c.default_search.to_owned())
+            .unwrap_or_default()
+    }
+
+    fn format_table(
+        items: &[Item],
+        search: &SearchQuery,
+        config: &SourceConfig,
+        theme: &Theme,
+    ) -> ResultTable {
+        let at = config.animetosho.to_owned().unwrap_or_default();
+        let raw_date_width = items.iter().map(|i| i.date.len()).max().unwrap_or_default() as u16;
+        let date_width = max(raw_date_width, 6);
+        let raw_nyaa_width = items
+            .iter()
+            .map(|i| i.extra.get("nyaa_id").map(|n| n.len()).unwrap_or(0))
+            .max()
+            .unwrap_or_default() as u16;
+        let nyaa_width = max(raw_nyaa_width, 4);
+
+        let header = ResultHeader::new([
+            ResultColumn::Normal("Cat".to_owned(), Constraint::Length(3)),
+            ResultColumn::Normal("Name".to_owned(), Constraint::Min(3)),
+            ResultColumn::Normal("Nyaa".to_owned(), Constraint::Length(nyaa_width)),
+            ResultColumn::Normal("Files".to_owned(), Constraint::Length(5)),
+            ResultColumn::Sorted("Size".to_owned(), 9, AnimeToshoSort::Size as u32),
+            ResultColumn::Sorted("Date".to_owned(), date_width, AnimeToshoSort::Date as u32),
+            ResultColumn::Sorted("".to_owned(), 4, AnimeToshoSort::Seeders as u32),
+            ResultColumn::Sorted("".to_owned(), 4, AnimeToshoSort::Leechers as u32),
+            ResultColumn::Sorted("".to_owned(), 5, AnimeToshoSort::Downloads as u32),
+        ]);
+        let mut binding = header.get_binding();
+        let align = [
+            Alignment::Left,
+            Alignment::Left,
+            Alignment::Left,
+            Alignment::Right,
+            Alignment::Right,
+            Alignment::Left,
+            Alignment::Right,
+            Alignment::Right,
+            Alignment::Left,
+        ];
+        let mut rows: Vec<ResultRow> = items
+            .iter()
+            .map(|item| {
+                ResultRow::new([
+                    item.icon.label.to_owned().fg((item.icon.color)(theme)),
+                    item.title.to_owned().fg(theme.fg),
+                    item.extra
+                        .get("nyaa_id")
+                        .cloned()
+                        .unwrap_or_default()
+                        .fg(theme.fg),
+                    item.extra
+                        .get("files")
+                        .cloned()
+                        .unwrap_or_default()
+                        .fg(theme.fg),
+                    item.size.clone().fg(theme.fg),
+                    item.date.clone().fg(theme.fg),
+                    item.seeders.to_string().fg(theme.success),
+                    item.leechers.to_string().fg(theme.error),
+                    shorten_number(item.downloads).fg(theme.fg),
+                ])
+                .aligned(align)
+                .fg(theme.fg)
+            })
+            .collect();
+        let mut headers = header.get_row(search.sort.dir, search.sort.sort as u32);
+        if let Some(columns) = at.columns {
+            let cols = columns.array();
+
+            headers.cells = cond_vec!(cols ; headers.cells);
+            rows = rows
+                .clone()
+                .into_iter()
+                .map(|mut r| {
+                    r.cells = cond_vec!(cols ; r.cells.to_owned());
+                    r
+                })
+                .collect::<Vec<ResultRow>>();
+            binding = cond_vec!(cols ; binding);
+        }
+
+        ResultTable {
+            headers,
+            rows,
+            binding,
+        }
+    }
+}
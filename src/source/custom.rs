@@ -0,0 +1,591 @@
+use std::{error::Error, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use ratatui::style::Color;
+use regex::Regex;
+use reqwest::{StatusCode, Url};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use strum::VariantArray;
+use urlencoding::encode;
+
+use crate::{
+    results::{ResultResponse, ResultTable},
+    sel,
+    sync::SearchQuery,
+    theme::Theme,
+    util::conv::{add_protocol, get_hash, to_bytes},
+    widget::{
+        category::{CatEntry, CatStruct},
+        sort::{SelectedSort, SortDir},
+    },
+};
+
+use super::{
+    nyaa_html::{nyaa_table, NyaaColumns, NyaaSort},
+    Item, ItemType, SourceConfig, SourceInfo, SourceResponse,
+};
+
+/// A source scraped using the selectors in its `[source.custom.<name>]` table.
+/// The defaults match Nyaa, so a clone of Nyaa only needs its `base_url`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CustomConfig {
+    pub base_url: String,
+    pub search_url: String,
+    pub default_sort: NyaaSort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: String,
+    pub default_category: String,
+    pub default_search: String,
+    pub date_format: String,
+    pub timeout: Option<u64>,
+    pub all_categories: String,
+    pub categories: Vec<CustomCategory>,
+    pub filters: Vec<CustomFilter>,
+    pub sorts: CustomSorts,
+    pub selectors: CustomSelectors,
+    pub columns: Option<NyaaColumns>,
+}
+
+impl Default for CustomConfig {
+    fn default() -> Self {
+        Self {
+            base_url: Default::default(),
+            search_url: "?q={query}&c={category}&f={filter}&p={page}&s={sort}&o={dir}".to_owned(),
+            default_sort: NyaaSort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: Default::default(),
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            date_format: "%Y-%m-%d %H:%M".to_owned(),
+            timeout: None,
+            all_categories: "0_0".to_owned(),
+            categories: vec![],
+            filters: vec![
+                CustomFilter::new("No Filter", "0"),
+                CustomFilter::new("No Remakes", "1"),
+                CustomFilter::new("Trusted Only", "2"),
+            ],
+            sorts: CustomSorts::default(),
+            selectors: CustomSelectors::default(),
+            columns: None,
+        }
+    }
+}
+
+/// A group of categories, shown as a heading in the category popup
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CustomCategory {
+    pub name: String,
+    pub entries: Vec<CustomCategoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CustomCategoryEntry {
+    pub name: String,
+    /// Used for `{category}` in the search url, and to find the category of each result
+    pub value: String,
+    pub label: String,
+    pub color: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CustomFilter {
+    pub name: String,
+    pub value: String,
+}
+
+impl CustomFilter {
+    fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+}
+
+/// The value of `{sort}` in the search url for each sort
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CustomSorts {
+    pub date: String,
+    pub downloads: String,
+    pub seeders: String,
+    pub leechers: String,
+    pub size: String,
+}
+
+impl Default for CustomSorts {
+    fn default() -> Self {
+        Self {
+            date: NyaaSort::Date.to_url(),
+            downloads: NyaaSort::Downloads.to_url(),
+            seeders: NyaaSort::Seeders.to_url(),
+            leechers: NyaaSort::Leechers.to_url(),
+            size: NyaaSort::Size.to_url(),
+        }
+    }
+}
+
+impl CustomSorts {
+    fn get(&self, sort: usize) -> &str {
+        match NyaaSort::from_repr(sort) {
+            Some(NyaaSort::Downloads) => &self.downloads,
+            Some(NyaaSort::Seeders) => &self.seeders,
+            Some(NyaaSort::Leechers) => &self.leechers,
+            Some(NyaaSort::Size) => &self.size,
+            _ => &self.date,
+        }
+    }
+}
+
+/// Where to find a field of an item within its row. Either a CSS selector to
+/// take the text of, or a table to take an attribute instead and keep only the
+/// first group matched by `pattern`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum FieldSelector {
+    Text(String),
+    Full {
+        selector: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        attr: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+}
+
+impl FieldSelector {
+    fn attr(selector: &str, attr: &str) -> Self {
+        FieldSelector::Full {
+            selector: selector.to_owned(),
+            attr: Some(attr.to_owned()),
+            pattern: None,
+        }
+    }
+
+    fn selector(&self) -> &str {
+        match self {
+            FieldSelector::Text(selector) => selector,
+            FieldSelector::Full { selector, .. } => selector,
+        }
+    }
+
+    fn compile(&self) -> Result<Option<CompiledField>, String> {
+        // An empty selector means the site doesn't have this field
+        if self.selector().is_empty() {
+            return Ok(None);
+        }
+        let (attr, pattern) = match self {
+            FieldSelector::Text(_) => (None, None),
+            FieldSelector::Full { attr, pattern, .. } => (attr.to_owned(), pattern.as_deref()),
+        };
+        let pattern = match pattern {
+            Some(p) => {
+                Some(Regex::new(p).map_err(|e| format!("Invalid pattern \"{}\":\n{}", p, e))?)
+            }
+            None => None,
+        };
+        Ok(Some(CompiledField {
+            selector: sel!(self.selector())
+                .map_err(|e| format!("Invalid selector \"{}\":\n{}", self.selector(), e))?,
+            attr,
+            pattern,
+        }))
+    }
+}
+
+struct CompiledField {
+    selector: Selector,
+    attr: Option<String>,
+    pattern: Option<Regex>,
+}
+
+impl CompiledField {
+    fn extract(&self, el: ElementRef) -> Option<String> {
+        let value = match self.attr.as_ref() {
+            Some(attr) => el.value().attr(attr)?.to_owned(),
+            None => el.text().collect::<String>(),
+        };
+        let value = value.trim();
+        match self.pattern.as_ref() {
+            Some(pattern) => {
+                let caps = pattern.captures(value)?;
+                caps.get(1).or(caps.get(0)).map(|m| m.as_str().to_owned())
+            }
+            None => Some(value.to_owned()),
+        }
+    }
+
+    /// The value of the first match within a row
+    fn get(&self, e: ElementRef) -> Option<String> {
+        self.extract(e.select(&self.selector).next()?)
+    }
+
+    /// The values of every match in the whole page
+    fn all(&self, doc: &Html) -> Vec<String> {
+        doc.select(&self.selector)
+            .filter_map(|el| self.extract(el))
+            .collect()
+    }
+}
+
+/// The same selectors used for Nyaa in `nyaa_html.rs`
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CustomSelectors {
+    pub rows: String,
+    pub title: FieldSelector,
+    pub post_link: FieldSelector,
+    pub torrent_link: FieldSelector,
+    pub magnet_link: FieldSelector,
+    pub size: FieldSelector,
+    pub date: FieldSelector,
+    pub seeders: FieldSelector,
+    pub leechers: FieldSelector,
+    pub downloads: FieldSelector,
+    pub category: FieldSelector,
+    pub pagination: FieldSelector,
+}
+
+impl Default for CustomSelectors {
+    fn default() -> Self {
+        Self {
+            rows: "table.torrent-list > tbody > tr".to_owned(),
+            title: FieldSelector::attr("td:nth-of-type(2) > a:last-of-type", "title"),
+            post_link: FieldSelector::attr("td:nth-of-type(2) > a:last-of-type", "href"),
+            torrent_link: FieldSelector::attr("td:nth-of-type(3) > a:nth-of-type(1)", "href"),
+            magnet_link: FieldSelector::attr("td:nth-of-type(3) > a:nth-of-type(2)", "href"),
+            size: FieldSelector::Text("td:nth-of-type(4)".to_owned()),
+            date: FieldSelector::Text("td:nth-of-type(5)".to_owned()),
+            seeders: FieldSelector::Text("td:nth-of-type(6)".to_owned()),
+            leechers: FieldSelector::Text("td:nth-of-type(7)".to_owned()),
+            downloads: FieldSelector::Text("td:nth-of-type(8)".to_owned()),
+            category: FieldSelector::Full {
+                selector: "td:first-of-type > a".to_owned(),
+                attr: Some("href".to_owned()),
+                pattern: Some("c=([^&]+)".to_owned()),
+            },
+            pagination: FieldSelector::Text("ul.pagination > li > a".to_owned()),
+        }
+    }
+}
+
+/// Custom categories can't have their own theme, so only the named colors are allowed
+fn category_color(color: &str) -> fn(&Theme) -> Color {
+    match color.to_lowercase().as_str() {
+        "black" => |_| Color::Black,
+        "red" => |_| Color::Red,
+        "green" => |_| Color::Green,
+        "yellow" => |_| Color::Yellow,
+        "blue" => |_| Color::Blue,
+        "magenta" => |_| Color::Magenta,
+        "cyan" => |_| Color::Cyan,
+        "gray" => |_| Color::Gray,
+        "darkgray" => |_| Color::DarkGray,
+        "lightred" => |_| Color::LightRed,
+        "lightgreen" => |_| Color::LightGreen,
+        "lightyellow" => |_| Color::LightYellow,
+        "lightblue" => |_| Color::LightBlue,
+        "lightmagenta" => |_| Color::LightMagenta,
+        "lightcyan" => |_| Color::LightCyan,
+        "white" => |_| Color::White,
+        _ => |t| t.fg,
+    }
+}
+
+fn config_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+fn get_config(name: &str, config: &SourceConfig) -> Result<CustomConfig, String> {
+    config
+        .custom
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("No custom source named \"{}\" in [source.custom]", name))
+}
+
+/// Categories are numbered in the order they are listed, after "All Categories"
+pub fn category_value(categories: &[CustomCategory], id: usize) -> Option<String> {
+    categories
+        .iter()
+        .flat_map(|c| c.entries.iter())
+        .nth(id.wrapping_sub(1))
+        .map(|e| e.value.to_owned())
+}
+
+/// The id of the category with `value`, or "All Categories" if there isn't one
+pub fn category_id(categories: &[CustomCategory], value: &str) -> usize {
+    categories
+        .iter()
+        .flat_map(|c| c.entries.iter())
+        .position(|ent| ent.value == value)
+        .map(|i| i + 1)
+        .unwrap_or_default()
+}
+
+/// Build the category popup from the groups declared by the user
+pub fn category_structs(categories: &[CustomCategory]) -> Vec<CatStruct> {
+    let mut cats = vec![CatStruct {
+        name: "All Categories".to_owned(),
+        entries: vec![CatEntry::new(
+            "All Categories",
+            "AllCategories",
+            0,
+            "---",
+            |t: &Theme| t.fg,
+        )],
+    }];
+    let mut id = 0;
+    for cat in categories.iter() {
+        cats.push(CatStruct {
+            name: cat.name.to_owned(),
+            entries: cat
+                .entries
+                .iter()
+                .map(|ent| {
+                    id += 1;
+                    let label = ent.label.chars().take(3).collect::<String>();
+                    CatEntry::new(
+                        &ent.name,
+                        &config_name(&ent.name),
+                        id,
+                        &format!("{:<3}", label),
+                        category_color(&ent.color),
+                    )
+                })
+                .collect(),
+        });
+    }
+    cats
+}
+
+fn get_url(cfg: &CustomConfig, search: &SearchQuery) -> Result<Url, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(cfg.base_url.to_owned(), true)?;
+    let filter = cfg
+        .filters
+        .get(search.filter)
+        .map(|f| f.value.to_owned())
+        .unwrap_or_default();
+    let path = cfg
+        .search_url
+        .replace("{query}", &encode(&search.query))
+        .replace("{page}", &search.page.max(1).to_string())
+        .replace("{sort}", cfg.sorts.get(search.sort.sort))
+        .replace("{dir}", &search.sort.dir.to_url())
+        .replace(
+            "{category}",
+            &category_value(&cfg.categories, search.category)
+                .unwrap_or(cfg.all_categories.to_owned()),
+        )
+        .replace("{filter}", &filter);
+    Ok(base_url.join(&path)?)
+}
+
+/// Dates are either a unix timestamp or in the configured `date_format`
+fn parse_date(date: &str, format: &str) -> DateTime<Local> {
+    match date.parse::<i64>() {
+        Ok(timestamp) => DateTime::from_timestamp(timestamp, 0)
+            .unwrap_or_default()
+            .with_timezone(&Local),
+        Err(_) => {
+            let naive = NaiveDateTime::parse_from_str(date, format).unwrap_or_default();
+            Local.from_utc_datetime(&naive)
+        }
+    }
+}
+
+/// Parse a search page of the custom source `name` using its selectors
+pub fn parse_custom(
+    name: &str,
+    cfg: &CustomConfig,
+    html: &str,
+    url: &Url,
+    date_format: Option<String>,
+    page: usize,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+    let s = &cfg.selectors;
+    let row_sel =
+        &sel!(s.rows.as_str()).map_err(|e| format!("Invalid selector \"{}\":\n{}", s.rows, e))?;
+    let title = s.title.compile()?;
+    let post_link = s.post_link.compile()?;
+    let torrent_link = s.torrent_link.compile()?;
+    let magnet_link = s.magnet_link.compile()?;
+    let size = s.size.compile()?;
+    let date = s.date.compile()?;
+    let seeders = s.seeders.compile()?;
+    let leechers = s.leechers.compile()?;
+    let downloads = s.downloads.compile()?;
+    let category = s.category.compile()?;
+    let pagination = s.pagination.compile()?;
+
+    let field = |f: &Option<CompiledField>, e: ElementRef| {
+        f.as_ref().and_then(|f| f.get(e)).unwrap_or_default()
+    };
+    let number = |f: &Option<CompiledField>, e: ElementRef| {
+        field(f, e)
+            .replace(',', "")
+            .parse::<u32>()
+            .unwrap_or_default()
+    };
+    let link = |f: &Option<CompiledField>, e: ElementRef| {
+        let link = field(f, e);
+        match link.is_empty() || link.starts_with("magnet:") {
+            true => link,
+            false => url.join(&link).map(Into::into).unwrap_or(link),
+        }
+    };
+
+    let info = cfg_info(cfg);
+    let date_format = date_format.unwrap_or("%Y-%m-%d %H:%M".to_owned());
+    let items = doc
+        .select(row_sel)
+        .filter_map(|e| {
+            let title = field(&title, e);
+            if title.is_empty() {
+                return None;
+            }
+            let post_link = link(&post_link, e);
+            let torrent_link = link(&torrent_link, e);
+            let magnet_link = link(&magnet_link, e);
+
+            // The last part of the post or torrent link is usually the id
+            let id = [&post_link, &torrent_link]
+                .into_iter()
+                .filter_map(|l| Url::parse(l).ok())
+                .find_map(|u| {
+                    u.path_segments()?
+                        .rfind(|s| !s.is_empty())
+                        .map(|s| s.split('.').next().unwrap_or(s).to_owned())
+                })
+                .unwrap_or(title.to_owned());
+
+            let cat_id = category_id(&cfg.categories, &field(&category, e));
+            let cat = info.clone().entry_from_id(cat_id);
+
+            let size = field(&size, e).replace('i', "").replace("Bytes", "B");
+            let date = parse_date(&field(&date, e), &cfg.date_format);
+
+            let mut item = Item {
+                id: format!("{}-{}", name, id),
+                date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders: number(&seeders, e),
+                leechers: number(&leechers, e),
+                downloads: number(&downloads, e),
+                bytes: to_bytes(&size),
+                size,
+                title,
+                torrent_link,
+                post_link,
+                file_name: format!("{}.torrent", id),
+                category: cat.id,
+                icon: cat.icon,
+                item_type: ItemType::None,
+                ..Default::default()
+            };
+            if let Some(hash) = get_hash(format!("{}&", magnet_link)) {
+                item.extra.insert("hash".to_owned(), hash.to_lowercase());
+            }
+            item.magnet_link = magnet_link;
+            Some(item)
+        })
+        .collect::<Vec<Item>>();
+
+    let last_page = pagination
+        .map(|p| p.all(&doc))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|p| p.parse::<usize>().ok())
+        .max()
+        .unwrap_or_default()
+        .max(page.max(1));
+    Ok(ResultResponse {
+        total_results: items.len(),
+        items,
+        last_page,
+    })
+}
+
+pub async fn search(
+    name: &str,
+    client: &reqwest::Client,
+    search: &SearchQuery,
+    config: &SourceConfig,
+    date_format: Option<String>,
+) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+    let cfg = get_config(name, config)?;
+    let url = get_url(&cfg, search)?;
+
+    let mut request = client.get(url.to_owned());
+    if let Some(timeout) = cfg.timeout {
+        request = request.timeout(Duration::from_secs(timeout));
+    }
+    let response = request.send().await?;
+    if response.status() != StatusCode::OK {
+        // Throw error if response code is not OK
+        let code = response.status().as_u16();
+        return Err(format!("{}\nInvalid response code: {}", url, code).into());
+    }
+    let content = response.text().await?;
+
+    let res = parse_custom(name, &cfg, &content, &url, date_format, search.page)?;
+    Ok(SourceResponse::Results(res))
+}
+
+fn cfg_info(cfg: &CustomConfig) -> SourceInfo {
+    SourceInfo {
+        cats: category_structs(&cfg.categories),
+        filters: cfg.filters.iter().map(|f| f.name.to_owned()).collect(),
+        sorts: NyaaSort::VARIANTS.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Missing sources fall back to the defaults, the error is shown when searching
+pub fn info(name: &str, config: &SourceConfig) -> SourceInfo {
+    cfg_info(&get_config(name, config).unwrap_or_default())
+}
+
+pub fn default_category(name: &str, config: &SourceConfig) -> usize {
+    let cfg = get_config(name, config).unwrap_or_default();
+    cfg_info(&cfg).entry_from_cfg(&cfg.default_category).id
+}
+
+pub fn default_sort(name: &str, config: &SourceConfig) -> SelectedSort {
+    let cfg = get_config(name, config).unwrap_or_default();
+    SelectedSort {
+        sort: cfg.default_sort as usize,
+        dir: cfg.default_sort_dir,
+    }
+}
+
+pub fn default_filter(name: &str, config: &SourceConfig) -> usize {
+    let cfg = get_config(name, config).unwrap_or_default();
+    cfg.filters
+        .iter()
+        .position(|f| f.name == cfg.default_filter)
+        .unwrap_or_default()
+}
+
+pub fn default_search(name: &str, config: &SourceConfig) -> String {
+    get_config(name, config)
+        .map(|c| c.default_search)
+        .unwrap_or_default()
+}
+
+pub fn format_table(
+    name: &str,
+    items: &[Item],
+    search: &SearchQuery,
+    config: &SourceConfig,
+    theme: &Theme,
+) -> ResultTable {
+    let cfg = get_config(name, config).unwrap_or_default();
+    nyaa_table(items.into(), theme, &search.sort, &cfg.columns)
+}
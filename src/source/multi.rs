@@ -0,0 +1,373 @@
+use std::{cmp::Ordering, collections::HashSet, error::Error};
+
+use ratatui::{
+    layout::{Alignment, Constraint},
+    style::Stylize as _,
+};
+use serde::{Deserialize, Serialize};
+use strum::{Display, FromRepr, VariantArray};
+
+use crate::{
+    cats,
+    results::{ResultColumn, ResultHeader, ResultResponse, ResultRow, ResultTable},
+    sync::SearchQuery,
+    theme::Theme,
+    util::conv::{item_hash, shorten_number},
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    Item, ItemDetails, ItemType, Source, SourceConfig, SourceInfo, SourceResponse, Sources,
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MultiConfig {
+    pub sources: Vec<Sources>,
+    pub default_sort: MultiSort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: MultiFilter,
+    pub default_search: String,
+}
+
+impl Default for MultiConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![Sources::Nyaa, Sources::TorrentGalaxy],
+            default_sort: MultiSort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: MultiFilter::NoFilter,
+            default_search: Default::default(),
+        }
+    }
+}
+
+// Discriminants line up with `NyaaSort` so the table headers match the other sources
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum MultiSort {
+    #[strum(serialize = "Date")]
+    Date = 0,
+    #[strum(serialize = "Downloads")]
+    Downloads = 1,
+    #[strum(serialize = "Seeders")]
+    Seeders = 2,
+    #[strum(serialize = "Leechers")]
+    Leechers = 3,
+    #[strum(serialize = "Size")]
+    Size = 4,
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum MultiFilter {
+    #[allow(clippy::enum_variant_names)]
+    #[strum(serialize = "No Filter")]
+    NoFilter = 0,
+    #[strum(serialize = "No Dead Torrents")]
+    NoDead = 1,
+    #[strum(serialize = "Trusted Only")]
+    TrustedOnly = 2,
+}
+
+pub struct MultiSource;
+
+/// The source an item was loaded from, stored in `extra` when merging results
+pub fn item_source(item: &Item) -> Option<Sources> {
+    item.extra
+        .get("source")
+        .cloned()
+        .and_then(|s| Sources::try_from(s).ok())
+}
+
+/// Build the query sent to a single source. The category, filter and sort come
+/// from the defaults of that source, except that the sort is kept if the source
+/// has one with the same name, so each source returns its best matches first.
+fn source_query(src: &Sources, search: &SearchQuery, config: &SourceConfig) -> SearchQuery {
+    let mut sort = src.default_sort(config);
+    let name = MultiSort::from_repr(search.sort.sort).map(|s| s.to_string());
+    if let Some(idx) = src
+        .info(config)
+        .sorts
+        .iter()
+        .position(|s| Some(s) == name.as_ref())
+    {
+        sort = SelectedSort {
+            sort: idx,
+            dir: search.sort.dir,
+        };
+    }
+    SearchQuery {
+        query: search.query.to_owned(),
+        page: search.page,
+        category: src.default_category(config),
+        filter: src.default_filter(config),
+        sort,
+        user: search.user.to_owned(),
+    }
+}
+
+/// Combine the results of each source in order. Items with the same info hash
+/// are only kept the first time they are seen.
+pub fn merge_results(results: Vec<(Sources, ResultResponse)>) -> ResultResponse {
+    let mut seen = HashSet::new();
+    let mut merged = ResultResponse::default();
+    for (src, res) in results.into_iter() {
+        merged.last_page = merged.last_page.max(res.last_page);
+        merged.total_results += res.total_results;
+        for mut item in res.items.into_iter() {
+            if let Some(hash) = item_hash(&item) {
+                if !seen.insert(hash) {
+                    merged.total_results = merged.total_results.saturating_sub(1);
+                    continue;
+                }
+            }
+            item.extra.insert("source".to_owned(), src.clone().into());
+            merged.items.push(item);
+        }
+    }
+    merged
+}
+
+pub fn sort_items(items: &mut [Item], sort: SelectedSort) {
+    let f: fn(&Item, &Item) -> Ordering = match MultiSort::from_repr(sort.sort) {
+        Some(MultiSort::Downloads) => |a, b| b.downloads.cmp(&a.downloads),
+        Some(MultiSort::Seeders) => |a, b| b.seeders.cmp(&a.seeders),
+        Some(MultiSort::Leechers) => |a, b| b.leechers.cmp(&a.leechers),
+        Some(MultiSort::Size) => |a, b| b.bytes.cmp(&a.bytes),
+        _ => |a, b| b.timestamp.cmp(&a.timestamp),
+    };
+    items.sort_by(f);
+    if sort.dir == SortDir::Asc {
+        items.reverse();
+    }
+}
+
+pub fn filter_items(items: &mut Vec<Item>, filter: usize) {
+    match MultiFilter::from_repr(filter) {
+        Some(MultiFilter::NoDead) => items.retain(|i| i.seeders > 0),
+        Some(MultiFilter::TrustedOnly) => {
+            items.retain(|i| matches!(i.item_type, ItemType::Trusted))
+        }
+        _ => {}
+    }
+}
+
+impl Source for MultiSource {
+    async fn search(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        let multi = config.multi.to_owned().unwrap_or_default();
+        if multi.sources.is_empty() {
+            return Err("No sources configured in [source.multi]".into());
+        }
+
+        let tasks = multi
+            .sources
+            .iter()
+            .filter(|s| **s != Sources::Multi)
+            .map(|src| {
+                let (src, client, config) = (src.clone(), client.clone(), config.clone());
+                let query = source_query(&src, search, &config);
+                let date_format = date_format.clone();
+                let task_src = src.clone();
+                let task = tokio::spawn(async move {
+                    task_src.search(&client, &query, &config, date_format).await
+                });
+                (src, task)
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = vec![];
+        let mut errors = vec![];
+        for (src, task) in tasks.into_iter() {
+            match task.await {
+                Ok(Ok(SourceResponse::Results(res))) => results.push((src, res)),
+                #[cfg(feature = "captcha")]
+                Ok(Ok(SourceResponse::Captcha(_))) => {
+                    errors.push(format!("{}: requires solving a captcha", src))
+                }
+                Ok(Err(e)) => errors.push(format!("{}: {}", src, e)),
+                Err(e) => errors.push(format!("{}: {}", src, e)),
+            }
+        }
+        // Only fail if there is nothing to show, a single source being down
+        // shouldn't hide the results of the others
+        if results.is_empty() {
+            return Err(errors.join("\n").into());
+        }
+
+        let mut res = merge_results(results);
+        filter_items(&mut res.items, search.filter);
+        sort_items(&mut res.items, search.sort);
+        Ok(SourceResponse::Results(res))
+    }
+    async fn sort(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        MultiSource::search(client, search, config, date_format).await
+    }
+    async fn filter(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        MultiSource::search(client, search, config, date_format).await
+    }
+    async fn categorize(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        MultiSource::search(client, search, config, date_format).await
+    }
+    async fn solve(
+        _solution: String,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        MultiSource::search(client, search, config, date_format).await
+    }
+
+    async fn details(
+        _client: &reqwest::Client,
+        _item: &Item,
+        _config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        // `Sources::details` looks up the source of the item instead
+        Err("Unknown source for item".into())
+    }
+
+    fn info() -> SourceInfo {
+        let cats = cats! {
+            "All Categories" => {
+                0 => ("---", "All Categories", "AllCategories", fg);
+            }
+        };
+        SourceInfo {
+            cats,
+            filters: MultiFilter::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sorts: MultiSort::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn load_config(config: &mut SourceConfig) {
+        if config.multi.is_none() {
+            config.multi = Some(MultiConfig::default());
+        }
+        let sources = config.multi.to_owned().unwrap_or_default().sources;
+        for src in sources.into_iter().filter(|s| *s != Sources::Multi) {
+            src.load_config(config);
+        }
+    }
+
+    fn default_category(_cfg: &SourceConfig) -> usize {
+        0
+    }
+
+    fn default_sort(cfg: &SourceConfig) -> SelectedSort {
+        cfg.multi
+            .as_ref()
+            .map(|c| SelectedSort {
+                sort: c.default_sort as usize,
+                dir: c.default_sort_dir,
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_filter(cfg: &SourceConfig) -> usize {
+        cfg.multi
+            .as_ref()
+            .map(|c| c.default_filter as usize)
+            .unwrap_or_default()
+    }
+
+    fn default_search(cfg: &SourceConfig) -> String {
+        cfg.multi
+            .as_ref()
+            .map(|c| c.default_search.to_owned())
+            .unwrap_or_default()
+    }
+
+    fn format_table(
+        items: &[Item],
+        search: &SearchQuery,
+        _config: &SourceConfig,
+        theme: &Theme,
+    ) -> ResultTable {
+        let date_width = items.iter().map(|i| i.date.len()).max().unwrap_or_default() as u16;
+        let source_width = items
+            .iter()
+            .filter_map(|i| item_source(i).map(|s| s.to_string().len()))
+            .max()
+            .unwrap_or_default() as u16;
+
+        let header = ResultHeader::new([
+            ResultColumn::Normal("Cat".to_owned(), Constraint::Length(3)),
+            ResultColumn::Normal("Source".to_owned(), Constraint::Length(source_width.max(6))),
+            ResultColumn::Normal("Name".to_owned(), Constraint::Min(3)),
+            ResultColumn::Sorted("Size".to_owned(), 9, MultiSort::Size as u32),
+            ResultColumn::Sorted("Date".to_owned(), date_width.max(6), MultiSort::Date as u32),
+            ResultColumn::Sorted("".to_owned(), 4, MultiSort::Seeders as u32),
+            ResultColumn::Sorted("".to_owned(), 4, MultiSort::Leechers as u32),
+            ResultColumn::Sorted("".to_owned(), 5, MultiSort::Downloads as u32),
+        ]);
+        let binding = header.get_binding();
+        let align = [
+            Alignment::Left,
+            Alignment::Left,
+            Alignment::Left,
+            Alignment::Right,
+            Alignment::Left,
+            Alignment::Right,
+            Alignment::Right,
+            Alignment::Left,
+        ];
+        let rows: Vec<ResultRow> = items
+            .iter()
+            .map(|item| {
+                ResultRow::new([
+                    item.icon.label.to_owned().fg((item.icon.color)(theme)),
+                    item_source(item)
+                        .map(|s| s.to_string())
+                        .unwrap_or_default()
+                        .fg(theme.border_color),
+                    item.title.to_owned().fg(match item.item_type {
+                        ItemType::Trusted => theme.success,
+                        ItemType::Remake => theme.error,
+                        ItemType::None => theme.fg,
+                    }),
+                    item.size.to_owned().fg(theme.fg),
+                    item.date.to_owned().fg(theme.fg),
+                    item.seeders.to_string().fg(theme.success),
+                    item.leechers.to_string().fg(theme.error),
+                    shorten_number(item.downloads).fg(theme.fg),
+                ])
+                .aligned(align)
+                .fg(theme.fg)
+            })
+            .collect();
+
+        ResultTable {
+            headers: header.get_row(search.sort.dir, search.sort.sort as u32),
+            rows,
+            binding,
+        }
+    }
+}
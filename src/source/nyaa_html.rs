@@ -6,7 +6,7 @@ use ratatui::{
     style::{Color, Stylize as _},
 };
 use reqwest::StatusCode;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
 use strum::{Display, FromRepr, VariantArray};
 use urlencoding::encode;
@@ -19,13 +19,14 @@ use crate::{
     theme::Theme,
     util::{
         conv::{shorten_number, to_bytes},
-        html::{as_type, attr, inner},
+        html::{as_type, attr, inner, text},
     },
     widget::sort::{SelectedSort, SortDir},
 };
 
 use super::{
-    add_protocol, nyaa_rss, Item, ItemType, Source, SourceConfig, SourceInfo, SourceResponse,
+    add_protocol, nyaa_rss, Item, ItemDetails, ItemFile, ItemType, Source, SourceConfig,
+    SourceInfo, SourceResponse,
 };
 
 #[derive(Serialize, Deserialize, Clone, Copy, Default)]
@@ -228,7 +229,7 @@ pub fn nyaa_table(
         .into_iter()
         .map(|item| {
             ResultRow::new([
-                item.icon.label.fg((item.icon.color)(theme)),
+                item.icon.label.to_owned().fg((item.icon.color)(theme)),
                 item.title.fg(match item.item_type {
                     ItemType::Trusted => theme.success,
                     ItemType::Remake => theme.error,
@@ -267,6 +268,107 @@ pub fn nyaa_table(
     }
 }
 
+fn child_elements(e: ElementRef) -> impl Iterator<Item = ElementRef> {
+    e.children().filter_map(ElementRef::wrap)
+}
+
+fn parse_files(li: ElementRef, prefix: &str, files: &mut Vec<ItemFile>) {
+    let folder = child_elements(li)
+        .find(|c| c.value().name() == "a" && c.value().classes().any(|c| c == "folder"));
+    match folder {
+        Some(folder) => {
+            let prefix = format!("{}{}/", prefix, text(folder));
+            for ul in child_elements(li).filter(|c| c.value().name() == "ul") {
+                for li in child_elements(ul).filter(|c| c.value().name() == "li") {
+                    parse_files(li, &prefix, files);
+                }
+            }
+        }
+        None => {
+            let name = li
+                .children()
+                .filter_map(|c| c.value().as_text().map(|t| t.to_string()))
+                .collect::<String>();
+            let size = child_elements(li)
+                .find(|c| c.value().classes().any(|c| c == "file-size"))
+                .map(text)
+                .unwrap_or_default();
+            files.push(ItemFile {
+                path: format!("{}{}", prefix, name.trim()),
+                size: size
+                    .trim_matches(|c| c == '(' || c == ')')
+                    .replace('i', "")
+                    .replace("Bytes", "B"),
+            });
+        }
+    }
+}
+
+/// Parse a post page from Nyaa or Sukebei, which share the same layout
+pub fn parse_details(html: &str) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+    let title_sel = &sel!(".panel-heading > .panel-title")?;
+    let row_sel = &sel!(".panel-body > .row")?;
+    let desc_sel = &sel!("#torrent-description")?;
+    let files_sel = &sel!(".torrent-file-list > ul > li")?;
+    let comments_sel = &sel!("#comments .panel-title")?;
+
+    let mut details = ItemDetails {
+        title: doc.select(title_sel).next().map(text).unwrap_or_default(),
+        description: doc
+            .select(desc_sel)
+            .next()
+            .map(|e| e.text().collect::<String>().trim().to_owned())
+            .unwrap_or_default(),
+        comments: doc
+            .select(comments_sel)
+            .next()
+            .and_then(|e| as_type(text(e)))
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    // Each row holds pairs of "Label:" and value columns
+    for row in doc.select(row_sel) {
+        let cols = child_elements(row).collect::<Vec<ElementRef>>();
+        for pair in cols.chunks(2) {
+            if let [label, value] = pair {
+                let value = text(*value);
+                match text(*label).trim_end_matches(':') {
+                    "Submitter" => details.submitter = value,
+                    "Date" => details.date = value,
+                    "File size" => details.size = value.replace('i', "").replace("Bytes", "B"),
+                    "Info hash" => details.info_hash = value,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    for li in doc.select(files_sel) {
+        parse_files(li, "", &mut details.files);
+    }
+    Ok(details)
+}
+
+pub async fn fetch_details(
+    client: &reqwest::Client,
+    url: &str,
+    timeout: Option<u64>,
+) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+    let mut request = client.get(url);
+    if let Some(timeout) = timeout {
+        request = request.timeout(Duration::from_secs(timeout));
+    }
+    let response = request.send().await?;
+    if response.status() != StatusCode::OK {
+        // Throw error if response code is not OK
+        let code = response.status().as_u16();
+        return Err(format!("{}\nInvalid response code: {}", url, code).into());
+    }
+    parse_details(&response.text().await?)
+}
+
 impl Source for NyaaHtmlSource {
     async fn search(
         client: &reqwest::Client,
@@ -400,6 +502,7 @@ impl Source for NyaaHtmlSource {
                 Some(Item {
                     id,
                     date,
+                    timestamp: date_time.timestamp(),
                     seeders,
                     leechers,
                     downloads,
@@ -467,6 +570,15 @@ impl Source for NyaaHtmlSource {
         NyaaHtmlSource::search(client, search, config, date_format).await
     }
 
+    async fn details(
+        client: &reqwest::Client,
+        item: &Item,
+        config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        let nyaa = config.nyaa.to_owned().unwrap_or_default();
+        fetch_details(client, &item.post_link, nyaa.timeout).await
+    }
+
     fn info() -> SourceInfo {
         let cats = cats! {
             "All Categories" => {
@@ -114,6 +114,7 @@ pub async fn search_rss<S: Source>(
             Some(Item {
                 id: format!("nyaa-{}", id_usize),
                 date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
                 seeders: get_ext_value(ext, "seeders"),
                 leechers: get_ext_value(ext, "leechers"),
                 downloads: get_ext_value(ext, "downloads"),
@@ -0,0 +1,446 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Mutex,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use human_bytes::human_bytes;
+use serde::{Deserialize, Serialize};
+use strum::VariantArray as _;
+use tokio::{io::AsyncWriteExt as _, process::Command};
+
+use crate::{
+    results::{ResultResponse, ResultTable},
+    sync::SearchQuery,
+    theme::Theme,
+    util::conv::{get_hash, to_bytes},
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    custom::{category_id, category_structs, category_value, CustomCategory},
+    nyaa_html::{nyaa_table, NyaaColumns, NyaaSort},
+    Item, ItemType, SourceConfig, SourceInfo, SourceResponse,
+};
+
+pub static PLUGINS_PATH: &str = "plugins";
+
+/// A source backed by an executable, which is sent a JSON request on stdin for
+/// each search and replies with JSON on stdout
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PluginConfig {
+    pub cmd: Option<String>,
+    pub args: Vec<String>,
+    pub default_sort: String,
+    pub default_sort_dir: SortDir,
+    pub default_filter: String,
+    pub default_category: String,
+    pub default_search: String,
+    pub timeout: Option<u64>,
+    pub columns: Option<NyaaColumns>,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            cmd: None,
+            args: vec![],
+            default_sort: Default::default(),
+            default_sort_dir: SortDir::Desc,
+            default_filter: Default::default(),
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            timeout: None,
+            columns: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+enum PluginRequest {
+    Info,
+    Search(PluginSearch),
+    Solve {
+        solution: String,
+        #[serde(flatten)]
+        search: PluginSearch,
+    },
+}
+
+#[derive(Serialize)]
+struct PluginSearch {
+    query: String,
+    page: usize,
+    category: Option<String>,
+    filter: Option<String>,
+    sort: Option<String>,
+    sort_dir: SortDir,
+    user: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+struct PluginInfo {
+    error: Option<String>,
+    categories: Vec<CustomCategory>,
+    filters: Vec<String>,
+    sorts: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PluginResults {
+    error: Option<String>,
+    captcha: Option<String>,
+    items: Vec<PluginItem>,
+    last_page: usize,
+    total_results: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PluginItem {
+    id: String,
+    title: String,
+    post_link: String,
+    torrent_link: String,
+    magnet_link: String,
+    file_name: String,
+    size: String,
+    bytes: Option<usize>,
+    timestamp: i64,
+    seeders: u32,
+    leechers: u32,
+    downloads: u32,
+    category: String,
+    trusted: bool,
+    remake: bool,
+    extra: HashMap<String, String>,
+}
+
+/// The info of each plugin command, so it's only asked for once
+static INFO: Mutex<BTreeMap<String, Result<PluginInfo, String>>> = Mutex::new(BTreeMap::new());
+
+fn get_config(name: &str, config: &SourceConfig) -> Result<PluginConfig, String> {
+    config
+        .plugin
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("No plugin named \"{}\" in [source.plugin]", name))
+}
+
+/// Relative commands are run from the plugins folder in the config folder
+fn get_cmd(name: &str, cfg: &PluginConfig, config: &SourceConfig) -> PathBuf {
+    let cmd = PathBuf::from(cfg.cmd.to_owned().unwrap_or(name.to_owned()));
+    match config.plugins_path.as_ref() {
+        Some(path) if cmd.is_relative() => path.join(cmd),
+        _ => cmd,
+    }
+}
+
+async fn run<T: for<'de> Deserialize<'de>>(
+    cmd: &PathBuf,
+    args: &[String],
+    request: &PluginRequest,
+    timeout: u64,
+) -> Result<T, Box<dyn Error + Send + Sync>> {
+    let path = cmd.to_string_lossy();
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("{}:\nFailed to run plugin:\n{}", path, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that exits without reading the request is reported by its status code
+        let _ = stdin.write_all(&serde_json::to_vec(request)?).await;
+    }
+    let output = tokio::time::timeout(Duration::from_secs(timeout), child.wait_with_output())
+        .await
+        .map_err(|_| format!("{}:\nPlugin timed out after {} seconds", path, timeout))??;
+    if !output.status.success() {
+        return Err(format!(
+            "{}:\nExited with status code {}:\n{}",
+            path,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("{}:\nInvalid response from plugin:\n{}", path, e).into())
+}
+
+fn info_key(cmd: &Path, cfg: &PluginConfig) -> String {
+    format!("{} {}", cmd.to_string_lossy(), cfg.args.join(" "))
+}
+
+fn cached_info(name: &str, config: &SourceConfig) -> Option<Result<PluginInfo, String>> {
+    let cfg = match get_config(name, config) {
+        Ok(cfg) => cfg,
+        Err(e) => return Some(Err(e)),
+    };
+    let key = info_key(&get_cmd(name, &cfg, config), &cfg);
+    INFO.lock().ok().and_then(|i| i.get(&key).cloned())
+}
+
+/// Whether the plugin has already been asked for its info, whether it answered or not
+pub fn info_loaded(name: &str, config: &SourceConfig) -> bool {
+    cached_info(name, config).is_some()
+}
+
+/// Ask the plugin for its categories, filters, and sorts. Errors are kept as
+/// well, so a broken plugin isn't run again every time its info is needed.
+pub async fn load_info(name: &str, config: &SourceConfig) -> Result<(), String> {
+    get_info(name, config).await.map(|_| ())
+}
+
+async fn get_info(name: &str, config: &SourceConfig) -> Result<PluginInfo, String> {
+    if let Some(info) = cached_info(name, config) {
+        return info;
+    }
+    let cfg = get_config(name, config)?;
+    let cmd = get_cmd(name, &cfg, config);
+    let timeout = cfg.timeout.unwrap_or(30);
+    let res = run::<PluginInfo>(&cmd, &cfg.args, &PluginRequest::Info, timeout)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|mut res| {
+            if let Some(error) = res.error.take() {
+                return Err(error);
+            }
+            if res.filters.is_empty() {
+                res.filters = vec!["No Filter".to_owned()];
+            }
+            if res.sorts.is_empty() {
+                res.sorts = vec![NyaaSort::Date.to_string()];
+            }
+            Ok(res)
+        });
+    if let Ok(mut cache) = INFO.lock() {
+        cache.insert(info_key(&cmd, &cfg), res.clone());
+    }
+    res
+}
+
+fn to_item(
+    name: &str,
+    item: PluginItem,
+    info: &SourceInfo,
+    categories: &[CustomCategory],
+    date_format: &str,
+) -> Item {
+    let date = DateTime::from_timestamp(item.timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local);
+    let bytes = item.bytes.unwrap_or(to_bytes(&item.size));
+    let size = match item.size.is_empty() {
+        true => human_bytes(bytes as f64).replace('i', ""),
+        false => item.size,
+    };
+    let cat = info
+        .clone()
+        .entry_from_id(category_id(categories, &item.category));
+    let file_name = match item.file_name.is_empty() {
+        true => format!("{}.torrent", item.id),
+        false => item.file_name,
+    };
+
+    let mut extra = item.extra;
+    if let Some(hash) = get_hash(format!("{}&", item.magnet_link)) {
+        extra
+            .entry("hash".to_owned())
+            .or_insert(hash.to_lowercase());
+    }
+    Item {
+        id: format!("{}-{}", name, item.id),
+        date: date.format(date_format).to_string(),
+        timestamp: item.timestamp,
+        seeders: item.seeders,
+        leechers: item.leechers,
+        downloads: item.downloads,
+        size,
+        bytes,
+        title: item.title,
+        torrent_link: item.torrent_link,
+        magnet_link: item.magnet_link,
+        post_link: item.post_link,
+        file_name,
+        category: cat.id,
+        icon: cat.icon,
+        item_type: match (item.trusted, item.remake) {
+            (true, _) => ItemType::Trusted,
+            (_, true) => ItemType::Remake,
+            _ => ItemType::None,
+        },
+        extra,
+    }
+}
+
+async fn request(
+    name: &str,
+    solution: Option<String>,
+    search: &SearchQuery,
+    config: &SourceConfig,
+    date_format: Option<String>,
+) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+    let cfg = get_config(name, config)?;
+    let plugin = get_info(name, config).await?;
+    let query = PluginSearch {
+        query: search.query.to_owned(),
+        page: search.page.max(1),
+        category: category_value(&plugin.categories, search.category),
+        filter: plugin.filters.get(search.filter).cloned(),
+        sort: plugin.sorts.get(search.sort.sort).cloned(),
+        sort_dir: search.sort.dir,
+        user: search.user.to_owned(),
+    };
+    let request = match solution {
+        Some(solution) => PluginRequest::Solve {
+            solution,
+            search: query,
+        },
+        None => PluginRequest::Search(query),
+    };
+
+    let cmd = get_cmd(name, &cfg, config);
+    let res: PluginResults = run(&cmd, &cfg.args, &request, cfg.timeout.unwrap_or(30)).await?;
+    if let Some(error) = res.error {
+        return Err(error.into());
+    }
+    if let Some(captcha) = res.captcha {
+        #[cfg(not(feature = "captcha"))]
+        {
+            let _ = captcha;
+            return Err(format!("{} requires solving a captcha, which needs nyaa to be built with the \"captcha\" feature", name).into());
+        }
+        #[cfg(feature = "captcha")]
+        {
+            use base64::Engine as _;
+            let bytes = base64::engine::general_purpose::STANDARD.decode(captcha)?;
+            let mut picker = ratatui_image::picker::Picker::new((1, 2));
+            picker.protocol_type = ratatui_image::picker::ProtocolType::Halfblocks;
+            let dyn_image = image::load_from_memory(&bytes[..])?;
+            let image = picker.new_resize_protocol(dyn_image);
+
+            return Ok(SourceResponse::Captcha(image));
+        }
+    }
+
+    let info = build_info(plugin.clone());
+    let date_format = date_format.unwrap_or("%Y-%m-%d %H:%M".to_owned());
+    let items = res
+        .items
+        .into_iter()
+        .map(|i| to_item(name, i, &info, &plugin.categories, &date_format))
+        .collect::<Vec<Item>>();
+    Ok(SourceResponse::Results(ResultResponse {
+        total_results: res.total_results.unwrap_or(items.len()),
+        last_page: res.last_page.max(search.page.max(1)),
+        items,
+    }))
+}
+
+pub async fn search(
+    name: &str,
+    search: &SearchQuery,
+    config: &SourceConfig,
+    date_format: Option<String>,
+) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+    request(name, None, search, config, date_format).await
+}
+
+pub async fn solve(
+    name: &str,
+    solution: String,
+    search: &SearchQuery,
+    config: &SourceConfig,
+    date_format: Option<String>,
+) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+    request(name, Some(solution), search, config, date_format).await
+}
+
+fn build_info(plugin: PluginInfo) -> SourceInfo {
+    SourceInfo {
+        cats: category_structs(&plugin.categories),
+        filters: plugin.filters,
+        sorts: plugin.sorts,
+    }
+}
+
+/// Plugins that haven't been loaded or fail to start only get "All Categories",
+/// the error is shown when searching
+pub fn info(name: &str, config: &SourceConfig) -> SourceInfo {
+    build_info(
+        cached_info(name, config)
+            .and_then(Result::ok)
+            .unwrap_or(PluginInfo {
+                filters: vec!["No Filter".to_owned()],
+                sorts: vec![NyaaSort::Date.to_string()],
+                ..Default::default()
+            }),
+    )
+}
+
+pub fn default_category(name: &str, config: &SourceConfig) -> usize {
+    let cfg = get_config(name, config).unwrap_or_default();
+    info(name, config).entry_from_cfg(&cfg.default_category).id
+}
+
+pub fn default_sort(name: &str, config: &SourceConfig) -> SelectedSort {
+    let cfg = get_config(name, config).unwrap_or_default();
+    SelectedSort {
+        sort: info(name, config)
+            .sorts
+            .iter()
+            .position(|s| *s == cfg.default_sort)
+            .unwrap_or_default(),
+        dir: cfg.default_sort_dir,
+    }
+}
+
+pub fn default_filter(name: &str, config: &SourceConfig) -> usize {
+    let cfg = get_config(name, config).unwrap_or_default();
+    info(name, config)
+        .filters
+        .iter()
+        .position(|f| *f == cfg.default_filter)
+        .unwrap_or_default()
+}
+
+pub fn default_search(name: &str, config: &SourceConfig) -> String {
+    get_config(name, config)
+        .map(|c| c.default_search)
+        .unwrap_or_default()
+}
+
+pub fn format_table(
+    name: &str,
+    items: &[Item],
+    search: &SearchQuery,
+    config: &SourceConfig,
+    theme: &Theme,
+) -> ResultTable {
+    let cfg = get_config(name, config).unwrap_or_default();
+    // Only highlight the column if the plugin's sort has the same name as one of Nyaa's
+    let sort = info(name, config)
+        .sorts
+        .get(search.sort.sort)
+        .and_then(|s| NyaaSort::VARIANTS.iter().find(|n| n.to_string() == *s))
+        .map(|s| *s as usize)
+        .unwrap_or(usize::MAX);
+    let sel_sort = SelectedSort {
+        sort,
+        dir: search.sort.dir,
+    };
+    nyaa_table(items.into(), theme, &sel_sort, &cfg.columns)
+}
@@ -23,8 +23,9 @@ use crate::{
 
 use super::{
     add_protocol,
-    nyaa_html::{nyaa_table, NyaaColumns, NyaaFilter, NyaaSort},
-    nyaa_rss, Item, ItemType, ResultTable, Source, SourceConfig, SourceInfo, SourceResponse,
+    nyaa_html::{fetch_details, nyaa_table, NyaaColumns, NyaaFilter, NyaaSort},
+    nyaa_rss, Item, ItemDetails, ItemType, ResultTable, Source, SourceConfig, SourceInfo,
+    SourceResponse,
 };
 
 #[derive(Serialize, Deserialize, Clone, Copy, Default)]
@@ -259,6 +260,7 @@ impl Source for SukebeiHtmlSource {
                 Some(Item {
                     id,
                     date,
+                    timestamp: date_time.timestamp(),
                     seeders,
                     leechers,
                     downloads,
@@ -301,6 +303,15 @@ impl Source for SukebeiHtmlSource {
         SukebeiHtmlSource::search(client, search, config, date_format).await
     }
 
+    async fn details(
+        client: &reqwest::Client,
+        item: &Item,
+        config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        let sukebei = config.sukebei.to_owned().unwrap_or_default();
+        fetch_details(client, &item.post_link, sukebei.timeout).await
+    }
+
     fn info() -> SourceInfo {
         let cats = cats! {
             "All Categories" => {
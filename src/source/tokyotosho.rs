@@ -0,0 +1,470 @@
+use std::{cmp::Ordering, error::Error, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use ratatui::style::Color;
+use reqwest::{StatusCode, Url};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use strum::{Display, FromRepr, VariantArray};
+use urlencoding::encode;
+
+use crate::{
+    cats,
+    results::{ResultResponse, ResultTable},
+    sel,
+    sync::SearchQuery,
+    theme::Theme,
+    util::{
+        conv::{get_hash, to_bytes},
+        html::attr,
+    },
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    add_protocol,
+    nyaa_html::{nyaa_table, NyaaColumns},
+    Item, ItemDetails, ItemType, Source, SourceConfig, SourceInfo, SourceResponse,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct TokyoToshoTheme {
+    #[serde(rename = "categories")]
+    pub cat: TokyoToshoCategoryTheme,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct TokyoToshoCategoryTheme {
+    #[serde(with = "color_to_tui")]
+    pub anime: Color,
+    #[serde(with = "color_to_tui")]
+    pub non_english: Color,
+    #[serde(with = "color_to_tui")]
+    pub raws: Color,
+    #[serde(with = "color_to_tui")]
+    pub batch: Color,
+    #[serde(with = "color_to_tui")]
+    pub manga: Color,
+    #[serde(with = "color_to_tui")]
+    pub drama: Color,
+    #[serde(with = "color_to_tui")]
+    pub music: Color,
+    #[serde(with = "color_to_tui")]
+    pub music_video: Color,
+    #[serde(with = "color_to_tui")]
+    pub hentai: Color,
+    #[serde(with = "color_to_tui")]
+    pub jav: Color,
+    #[serde(with = "color_to_tui")]
+    pub other: Color,
+}
+
+impl Default for TokyoToshoCategoryTheme {
+    fn default() -> Self {
+        use Color::*;
+        Self {
+            anime: LightMagenta,
+            non_english: LightGreen,
+            raws: Gray,
+            batch: LightCyan,
+            manga: LightYellow,
+            drama: Magenta,
+            music: Yellow,
+            music_video: LightBlue,
+            hentai: LightRed,
+            jav: Red,
+            other: DarkGray,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TokyoToshoConfig {
+    pub base_url: String,
+    pub default_sort: TokyoToshoSort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: TokyoToshoFilter,
+    pub default_category: String,
+    pub default_search: String,
+    pub timeout: Option<u64>,
+    pub columns: Option<NyaaColumns>,
+}
+
+impl Default for TokyoToshoConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://www.tokyotosho.info/".to_owned(),
+            default_sort: TokyoToshoSort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: TokyoToshoFilter::NoFilter,
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            timeout: None,
+            columns: None,
+        }
+    }
+}
+
+// Discriminants line up with `NyaaSort` so results can share `nyaa_table`
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum TokyoToshoSort {
+    #[strum(serialize = "Date")]
+    Date = 0,
+    #[strum(serialize = "Completed")]
+    Completed = 1,
+    #[strum(serialize = "Seeders")]
+    Seeders = 2,
+    #[strum(serialize = "Leechers")]
+    Leechers = 3,
+    #[strum(serialize = "Size")]
+    Size = 4,
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum TokyoToshoFilter {
+    #[allow(clippy::enum_variant_names)]
+    #[strum(serialize = "No Filter")]
+    NoFilter = 0,
+    #[strum(serialize = "No Dead Torrents")]
+    NoDead = 1,
+}
+
+pub struct TokyoToshoSource;
+
+/// Get a field from the `Submitter: x | Size: y | ...` line below each title
+fn desc_field(desc: &str, name: &str) -> Option<String> {
+    desc.split('|')
+        .filter_map(|f| f.split_once(':'))
+        .find(|(k, _)| k.trim() == name)
+        .map(|(_, v)| v.trim().to_owned())
+}
+
+/// Sizes are shown without a space, like `1.36GB`
+fn parse_size(size: &str) -> String {
+    match size.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => format!("{} {}", &size[..i], &size[i..]),
+        None => size.to_owned(),
+    }
+}
+
+/// Get the number after a label in the stats cell, like `S: 12`
+fn stat(e: ElementRef, label: &str) -> u32 {
+    let text = e.text().collect::<String>();
+    text.split_once(label)
+        .map(|(_, rest)| {
+            rest.trim_start()
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+        })
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_default()
+}
+
+fn get_url(base_url: String, search: &SearchQuery) -> Result<Url, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(base_url, true)?;
+    let page = search.page.max(1);
+    // The search page returns nothing without any terms, so use the listing instead
+    let (path, query) = match search.query.is_empty() {
+        true => ("", format!("cat={}&page={}", search.category, page)),
+        false => (
+            "search.php",
+            format!(
+                "terms={}&type={}&searchName=true&searchComment=true&page={}",
+                encode(&search.query),
+                search.category,
+                page
+            ),
+        ),
+    };
+    let mut url = base_url.join(path)?;
+    url.set_query(Some(&query));
+    Ok(url)
+}
+
+/// Parse a TokyoTosho listing or search page into items. Each result takes up
+/// two rows, the first with the title and links, the second with its stats.
+pub fn parse_tokyotosho<S: Source>(
+    html: &str,
+    base_url: &Url,
+    date_format: Option<String>,
+    page: usize,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+
+    let row_sel = &sel!("table.listing tr")?;
+    let top_sel = &sel!("td.desc-top")?;
+    let bot_sel = &sel!("td.desc-bot")?;
+    let stats_sel = &sel!("td.stats")?;
+    let cat_sel = &sel!("td:first-child > a")?;
+    let title_sel = &sel!("td.desc-top > a[type=\"application/x-bittorrent\"]")?;
+    let magnet_sel = &sel!("td.desc-top > a[href^=\"magnet:\"]")?;
+    let details_sel = &sel!("td.web > a[href*=\"details.php\"]")?;
+
+    let info = S::info();
+    let rows = doc.select(row_sel).collect::<Vec<ElementRef>>();
+    let items = rows
+        .iter()
+        .zip(rows.iter().skip(1))
+        .filter(|(top, _)| top.select(top_sel).next().is_some())
+        .filter_map(|(top, bot)| {
+            let desc = bot
+                .select(bot_sel)
+                .next()?
+                .text()
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ");
+            let stats = bot.select(stats_sel).next()?;
+
+            let post_link = base_url.join(&attr(*top, details_sel, "href")).ok()?;
+            let id = post_link
+                .query_pairs()
+                .find(|(k, _)| k == "id")
+                .map(|(_, v)| v.to_string())?;
+
+            let cat_id = attr(*top, cat_sel, "href")
+                .rsplit_once('=')
+                .and_then(|(_, c)| c.parse::<usize>().ok())
+                .unwrap_or(5);
+            let cat = info.clone().entry_from_id(cat_id);
+
+            // The title links to the torrent file
+            let title = top.select(title_sel).next()?.text().collect::<String>();
+            let torrent_link = attr(*top, title_sel, "href");
+            let magnet_link = attr(*top, magnet_sel, "href");
+
+            let size = parse_size(&desc_field(&desc, "Size").unwrap_or_default());
+            let date = desc_field(&desc, "Date").unwrap_or_default();
+            let naive =
+                NaiveDateTime::parse_from_str(date.trim_end_matches(" UTC"), "%Y-%m-%d %H:%M")
+                    .unwrap_or_default();
+            let date: DateTime<Local> = Local.from_utc_datetime(&naive);
+            let date_format = date_format
+                .to_owned()
+                .unwrap_or("%Y-%m-%d %H:%M".to_owned());
+
+            let mut item = Item {
+                id: format!("tokyotosho-{}", id),
+                date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders: stat(stats, "S:"),
+                leechers: stat(stats, "L:"),
+                downloads: stat(stats, "C:"),
+                bytes: to_bytes(&size),
+                size,
+                title,
+                torrent_link,
+                post_link: post_link.to_string(),
+                file_name: format!("{}.torrent", id),
+                category: cat.id,
+                icon: cat.icon,
+                item_type: ItemType::None,
+                ..Default::default()
+            };
+            if let Some(hash) = get_hash(magnet_link.clone()) {
+                item.extra.insert("hash".to_owned(), hash.to_lowercase());
+            }
+            item.magnet_link = magnet_link;
+            Some(item)
+        })
+        .collect::<Vec<Item>>();
+
+    // There is no result count, so keep going until a page is empty
+    let last_page = match items.is_empty() {
+        true => page.max(1),
+        false => page + 1,
+    };
+    Ok(ResultResponse {
+        total_results: items.len(),
+        items,
+        last_page,
+    })
+}
+
+fn sort_items(items: &mut [Item], sort: SelectedSort) {
+    let f: fn(&Item, &Item) -> Ordering = match TokyoToshoSort::from_repr(sort.sort) {
+        Some(TokyoToshoSort::Completed) => |a, b| b.downloads.cmp(&a.downloads),
+        Some(TokyoToshoSort::Seeders) => |a, b| b.seeders.cmp(&a.seeders),
+        Some(TokyoToshoSort::Leechers) => |a, b| b.leechers.cmp(&a.leechers),
+        Some(TokyoToshoSort::Size) => |a, b| b.bytes.cmp(&a.bytes),
+        _ => |a, b| b.timestamp.cmp(&a.timestamp),
+    };
+    items.sort_by(f);
+    if sort.dir == SortDir::Asc {
+        items.reverse();
+    }
+}
+
+impl Source for TokyoToshoSource {
+    async fn search(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        let tt = config.tokyotosho.to_owned().unwrap_or_default();
+        let url = get_url(tt.base_url, search)?;
+
+        let mut request = client.get(url.to_owned());
+        if let Some(timeout) = tt.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", url, code).into());
+        }
+        let content = response.text().await?;
+
+        let mut res = parse_tokyotosho::<Self>(&content, &url, date_format, search.page)?;
+        // TokyoTosho can't sort or filter by seeders, so the page is sorted locally
+        if TokyoToshoFilter::from_repr(search.filter) == Some(TokyoToshoFilter::NoDead) {
+            res.items.retain(|i| i.seeders > 0);
+        }
+        sort_items(&mut res.items, search.sort);
+        Ok(SourceResponse::Results(res))
+    }
+    async fn sort(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TokyoToshoSource::search(client, search, config, date_format).await
+    }
+    async fn filter(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TokyoToshoSource::search(client, search, config, date_format).await
+    }
+    async fn categorize(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TokyoToshoSource::search(client, search, config, date_format).await
+    }
+    async fn solve(
+        _solution: String,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TokyoToshoSource::search(client, search, config, date_format).await
+    }
+
+    async fn details(
+        _client: &reqwest::Client,
+        _item: &Item,
+        _config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        Err("Details are not supported for TokyoTosho".into())
+    }
+
+    fn info() -> SourceInfo {
+        let cats = cats! {
+            "All Categories" => {
+                0 => ("---", "All Categories", "AllCategories", fg);
+            }
+            "Anime" => {
+                1 => ("Ani", "Anime", "Anime", source.tokyotosho.cat.anime);
+                10 => ("NEn", "Non-English", "NonEnglish", source.tokyotosho.cat.non_english);
+                7 => ("Raw", "Raws", "Raws", source.tokyotosho.cat.raws);
+                15 => ("Bat", "Batch", "Batch", source.tokyotosho.cat.batch);
+            }
+            "Manga" => {
+                3 => ("Mng", "Manga", "Manga", source.tokyotosho.cat.manga);
+            }
+            "Drama" => {
+                8 => ("Drm", "Drama", "Drama", source.tokyotosho.cat.drama);
+            }
+            "Music" => {
+                2 => ("Mus", "Music", "Music", source.tokyotosho.cat.music);
+                9 => ("MV ", "Music Video", "MusicVideo", source.tokyotosho.cat.music_video);
+            }
+            "Hentai" => {
+                4 => ("Hen", "Hentai", "Hentai", source.tokyotosho.cat.hentai);
+                12 => ("HAn", "Hentai (Anime)", "HentaiAnime", source.tokyotosho.cat.hentai);
+                13 => ("HMn", "Hentai (Manga)", "HentaiManga", source.tokyotosho.cat.hentai);
+                14 => ("HGm", "Hentai (Games)", "HentaiGames", source.tokyotosho.cat.hentai);
+                11 => ("JAV", "JAV", "JAV", source.tokyotosho.cat.jav);
+            }
+            "Other" => {
+                5 => ("Oth", "Other", "Other", source.tokyotosho.cat.other);
+            }
+        };
+        SourceInfo {
+            cats,
+            filters: TokyoToshoFilter::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sorts: TokyoToshoSort::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn load_config(config: &mut SourceConfig) {
+        if config.tokyotosho.is_none() {
+            config.tokyotosho = Some(TokyoToshoConfig::default());
+        }
+    }
+
+    fn default_category(cfg: &SourceConfig) -> usize {
+        let default = cfg
+            .tokyotosho
+            .as_ref()
+            .map(|c| c.default_category.to_owned())
+            .unwrap_or_default();
+        Self::info().entry_from_cfg(&default).id
+    }
+
+    fn default_sort(cfg: &SourceConfig) -> SelectedSort {
+        cfg.tokyotosho
+            .as_ref()
+            .map(|c| SelectedSort {
+                sort: c.default_sort as usize,
+                dir: c.default_sort_dir,
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_filter(cfg: &SourceConfig) -> usize {
+        cfg.tokyotosho
+            .as_ref()
+            .map(|c| c.default_filter as usize)
+            .unwrap_or_default()
+    }
+
+    fn default_search(cfg: &SourceConfig) -> String {
+        cfg.tokyotosho
+            .as_ref()
+            .map(|c| c.default_search.to_owned())
+            .unwrap_or_default()
+    }
+
+    fn format_table(
+        items: &[Item],
+        search: &SearchQuery,
+        config: &SourceConfig,
+        theme: &Theme,
+    ) -> ResultTable {
+        let tt = config.tokyotosho.to_owned().unwrap_or_default();
+        nyaa_table(items.into(), theme, &search.sort, &tt.columns)
+    }
+}
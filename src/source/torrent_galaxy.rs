@@ -5,6 +5,7 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use chrono::NaiveDateTime;
 use ratatui::{
     layout::{Alignment, Constraint},
     style::{Color, Stylize},
@@ -23,12 +24,15 @@ use crate::{
     theme::Theme,
     util::{
         conv::{shorten_number, to_bytes},
-        html::{as_type, attr, inner},
+        html::{as_type, attr, inner, text},
     },
     widget::sort::{SelectedSort, SortDir},
 };
 
-use super::{add_protocol, Item, ItemType, Source, SourceConfig, SourceInfo, SourceResponse};
+use super::{
+    add_protocol, Item, ItemDetails, ItemFile, ItemType, Source, SourceConfig, SourceInfo,
+    SourceResponse,
+};
 
 #[derive(Serialize, Deserialize, Clone, Copy, Default)]
 #[serde(default)]
@@ -361,6 +365,56 @@ fn get_status_color(status: String) -> Option<Color> {
     }
 }
 
+/// Parse a TorrentGalaxy post page
+pub fn parse_details(html: &str) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+    let title_sel = &sel!("div.torrentpagetable h3, #torrentpagetitle")?;
+    let row_sel = &sel!("div.tprow")?;
+    let cell_sel = &sel!("div.tpcell")?;
+    let desc_sel = &sel!("#smallguidedesc, #torpicture")?;
+    let file_sel = &sel!("table.table tr")?;
+    let name_sel = &sel!("td.table_col1")?;
+    let size_sel = &sel!("td.table_col2")?;
+    let comment_sel = &sel!("#commentsbox .comment, div.commentbody")?;
+
+    let mut details = ItemDetails {
+        title: doc.select(title_sel).next().map(text).unwrap_or_default(),
+        description: doc
+            .select(desc_sel)
+            .next()
+            .map(|e| e.text().collect::<String>().trim().to_owned())
+            .unwrap_or_default(),
+        comments: doc.select(comment_sel).count(),
+        ..Default::default()
+    };
+
+    for row in doc.select(row_sel) {
+        let mut cells = row.select(cell_sel).map(text);
+        let (Some(label), Some(value)) = (cells.next(), cells.next()) else {
+            continue;
+        };
+        match label.trim_end_matches(':').to_lowercase().as_str() {
+            "name" if details.title.is_empty() => details.title = value,
+            "uploader" => details.submitter = value,
+            "added" => details.date = value,
+            "total size" => details.size = value,
+            "info hash" => details.info_hash = value,
+            _ => {}
+        }
+    }
+
+    details.files = doc
+        .select(file_sel)
+        .filter_map(|row| {
+            Some(ItemFile {
+                path: row.select(name_sel).next().map(text)?,
+                size: row.select(size_sel).next().map(text).unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(details)
+}
+
 impl Source for TorrentGalaxyHtmlSource {
     async fn filter(
         client: &reqwest::Client,
@@ -480,7 +534,11 @@ impl Source for TorrentGalaxyHtmlSource {
                 let date = e
                     .select(date_sel)
                     .nth(0)
-                    .map(|e| e.text().collect())
+                    .map(|e| e.text().collect::<String>())
+                    .unwrap_or_default();
+                // Dates are shown as "dd/mm/yy HH:MM", but recent uploads may be relative
+                let timestamp = NaiveDateTime::parse_from_str(date.trim(), "%d/%m/%y %H:%M")
+                    .map(|d| d.and_utc().timestamp())
                     .unwrap_or_default();
                 let seeders = as_type(inner(e, seed_sel, "0")).unwrap_or_default();
                 let leechers = as_type(inner(e, leech_sel, "0")).unwrap_or_default();
@@ -545,6 +603,7 @@ impl Source for TorrentGalaxyHtmlSource {
 
                 Some(Item {
                     id,
+                    timestamp,
                     date,
                     seeders,
                     leechers,
@@ -647,6 +706,28 @@ impl Source for TorrentGalaxyHtmlSource {
         TorrentGalaxyHtmlSource::search(client, search, config, date_format).await
     }
 
+    async fn details(
+        client: &reqwest::Client,
+        item: &Item,
+        config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        let tgx = config.tgx.to_owned().unwrap_or_default();
+        let mut request = client.get(&item.post_link).header(
+            "User-Agent",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:126.0) Gecko/20100101 Firefox/126.0",
+        );
+        if let Some(timeout) = tgx.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", item.post_link, code).into());
+        }
+        parse_details(&response.text().await?)
+    }
+
     fn info() -> SourceInfo {
         let cats = cats! {
             "All Categories" => { 0 => ("---", "All Categories", "AllCategories", source.tgx.cat.all_categories); }
@@ -788,7 +869,7 @@ impl Source for TorrentGalaxyHtmlSource {
             .iter()
             .map(|item| {
                 ResultRow::new([
-                    item.icon.label.fg((item.icon.color)(theme)),
+                    item.icon.label.to_owned().fg((item.icon.color)(theme)),
                     item.extra
                         .get("lang")
                         .map(|l| get_lang(l.to_owned()))
@@ -0,0 +1,544 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use human_bytes::human_bytes;
+use ratatui::style::Color;
+use reqwest::StatusCode;
+use rss::{extension::Extension, Channel};
+use serde::{Deserialize, Serialize};
+use strum::{Display, FromRepr, VariantArray};
+use urlencoding::encode;
+
+use crate::{
+    cats, collection,
+    results::{ResultResponse, ResultTable},
+    sync::SearchQuery,
+    theme::Theme,
+    util::conv::get_hash,
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    add_protocol,
+    nyaa_html::{nyaa_table, NyaaColumns},
+    Item, ItemDetails, ItemType, Source, SourceConfig, SourceInfo, SourceResponse,
+};
+
+type ExtensionMap = BTreeMap<String, Vec<Extension>>;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct TorznabTheme {
+    #[serde(rename = "categories")]
+    pub cat: TorznabCategoryTheme,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct TorznabCategoryTheme {
+    #[serde(with = "color_to_tui")]
+    pub console: Color,
+    #[serde(with = "color_to_tui")]
+    pub movies: Color,
+    #[serde(with = "color_to_tui")]
+    pub audio: Color,
+    #[serde(with = "color_to_tui")]
+    pub pc: Color,
+    #[serde(with = "color_to_tui")]
+    pub tv: Color,
+    #[serde(with = "color_to_tui")]
+    pub anime: Color,
+    #[serde(with = "color_to_tui")]
+    pub xxx: Color,
+    #[serde(with = "color_to_tui")]
+    pub books: Color,
+    #[serde(with = "color_to_tui")]
+    pub other: Color,
+}
+
+impl Default for TorznabCategoryTheme {
+    fn default() -> Self {
+        use Color::*;
+        Self {
+            console: LightBlue,
+            movies: Magenta,
+            audio: Yellow,
+            pc: Blue,
+            tv: LightCyan,
+            anime: LightMagenta,
+            xxx: Red,
+            books: LightGreen,
+            other: Gray,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TorznabConfig {
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub default_sort: TorznabSort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: TorznabFilter,
+    pub default_category: String,
+    pub default_search: String,
+    pub limit: usize,
+    pub timeout: Option<u64>,
+    pub columns: Option<NyaaColumns>,
+}
+
+impl Default for TorznabConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:9117/api/v2.0/indexers/all/results/torznab/".to_owned(),
+            api_key: None,
+            default_sort: TorznabSort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: TorznabFilter::NoFilter,
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            limit: 100,
+            timeout: None,
+            columns: None,
+        }
+    }
+}
+
+// Discriminants line up with `NyaaSort` so results can share `nyaa_table`
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum TorznabSort {
+    #[strum(serialize = "Date")]
+    Date = 0,
+    #[strum(serialize = "Grabs")]
+    Grabs = 1,
+    #[strum(serialize = "Seeders")]
+    Seeders = 2,
+    #[strum(serialize = "Leechers")]
+    Leechers = 3,
+    #[strum(serialize = "Size")]
+    Size = 4,
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum TorznabFilter {
+    #[allow(clippy::enum_variant_names)]
+    #[strum(serialize = "No Filter")]
+    NoFilter = 0,
+    #[strum(serialize = "No Dead Torrents")]
+    NoDead = 1,
+    #[strum(serialize = "Freeleech Only")]
+    FreeleechOnly = 2,
+}
+
+pub struct TorznabSource;
+
+fn get_attr(attrs: &ExtensionMap, name: &str) -> Option<String> {
+    attrs
+        .get("attr")?
+        .iter()
+        .find(|a| a.attrs().get("name").is_some_and(|n| n == name))
+        .and_then(|a| a.attrs().get("value").cloned())
+}
+
+fn get_attrs(attrs: &ExtensionMap, name: &str) -> Vec<String> {
+    attrs
+        .get("attr")
+        .map(|v| {
+            v.iter()
+                .filter(|a| a.attrs().get("name").is_some_and(|n| n == name))
+                .filter_map(|a| a.attrs().get("value").cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the body of a Torznab `t=search` response into items.
+pub fn parse_torznab<S: Source>(
+    bytes: &[u8],
+    date_format: Option<String>,
+    limit: usize,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    let channel = Channel::read_from(bytes)?;
+
+    let info = S::info();
+    let empty = ExtensionMap::new();
+    let items: Vec<Item> = channel
+        .items
+        .iter()
+        .filter_map(|item| {
+            // Jackett and Prowlarr both use the `torznab` prefix, but older indexers still
+            // send the `newznab` namespace for the same attributes
+            let ext = item
+                .extensions()
+                .get("torznab")
+                .or(item.extensions().get("newznab"))
+                .unwrap_or(&empty);
+
+            let title = item.title()?.to_owned();
+            let guid = item
+                .guid()
+                .map(|g| g.value().to_owned())
+                .or(item.link().map(ToOwned::to_owned))?;
+
+            let enclosure = item.enclosure().map(|e| e.url().to_owned());
+            let link = item.link().map(ToOwned::to_owned);
+            let mut magnet_link = get_attr(ext, "magneturl").unwrap_or_default();
+            let mut torrent_link = String::new();
+            for l in [enclosure, link].into_iter().flatten() {
+                if l.starts_with("magnet:") {
+                    if magnet_link.is_empty() {
+                        magnet_link = l;
+                    }
+                } else if torrent_link.is_empty() {
+                    torrent_link = l;
+                }
+            }
+
+            let hash = get_attr(ext, "infohash")
+                .or(get_hash(magnet_link.clone()))
+                .map(|h| h.to_lowercase());
+            if magnet_link.is_empty() {
+                if let Some(hash) = hash.as_ref() {
+                    magnet_link = format!("magnet:?xt=urn:btih:{}&dn={}", hash, encode(&title));
+                }
+            }
+
+            let bytes = get_attr(ext, "size")
+                .and_then(|s| s.parse::<usize>().ok())
+                .or(item
+                    .enclosure()
+                    .and_then(|e| e.length().parse::<usize>().ok()))
+                .unwrap_or_default();
+            let size = human_bytes(bytes as f64).replace('i', "");
+
+            let seeders = get_attr(ext, "seeders")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default();
+            let leechers = match get_attr(ext, "leechers").and_then(|s| s.parse().ok()) {
+                Some(l) => l,
+                // `peers` includes seeders
+                None => get_attr(ext, "peers")
+                    .and_then(|s| s.parse::<u32>().ok())
+                    .unwrap_or_default()
+                    .saturating_sub(seeders),
+            };
+            let downloads = get_attr(ext, "grabs")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default();
+
+            // Prefer the most specific category we know about
+            let cats = get_attrs(ext, "category")
+                .into_iter()
+                .chain(item.categories().iter().map(|c| c.name().to_owned()))
+                .filter_map(|c| c.parse::<usize>().ok())
+                .collect::<Vec<usize>>();
+            let cat = cats
+                .iter()
+                .find_map(|c| {
+                    info.cats
+                        .iter()
+                        .flat_map(|c| c.entries.iter())
+                        .find(|e| e.id == *c)
+                })
+                .or(cats.iter().find_map(|c| {
+                    info.cats
+                        .iter()
+                        .flat_map(|c| c.entries.iter())
+                        .find(|e| e.id == c / 1000 * 1000)
+                }))
+                .cloned()
+                .unwrap_or(info.cats[0].entries[0].clone());
+
+            let pub_date = item.pub_date().unwrap_or("");
+            let date = DateTime::parse_from_rfc2822(pub_date).unwrap_or_default();
+            let date = date.with_timezone(&Local);
+            let date_format = date_format
+                .to_owned()
+                .unwrap_or("%Y-%m-%d %H:%M".to_owned());
+
+            let freeleech = get_attr(ext, "downloadvolumefactor").is_some_and(|f| f == "0");
+            let id = format!("torznab-{}", hash.clone().unwrap_or(guid.clone()));
+            let file_name = format!("{}.torrent", hash.clone().unwrap_or(id.clone()));
+            let post_link = item
+                .comments()
+                .map(ToOwned::to_owned)
+                .unwrap_or(guid.clone());
+
+            let mut extra: HashMap<String, String> = collection![
+                "freeleech".to_owned() => freeleech.to_string(),
+            ];
+            if let Some(hash) = hash {
+                extra.insert("hash".to_owned(), hash);
+            }
+
+            Some(Item {
+                id,
+                date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders,
+                leechers,
+                downloads,
+                size,
+                bytes,
+                title,
+                torrent_link,
+                magnet_link,
+                post_link,
+                file_name,
+                category: cat.id,
+                icon: cat.icon,
+                // Freeleech says nothing about the uploader, so it's only kept in `extra`
+                item_type: ItemType::None,
+                extra,
+            })
+        })
+        .collect();
+
+    // `<torznab:response offset="0" total="1234"/>` is optional, so fall back to a single page
+    let total_results = channel
+        .extensions()
+        .get("torznab")
+        .or(channel.extensions().get("newznab"))
+        .and_then(|e| e.get("response"))
+        .and_then(|r| r.first())
+        .and_then(|r| r.attrs().get("total"))
+        .and_then(|t| t.parse::<usize>().ok())
+        .unwrap_or(items.len());
+    let last_page = total_results.div_ceil(limit.max(1)).max(1);
+
+    Ok(ResultResponse {
+        items,
+        total_results,
+        last_page,
+    })
+}
+
+fn sort_items(items: &mut [Item], sort: SelectedSort) {
+    let f: fn(&Item, &Item) -> Ordering = match TorznabSort::from_repr(sort.sort) {
+        Some(TorznabSort::Grabs) => |a, b| b.downloads.cmp(&a.downloads),
+        Some(TorznabSort::Seeders) => |a, b| b.seeders.cmp(&a.seeders),
+        Some(TorznabSort::Leechers) => |a, b| b.leechers.cmp(&a.leechers),
+        Some(TorznabSort::Size) => |a, b| b.bytes.cmp(&a.bytes),
+        // Indexers already return results newest first
+        _ => |_, _| Ordering::Equal,
+    };
+    items.sort_by(f);
+    if sort.dir == SortDir::Asc {
+        items.reverse();
+    }
+}
+
+fn filter_items(items: &mut Vec<Item>, filter: usize) {
+    match TorznabFilter::from_repr(filter) {
+        Some(TorznabFilter::NoDead) => items.retain(|i| i.seeders > 0),
+        Some(TorznabFilter::FreeleechOnly) => {
+            items.retain(|i| i.extra.get("freeleech").is_some_and(|f| f == "true"))
+        }
+        _ => {}
+    }
+}
+
+impl Source for TorznabSource {
+    async fn search(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        let torznab = config.torznab.to_owned().unwrap_or_default();
+        let base_url = add_protocol(torznab.base_url, false)?;
+        let limit = torznab.limit.max(1);
+        let offset = search.page.saturating_sub(1) * limit;
+
+        let mut query = format!(
+            "t=search&extended=1&q={}&offset={}&limit={}",
+            encode(&search.query),
+            offset,
+            limit
+        );
+        if search.category != 0 {
+            query.push_str(&format!("&cat={}", search.category));
+        }
+        if let Some(api_key) = torznab.api_key {
+            query.push_str(&format!("&apikey={}", encode(&api_key)));
+        }
+        let mut url = base_url.join("api")?;
+        // Jackett and Prowlarr endpoints already end in `/api`
+        if base_url.path().trim_end_matches('/').ends_with("/api") {
+            url = base_url.clone();
+        }
+        url.set_query(Some(&query));
+
+        let mut request = client.get(url.to_owned());
+        if let Some(timeout) = torznab.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", base_url, code).into());
+        }
+        let bytes = response.bytes().await?;
+
+        let mut res = parse_torznab::<Self>(&bytes[..], date_format, limit)?;
+        filter_items(&mut res.items, search.filter);
+        // Torznab has no sort parameter, so sort the page locally
+        sort_items(&mut res.items, search.sort);
+        Ok(SourceResponse::Results(res))
+    }
+    async fn sort(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TorznabSource::search(client, search, config, date_format).await
+    }
+    async fn filter(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TorznabSource::search(client, search, config, date_format).await
+    }
+    async fn categorize(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TorznabSource::search(client, search, config, date_format).await
+    }
+    async fn solve(
+        _solution: String,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        TorznabSource::search(client, search, config, date_format).await
+    }
+
+    async fn details(
+        _client: &reqwest::Client,
+        _item: &Item,
+        _config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        Err("Details are not supported for Torznab".into())
+    }
+
+    fn info() -> SourceInfo {
+        let cats = cats! {
+            "All Categories" => {
+                0 => ("---", "All Categories", "AllCategories", fg);
+            }
+            "Console" => {
+                1000 => ("Con", "All Console", "AllConsole", source.torznab.cat.console);
+            }
+            "Movies" => {
+                2000 => ("Mov", "All Movies", "AllMovies", source.torznab.cat.movies);
+                2030 => ("SdM", "SD Movies", "MoviesSD", source.torznab.cat.movies);
+                2040 => ("HdM", "HD Movies", "MoviesHD", source.torznab.cat.movies);
+                2045 => ("4kM", "UHD Movies", "MoviesUHD", source.torznab.cat.movies);
+            }
+            "Audio" => {
+                3000 => ("Aud", "All Audio", "AllAudio", source.torznab.cat.audio);
+                3010 => ("Mp3", "MP3", "AudioMP3", source.torznab.cat.audio);
+                3040 => ("Los", "Lossless", "AudioLossless", source.torznab.cat.audio);
+                3030 => ("Abk", "Audiobook", "AudioAudiobook", source.torznab.cat.audio);
+            }
+            "PC" => {
+                4000 => ("PC ", "All PC", "AllPC", source.torznab.cat.pc);
+                4050 => ("Gam", "Games", "PCGames", source.torznab.cat.pc);
+            }
+            "TV" => {
+                5000 => ("TV ", "All TV", "AllTV", source.torznab.cat.tv);
+                5030 => ("SdT", "SD TV", "TVSD", source.torznab.cat.tv);
+                5040 => ("HdT", "HD TV", "TVHD", source.torznab.cat.tv);
+                5045 => ("4kT", "UHD TV", "TVUHD", source.torznab.cat.tv);
+                5070 => ("Ani", "Anime", "TVAnime", source.torznab.cat.anime);
+            }
+            "XXX" => {
+                6000 => ("XXX", "All XXX", "AllXXX", source.torznab.cat.xxx);
+            }
+            "Books" => {
+                7000 => ("Bks", "All Books", "AllBooks", source.torznab.cat.books);
+                7020 => ("Ebk", "Ebooks", "BooksEbook", source.torznab.cat.books);
+                7030 => ("Com", "Comics", "BooksComics", source.torznab.cat.books);
+            }
+            "Other" => {
+                8000 => ("Oth", "All Other", "AllOther", source.torznab.cat.other);
+            }
+        };
+        SourceInfo {
+            cats,
+            filters: TorznabFilter::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sorts: TorznabSort::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn load_config(config: &mut SourceConfig) {
+        if config.torznab.is_none() {
+            config.torznab = Some(TorznabConfig::default());
+        }
+    }
+
+    fn default_category(cfg: &SourceConfig) -> usize {
+        let default = cfg
+            .torznab
+            .as_ref()
+            .map(|c| c.default_category.to_owned())
+            .unwrap_or_default();
+        Self::info().entry_from_cfg(&default).id
+    }
+
+    fn default_sort(cfg: &SourceConfig) -> SelectedSort {
+        cfg.torznab
+            .as_ref()
+            .map(|c| SelectedSort {
+                sort: c.default_sort as usize,
+                dir: c.default_sort_dir,
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_filter(cfg: &SourceConfig) -> usize {
+        cfg.torznab
+            .as_ref()
+            .map(|c| c.default_filter as usize)
+            .unwrap_or_default()
+    }
+
+    fn default_search(cfg: &SourceConfig) -> String {
+        cfg.torznab
+            .as_ref()
+            .map(|c| c.default_search.to_owned())
+            .unwrap_or_default()
+    }
+
+    fn format_table(
+        items: &[Item],
+        search: &SearchQuery,
+        config: &SourceConfig,
+        theme: &Theme,
+    ) -> ResultTable {
+        let torznab = config.torznab.to_owned().unwrap_or_default();
+        nyaa_table(items.into(), theme, &search.sort, &torznab.columns)
+    }
+}
@@ -0,0 +1,616 @@
+use std::{cmp::max, error::Error, time::Duration};
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use ratatui::{
+    layout::{Alignment, Constraint},
+    style::{Color, Stylize as _},
+};
+use reqwest::{StatusCode, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use strum::{Display, FromRepr, VariantArray};
+use urlencoding::encode;
+
+use crate::{
+    cats, cond_vec,
+    results::{ResultColumn, ResultHeader, ResultResponse, ResultRow, ResultTable},
+    sel,
+    sync::SearchQuery,
+    theme::Theme,
+    util::{
+        conv::{get_hash, to_bytes},
+        html::{as_type, attr, inner},
+    },
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use super::{
+    add_protocol, Item, ItemDetails, ItemType, Source, SourceConfig, SourceInfo, SourceResponse,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct X1337Theme {
+    #[serde(rename = "categories")]
+    pub cat: X1337CategoryTheme,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct X1337CategoryTheme {
+    #[serde(with = "color_to_tui")]
+    pub movies: Color,
+    #[serde(with = "color_to_tui")]
+    pub tv: Color,
+    #[serde(with = "color_to_tui")]
+    pub games: Color,
+    #[serde(with = "color_to_tui")]
+    pub music: Color,
+    #[serde(with = "color_to_tui")]
+    pub apps: Color,
+    #[serde(with = "color_to_tui")]
+    pub documentaries: Color,
+    #[serde(with = "color_to_tui")]
+    pub anime: Color,
+    #[serde(with = "color_to_tui")]
+    pub other: Color,
+    #[serde(with = "color_to_tui")]
+    pub xxx: Color,
+}
+
+impl Default for X1337CategoryTheme {
+    fn default() -> Self {
+        use Color::*;
+        Self {
+            movies: LightMagenta,
+            tv: LightGreen,
+            games: LightCyan,
+            music: Yellow,
+            apps: LightBlue,
+            documentaries: LightYellow,
+            anime: Magenta,
+            other: Gray,
+            xxx: LightRed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct X1337Config {
+    pub base_url: String,
+    pub default_sort: X1337Sort,
+    pub default_sort_dir: SortDir,
+    pub default_filter: X1337Filter,
+    pub default_category: String,
+    pub default_search: String,
+    pub timeout: Option<u64>,
+    pub columns: Option<X1337Columns>,
+}
+
+impl Default for X1337Config {
+    fn default() -> Self {
+        Self {
+            base_url: "https://1337x.to/".to_owned(),
+            default_sort: X1337Sort::Date,
+            default_sort_dir: SortDir::Desc,
+            default_filter: X1337Filter::NoFilter,
+            default_category: "AllCategories".to_owned(),
+            default_search: Default::default(),
+            timeout: None,
+            columns: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Default)]
+pub struct X1337Columns {
+    category: Option<bool>,
+    title: Option<bool>,
+    uploader: Option<bool>,
+    size: Option<bool>,
+    date: Option<bool>,
+    seeders: Option<bool>,
+    leechers: Option<bool>,
+}
+
+impl X1337Columns {
+    fn array(self) -> [bool; 7] {
+        [
+            self.category.unwrap_or(true),
+            self.title.unwrap_or(true),
+            self.uploader.unwrap_or(true),
+            self.size.unwrap_or(true),
+            self.date.unwrap_or(true),
+            self.seeders.unwrap_or(true),
+            self.leechers.unwrap_or(true),
+        ]
+    }
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum X1337Sort {
+    #[strum(serialize = "Date")]
+    Date = 0,
+    #[strum(serialize = "Seeders")]
+    Seeders = 1,
+    #[strum(serialize = "Leechers")]
+    Leechers = 2,
+    #[strum(serialize = "Size")]
+    Size = 3,
+}
+
+impl X1337Sort {
+    fn to_url(self) -> &'static str {
+        match self {
+            X1337Sort::Date => "time",
+            X1337Sort::Seeders => "seeders",
+            X1337Sort::Leechers => "leechers",
+            X1337Sort::Size => "size",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Display, Clone, Copy, VariantArray, PartialEq, Eq, FromRepr)]
+#[repr(usize)]
+pub enum X1337Filter {
+    #[allow(clippy::enum_variant_names)]
+    #[strum(serialize = "No Filter")]
+    NoFilter = 0,
+    #[strum(serialize = "No Dead Torrents")]
+    NoDead = 1,
+}
+
+pub struct X1337Source;
+
+/// The name used for a category in urls, like `/category-search/{query}/Movies/1/`
+fn category_name(id: usize) -> Option<&'static str> {
+    match id {
+        1 => Some("Movies"),
+        2 => Some("TV"),
+        3 => Some("Games"),
+        4 => Some("Music"),
+        5 => Some("Apps"),
+        6 => Some("Documentaries"),
+        7 => Some("Anime"),
+        8 => Some("Other"),
+        9 => Some("XXX"),
+        _ => None,
+    }
+}
+
+/// Results only link to their subcategory, like `/sub/41/0/`, so map it back to
+/// the category it belongs to
+fn subcategory(sub: usize) -> usize {
+    match sub {
+        1..=4 | 42 | 54 | 55 | 66 | 70 | 73 | 76 => 1,
+        5..=7 | 41 | 71 | 74 | 75 => 2,
+        10..=17 | 43..=46 | 72 | 77 | 82 => 3,
+        22..=27 | 53 | 58..=60 => 4,
+        18..=21 | 56 | 57 => 5,
+        9 => 6,
+        28 | 78..=80 => 7,
+        48..=51 | 67 => 9,
+        _ => 8,
+    }
+}
+
+fn get_url(base_url: String, search: &SearchQuery) -> Result<Url, Box<dyn Error + Send + Sync>> {
+    let base_url = add_protocol(base_url, true)?;
+    let page = search.page.max(1);
+    let sort = X1337Sort::from_repr(search.sort.sort).unwrap_or(X1337Sort::Date);
+    let dir = match search.sort.dir {
+        SortDir::Desc => "desc",
+        SortDir::Asc => "asc",
+    };
+    let query = encode(&search.query);
+    let cat = category_name(search.category);
+
+    // Searching requires a query, so browse the category listing without one
+    let path = match (search.query.is_empty(), cat) {
+        (true, Some(cat)) => format!("sort-cat/{}/{}/{}/{}/", cat, sort.to_url(), dir, page),
+        (true, None) => "trending".to_owned(),
+        (false, Some(cat)) => format!(
+            "sort-category-search/{}/{}/{}/{}/{}/",
+            query,
+            cat,
+            sort.to_url(),
+            dir,
+            page
+        ),
+        (false, None) => format!("sort-search/{}/{}/{}/{}/", query, sort.to_url(), dir, page),
+    };
+    Ok(base_url.join(&path)?)
+}
+
+/// Dates are either a time for today's uploads, like `9:30am`, or a day like
+/// `Oct. 14th '23`
+fn parse_date(date: &str) -> NaiveDateTime {
+    let date = date.trim();
+    if let Ok(time) = NaiveTime::parse_from_str(&date.to_uppercase(), "%-I:%M%p") {
+        return Local::now().date_naive().and_time(time);
+    }
+    let cleaned = date
+        .replace('.', "")
+        .split_whitespace()
+        .map(
+            |w| match w.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+                true => w.trim_end_matches(char::is_alphabetic).to_owned(),
+                false => w.to_owned(),
+            },
+        )
+        .collect::<Vec<String>>()
+        .join(" ");
+    NaiveDate::parse_from_str(&cleaned, "%b %d '%y")
+        .or_else(|_| {
+            // Uploads from this year leave out the year
+            NaiveDate::parse_from_str(
+                &format!("{} '{:02}", cleaned, Local::now().year() % 100),
+                "%b %d '%y",
+            )
+        })
+        .unwrap_or_default()
+        .and_time(NaiveTime::default())
+}
+
+/// Parse a 1337x search or listing page into items. Results don't include
+/// any links to download them, those are added by [`X1337Source::resolve`].
+pub fn parse_1337x<S: Source>(
+    html: &str,
+    base_url: &Url,
+    date_format: Option<String>,
+    page: usize,
+) -> Result<ResultResponse, Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+
+    let row_sel = &sel!("table.table-list > tbody > tr")?;
+    let title_sel = &sel!("td.name > a[href^=\"/torrent/\"]")?;
+    let sub_sel = &sel!("td.name > a[href^=\"/sub/\"]")?;
+    let seeders_sel = &sel!("td.seeds")?;
+    let leechers_sel = &sel!("td.leeches")?;
+    let date_sel = &sel!("td.coll-date")?;
+    let size_sel = &sel!("td.size")?;
+    let uploader_sel = &sel!("td.coll-5 > a")?;
+    let pages_sel = &sel!("div.pagination li > a")?;
+
+    let info = S::info();
+    let items = doc
+        .select(row_sel)
+        .filter_map(|e| {
+            let href = attr(e, title_sel, "href");
+            let post_link = base_url.join(&href).ok()?;
+            // Links look like `/torrent/{id}/{name}/`
+            let id = href.split('/').nth(2).filter(|id| !id.is_empty())?;
+            let title = inner(e, title_sel, "???");
+
+            let sub = attr(e, sub_sel, "href")
+                .split('/')
+                .nth(2)
+                .and_then(|s| s.parse::<usize>().ok())
+                .unwrap_or_default();
+            let cat = info.clone().entry_from_id(subcategory(sub));
+
+            // The size cell also holds the seeders on small screens
+            let size = e
+                .select(size_sel)
+                .next()
+                .and_then(|s| s.text().next())
+                .unwrap_or_default()
+                .trim()
+                .to_owned();
+            let date: DateTime<Local> =
+                Local.from_utc_datetime(&parse_date(&inner(e, date_sel, "")));
+            let date_format = date_format.to_owned().unwrap_or("%Y-%m-%d".to_owned());
+
+            let mut item = Item {
+                id: format!("1337x-{}", id),
+                date: date.format(&date_format).to_string(),
+                timestamp: date.timestamp(),
+                seeders: as_type(inner(e, seeders_sel, "0")).unwrap_or_default(),
+                leechers: as_type(inner(e, leechers_sel, "0")).unwrap_or_default(),
+                bytes: to_bytes(&size),
+                size,
+                title,
+                post_link: post_link.to_string(),
+                file_name: format!("{}.torrent", id),
+                category: cat.id,
+                icon: cat.icon,
+                item_type: ItemType::None,
+                ..Default::default()
+            };
+            let uploader = inner(e, uploader_sel, "");
+            if !uploader.is_empty() {
+                item.extra.insert("uploader".to_owned(), uploader);
+            }
+            Some(item)
+        })
+        .collect::<Vec<Item>>();
+
+    let last_page = doc
+        .select(pages_sel)
+        .filter_map(|a| {
+            a.value()
+                .attr("href")?
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()?
+                .parse::<usize>()
+                .ok()
+        })
+        .max()
+        .unwrap_or_default()
+        .max(page.max(1));
+    Ok(ResultResponse {
+        total_results: items.len(),
+        items,
+        last_page,
+    })
+}
+
+/// Get the links to download an item from its page, as `(magnet, torrent)`
+pub fn parse_1337x_links(html: &str) -> Result<(String, String), Box<dyn Error + Send + Sync>> {
+    let doc = Html::parse_document(html);
+    let magnet_sel = &sel!("a[href^=\"magnet:\"]")?;
+    let torrent_sel = &sel!("a[href$=\".torrent\"]")?;
+
+    let root = doc.root_element();
+    let magnet = attr(root, magnet_sel, "href");
+    if magnet.is_empty() {
+        return Err("No magnet link found".into());
+    }
+    Ok((magnet, attr(root, torrent_sel, "href")))
+}
+
+impl Source for X1337Source {
+    async fn search(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        let x1337 = config.x1337.to_owned().unwrap_or_default();
+        let url = get_url(x1337.base_url, search)?;
+
+        let mut request = client.get(url.to_owned());
+        if let Some(timeout) = x1337.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            // Throw error if response code is not OK
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", url, code).into());
+        }
+        let content = response.text().await?;
+
+        let mut res = parse_1337x::<Self>(&content, &url, date_format, search.page)?;
+        if X1337Filter::from_repr(search.filter) == Some(X1337Filter::NoDead) {
+            res.items.retain(|i| i.seeders > 0);
+        }
+        Ok(SourceResponse::Results(res))
+    }
+    async fn sort(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        X1337Source::search(client, search, config, date_format).await
+    }
+    async fn filter(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        X1337Source::search(client, search, config, date_format).await
+    }
+    async fn categorize(
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        X1337Source::search(client, search, config, date_format).await
+    }
+    async fn solve(
+        _solution: String,
+        client: &reqwest::Client,
+        search: &SearchQuery,
+        config: &SourceConfig,
+        date_format: Option<String>,
+    ) -> Result<SourceResponse, Box<dyn Error + Send + Sync>> {
+        X1337Source::search(client, search, config, date_format).await
+    }
+
+    async fn details(
+        _client: &reqwest::Client,
+        _item: &Item,
+        _config: &SourceConfig,
+    ) -> Result<ItemDetails, Box<dyn Error + Send + Sync>> {
+        Err("Details are not supported for 1337x".into())
+    }
+
+    async fn resolve(
+        client: &reqwest::Client,
+        mut item: Item,
+        config: &SourceConfig,
+    ) -> Result<Item, Box<dyn Error + Send + Sync>> {
+        // Already resolved, like when downloading again from history
+        if !item.magnet_link.is_empty() {
+            return Ok(item);
+        }
+        let x1337 = config.x1337.to_owned().unwrap_or_default();
+        let mut request = client.get(&item.post_link);
+        if let Some(timeout) = x1337.timeout {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        if response.status() != StatusCode::OK {
+            let code = response.status().as_u16();
+            return Err(format!("{}\nInvalid response code: {}", item.post_link, code).into());
+        }
+        let (magnet, torrent) = parse_1337x_links(&response.text().await?)?;
+
+        if let Some(hash) = get_hash(magnet.clone()) {
+            item.extra.insert("hash".to_owned(), hash.to_lowercase());
+        }
+        item.magnet_link = magnet;
+        item.torrent_link = torrent;
+        Ok(item)
+    }
+
+    fn info() -> SourceInfo {
+        let cats = cats! {
+            "All Categories" => {
+                0 => ("---", "All Categories", "AllCategories", fg);
+            }
+            "Video" => {
+                1 => ("Mov", "Movies", "Movies", source.x1337.cat.movies);
+                2 => ("TV ", "TV", "TV", source.x1337.cat.tv);
+                6 => ("Doc", "Documentaries", "Documentaries", source.x1337.cat.documentaries);
+                7 => ("Ani", "Anime", "Anime", source.x1337.cat.anime);
+            }
+            "Software" => {
+                3 => ("Gam", "Games", "Games", source.x1337.cat.games);
+                5 => ("App", "Apps", "Apps", source.x1337.cat.apps);
+            }
+            "Music" => {
+                4 => ("Mus", "Music", "Music", source.x1337.cat.music);
+            }
+            "Other" => {
+                8 => ("Oth", "Other", "Other", source.x1337.cat.other);
+                9 => ("XXX", "XXX", "XXX", source.x1337.cat.xxx);
+            }
+        };
+        SourceInfo {
+            cats,
+            filters: X1337Filter::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sorts: X1337Sort::VARIANTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn load_config(config: &mut SourceConfig) {
+        if config.x1337.is_none() {
+            config.x1337 = Some(X1337Config::default());
+        }
+    }
+
+    fn default_category(cfg: &SourceConfig) -> usize {
+        let default = cfg
+            .x1337
+            .as_ref()
+            .map(|c| c.default_category.to_owned())
+            .unwrap_or_default();
+        Self::info().entry_from_cfg(&default).id
+    }
+
+    fn default_sort(cfg: &SourceConfig) -> SelectedSort {
+        cfg.x1337
+            .as_ref()
+            .map(|c| SelectedSort {
+                sort: c.default_sort as usize,
+                dir: c.default_sort_dir,
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_filter(cfg: &SourceConfig) -> usize {
+        cfg.x1337
+            .as_ref()
+            .map(|c| c.default_filter as usize)
+            .unwrap_or_default()
+    }
+
+    fn default_search(cfg: &SourceConfig) -> String {
+        cfg.x1337
+            .as_ref()
+            .map(|c| c.default_search.to_owned())
+            .unwrap_or_default()
+    }
+
+    fn format_table(
+        items: &[Item],
+        search: &SearchQuery,
+        config: &SourceConfig,
+        theme: &Theme,
+    ) -> ResultTable {
+        let x1337 = config.x1337.to_owned().unwrap_or_default();
+        let raw_date_width = items.iter().map(|i| i.date.len()).max().unwrap_or_default() as u16;
+        let date_width = max(raw_date_width, 6);
+        let raw_uploader_width = items
+            .iter()
+            .map(|i| i.extra.get("uploader").map(|u| u.len()).unwrap_or(0))
+            .max()
+            .unwrap_or_default() as u16;
+        let uploader_width = max(raw_uploader_width, 8);
+
+        let header = ResultHeader::new([
+            ResultColumn::Normal("Cat".to_owned(), Constraint::Length(3)),
+            ResultColumn::Normal("Name".to_owned(), Constraint::Min(3)),
+            ResultColumn::Normal("Uploader".to_owned(), Constraint::Length(uploader_width)),
+            ResultColumn::Sorted("Size".to_owned(), 9, X1337Sort::Size as u32),
+            ResultColumn::Sorted("Date".to_owned(), date_width, X1337Sort::Date as u32),
+            ResultColumn::Sorted("".to_owned(), 4, X1337Sort::Seeders as u32),
+            ResultColumn::Sorted("".to_owned(), 4, X1337Sort::Leechers as u32),
+        ]);
+        let mut binding = header.get_binding();
+        let align = [
+            Alignment::Left,
+            Alignment::Left,
+            Alignment::Left,
+            Alignment::Right,
+            Alignment::Left,
+            Alignment::Right,
+            Alignment::Right,
+        ];
+        let mut rows: Vec<ResultRow> = items
+            .iter()
+            .map(|item| {
+                ResultRow::new([
+                    item.icon.label.to_owned().fg((item.icon.color)(theme)),
+                    item.title.to_owned().fg(theme.fg),
+                    item.extra
+                        .get("uploader")
+                        .cloned()
+                        .unwrap_or_default()
+                        .fg(theme.fg),
+                    item.size.clone().fg(theme.fg),
+                    item.date.clone().fg(theme.fg),
+                    item.seeders.to_string().fg(theme.success),
+                    item.leechers.to_string().fg(theme.error),
+                ])
+                .aligned(align)
+                .fg(theme.fg)
+            })
+            .collect();
+        let mut headers = header.get_row(search.sort.dir, search.sort.sort as u32);
+        if let Some(columns) = x1337.columns {
+            let cols = columns.array();
+
+            headers.cells = cond_vec!(cols ; headers.cells);
+            rows = rows
+                .clone()
+                .into_iter()
+                .map(|mut r| {
+                    r.cells = cond_vec!(cols ; r.cells.to_owned());
+                    r
+                })
+                .collect::<Vec<ResultRow>>();
+            binding = cond_vec!(cols ; binding);
+        }
+
+        ResultTable {
+            headers,
+            rows,
+            binding,
+        }
+    }
+}
@@ -10,14 +10,21 @@ use tokio::sync::mpsc;
 
 use crate::{
     app::LoadType,
-    client::{Client, ClientConfig, DownloadResult},
+    client::{ClientConfig, DownloadResult, Profile, StatusResult, TorrentAction},
     config::CONFIG_FILE,
     results::Results,
-    source::{Item, SourceConfig, SourceResponse, SourceResults, Sources},
+    routing::{self, RoutingRule},
+    source::{Item, ItemDetails, SourceConfig, SourceResponse, SourceResults, Sources},
     theme::{Theme, THEMES_PATH},
+    torrent::{self, TorrentInfo},
     widget::sort::SelectedSort,
 };
 
+/// The details of the item with the id
+pub type DetailsResult = (String, Result<ItemDetails, Box<dyn Error + Send + Sync>>);
+/// The torrent file of the item with the id
+pub type TorrentResult = (String, Result<TorrentInfo, Box<dyn Error + Send + Sync>>);
+
 pub trait EventSync {
     #[allow(clippy::too_many_arguments)]
     fn load_results(
@@ -31,6 +38,13 @@ pub trait EventSync {
         theme: Theme,
         date_format: Option<String>,
     ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
+    fn load_info(
+        self,
+        tx_info: mpsc::Sender<Result<(), Box<dyn Error + Send + Sync>>>,
+        src: Sources,
+        config: SourceConfig,
+    ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
+    #[allow(clippy::too_many_arguments)]
     fn download(
         self,
         tx_dl: mpsc::Sender<DownloadResult>,
@@ -38,7 +52,43 @@ pub trait EventSync {
         items: Vec<Item>,
         config: ClientConfig,
         rq_client: reqwest::Client,
-        client: Client,
+        client: Profile,
+        src: Sources,
+        routing: Vec<RoutingRule>,
+        source_client: reqwest::Client,
+        sources: SourceConfig,
+    ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
+    fn load_details(
+        self,
+        tx_details: mpsc::Sender<DetailsResult>,
+        src: Sources,
+        client: reqwest::Client,
+        item: Item,
+        config: SourceConfig,
+    ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
+    fn load_torrent(
+        self,
+        tx_torrent: mpsc::Sender<TorrentResult>,
+        client: reqwest::Client,
+        item: Item,
+    ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
+    fn load_status(
+        self,
+        tx_status: mpsc::Sender<StatusResult>,
+        hashes: Vec<(Profile, Vec<String>)>,
+        config: ClientConfig,
+        rq_client: reqwest::Client,
+    ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
+    #[allow(clippy::too_many_arguments)]
+    fn manage_torrent(
+        self,
+        tx_manage: mpsc::Sender<Result<String, Box<dyn Error + Send + Sync>>>,
+        hash: String,
+        name: String,
+        action: TorrentAction,
+        config: ClientConfig,
+        rq_client: reqwest::Client,
+        client: Profile,
     ) -> impl std::future::Future<Output = ()> + std::marker::Send + 'static;
     fn read_event_loop(
         self,
@@ -123,15 +173,100 @@ impl EventSync for AppSync {
         items: Vec<Item>,
         config: ClientConfig,
         rq_client: reqwest::Client,
-        client: Client,
+        client: Profile,
+        src: Sources,
+        routing: Vec<RoutingRule>,
+        source_client: reqwest::Client,
+        sources: SourceConfig,
     ) {
-        let res = match batch {
-            true => client.batch_download(items, config, rq_client).await,
-            false => client.download(items[0].clone(), config, rq_client).await,
-        };
+        let requested = items.iter().map(|i| i.id.to_owned()).collect();
+        let (items, errors) = src.resolve(&source_client, items, &sources).await;
+        let mut res = routing::download(
+            &routing,
+            Some(&src),
+            batch,
+            items.clone(),
+            &client,
+            &config,
+            rq_client,
+            source_client,
+        )
+        .await;
+        res.errors.extend(errors);
+        res.resolved = items;
+        res.requested = requested;
         let _ = tx_dl.send(res).await;
     }
 
+    async fn load_info(
+        self,
+        tx_info: mpsc::Sender<Result<(), Box<dyn Error + Send + Sync>>>,
+        src: Sources,
+        config: SourceConfig,
+    ) {
+        let res = src.load_info(&config).await;
+        let _ = tx_info.send(res).await;
+    }
+
+    async fn load_details(
+        self,
+        tx_details: mpsc::Sender<DetailsResult>,
+        src: Sources,
+        client: reqwest::Client,
+        item: Item,
+        config: SourceConfig,
+    ) {
+        let res = src.details(&client, &item, &config).await;
+        let _ = tx_details.send((item.id, res)).await;
+    }
+
+    async fn load_torrent(
+        self,
+        tx_torrent: mpsc::Sender<TorrentResult>,
+        client: reqwest::Client,
+        item: Item,
+    ) {
+        let res = torrent::fetch_torrent(&client, &item.torrent_link).await;
+        let _ = tx_torrent.send((item.id, res)).await;
+    }
+
+    async fn load_status(
+        self,
+        tx_status: mpsc::Sender<StatusResult>,
+        hashes: Vec<(Profile, Vec<String>)>,
+        config: ClientConfig,
+        rq_client: reqwest::Client,
+    ) {
+        let mut res = vec![];
+        for (client, hashes) in hashes.into_iter() {
+            let found = client
+                .list_torrents(hashes, config.clone(), rq_client.clone())
+                .await;
+            res.push((client, found));
+        }
+        let _ = tx_status.send(res).await;
+    }
+
+    async fn manage_torrent(
+        self,
+        tx_manage: mpsc::Sender<Result<String, Box<dyn Error + Send + Sync>>>,
+        hash: String,
+        name: String,
+        action: TorrentAction,
+        config: ClientConfig,
+        rq_client: reqwest::Client,
+        client: Profile,
+    ) {
+        let res = match client
+            .manage(vec![hash], action.clone(), config, rq_client)
+            .await
+        {
+            Ok(()) => Ok(action.success_msg(&name)),
+            Err(e) => Err(format!("Failed to {} \"{}\":\n{}", action, name, e).into()),
+        };
+        let _ = tx_manage.send(res).await;
+    }
+
     async fn read_event_loop(self, tx_evt: mpsc::Sender<Event>) {
         loop {
             if let Ok(evt) = event::read() {
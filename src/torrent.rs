@@ -0,0 +1,179 @@
+use std::error::Error;
+
+use human_bytes::human_bytes;
+use indexmap::IndexMap;
+use reqwest::StatusCode;
+
+use crate::util::bencode::{self, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TorrentFile {
+    pub path: Vec<String>,
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TorrentInfo {
+    pub name: String,
+    pub info_hash: String,
+    pub files: Vec<TorrentFile>,
+    pub total_size: u64,
+    pub piece_length: u64,
+    pub pieces: usize,
+    pub trackers: Vec<String>,
+    pub private: bool,
+    pub comment: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Default)]
+struct FileNode {
+    size: Option<u64>,
+    children: IndexMap<String, FileNode>,
+}
+
+pub fn format_size(bytes: u64) -> String {
+    human_bytes(bytes as f64).replace('i', "")
+}
+
+impl TorrentInfo {
+    pub fn parse(data: &[u8]) -> Result<TorrentInfo, Box<dyn Error + Send + Sync>> {
+        let (root, info_range) = bencode::decode_with_info(data)?;
+        let (Some(info), Some(info_range)) = (root.get("info"), info_range) else {
+            return Err("Torrent file has no info dictionary".into());
+        };
+
+        let name = info
+            .get("name.utf-8")
+            .or(info.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let files = match info.get("files").and_then(Value::as_list) {
+            // Multi-file torrent, paths are relative to a folder called `name`
+            Some(files) => files
+                .iter()
+                .map(|f| {
+                    let path = f
+                        .get("path.utf-8")
+                        .or(f.get("path"))
+                        .and_then(Value::as_list)
+                        .map(|p| p.iter().filter_map(Value::as_str).collect())
+                        .unwrap_or_default();
+                    TorrentFile {
+                        path,
+                        size: f.get("length").and_then(Value::as_int).unwrap_or(0) as u64,
+                    }
+                })
+                .collect(),
+            None => vec![TorrentFile {
+                path: vec![name.to_owned()],
+                size: info.get("length").and_then(Value::as_int).unwrap_or(0) as u64,
+            }],
+        };
+
+        let mut trackers = root
+            .get("announce")
+            .and_then(Value::as_str)
+            .into_iter()
+            .collect::<Vec<String>>();
+        for tier in root
+            .get("announce-list")
+            .and_then(Value::as_list)
+            .into_iter()
+            .flatten()
+        {
+            for tracker in tier.as_list().into_iter().flatten() {
+                if let Some(tracker) = tracker.as_str() {
+                    if !trackers.contains(&tracker) {
+                        trackers.push(tracker);
+                    }
+                }
+            }
+        }
+
+        let info_hash = sha1_smol::Sha1::from(&data[info_range])
+            .digest()
+            .to_string();
+        Ok(TorrentInfo {
+            name,
+            info_hash,
+            total_size: files.iter().map(|f| f.size).sum(),
+            files,
+            piece_length: info
+                .get("piece length")
+                .and_then(Value::as_int)
+                .unwrap_or(0) as u64,
+            pieces: info
+                .get("pieces")
+                .and_then(Value::as_bytes)
+                .map(|p| p.len() / 20)
+                .unwrap_or(0),
+            trackers,
+            private: info.get("private").and_then(Value::as_int) == Some(1),
+            comment: root.get("comment").and_then(Value::as_str),
+            created_by: root.get("created by").and_then(Value::as_str),
+        })
+    }
+
+    /// Render the files as an indented tree, with sizes after each file
+    pub fn file_tree(&self) -> Vec<String> {
+        let mut root = FileNode::default();
+        for file in self.files.iter() {
+            let node = file.path.iter().fold(&mut root, |node, part| {
+                node.children.entry(part.to_owned()).or_default()
+            });
+            node.size = Some(file.size);
+        }
+
+        fn walk(node: &FileNode, prefix: &str, lines: &mut Vec<String>) {
+            let len = node.children.len();
+            for (i, (name, child)) in node.children.iter().enumerate() {
+                let (branch, indent) = match i + 1 == len {
+                    true => ("└─ ", "   "),
+                    false => ("├─ ", "│  "),
+                };
+                match child.size {
+                    Some(size) if child.children.is_empty() => lines.push(format!(
+                        "{}{}{} ({})",
+                        prefix,
+                        branch,
+                        name,
+                        format_size(size)
+                    )),
+                    _ => {
+                        lines.push(format!("{}{}{}/", prefix, branch, name));
+                        walk(child, &format!("{}{}", prefix, indent), lines);
+                    }
+                }
+            }
+        }
+
+        let mut lines = vec![];
+        match self.files.len() == 1 && self.files[0].path == [self.name.to_owned()] {
+            // Single file torrent
+            true => walk(&root, "", &mut lines),
+            false => {
+                lines.push(format!("{}/", self.name));
+                walk(&root, "", &mut lines);
+            }
+        }
+        lines
+    }
+}
+
+/// Download a .torrent file into memory and parse it
+pub async fn fetch_torrent(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<TorrentInfo, Box<dyn Error + Send + Sync>> {
+    if url.is_empty() {
+        return Err("No torrent link for this item".into());
+    }
+    let response = client.get(url).send().await?;
+    if response.status() != StatusCode::OK {
+        // Throw error if response code is not OK
+        let code = response.status().as_u16();
+        return Err(format!("{}\nInvalid response code: {}", url, code).into());
+    }
+    TorrentInfo::parse(&response.bytes().await?)
+}
@@ -1,3 +1,4 @@
+pub mod bencode;
 pub mod cmd;
 pub mod conv;
 pub mod html;
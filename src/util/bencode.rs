@@ -0,0 +1,183 @@
+use std::{collections::BTreeMap, error::Error, fmt::Display, ops::Range};
+
+/// Lists and dicts nested deeper than this are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Dict(BTreeMap<Vec<u8>, Value>),
+}
+
+#[derive(Debug)]
+pub struct BencodeError {
+    pos: usize,
+    msg: &'static str,
+}
+
+impl Display for BencodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid bencode at byte {}: {}", self.pos, self.msg)
+    }
+}
+
+impl Error for BencodeError {}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Dict(d) => d.get(key.as_bytes()),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<String> {
+        self.as_bytes()
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    depth: usize,
+    info: Option<Range<usize>>,
+}
+
+impl<'a> Decoder<'a> {
+    fn err<T>(&self, msg: &'static str) -> Result<T, BencodeError> {
+        Err(BencodeError { pos: self.pos, msg })
+    }
+
+    fn peek(&self) -> Result<u8, BencodeError> {
+        match self.data.get(self.pos) {
+            Some(b) => Ok(*b),
+            None => self.err("unexpected end of input"),
+        }
+    }
+
+    fn read_until(&mut self, end: u8) -> Result<&'a str, BencodeError> {
+        let start = self.pos;
+        let Some(len) = self.data[start..].iter().position(|b| *b == end) else {
+            return self.err("unterminated value");
+        };
+        self.pos += len + 1;
+        match std::str::from_utf8(&self.data[start..start + len]) {
+            Ok(s) => Ok(s),
+            Err(_) => self.err("invalid number"),
+        }
+    }
+
+    fn int(&mut self) -> Result<i64, BencodeError> {
+        self.pos += 1;
+        let num = self.read_until(b'e')?;
+        match num.parse() {
+            Ok(i) => Ok(i),
+            Err(_) => self.err("invalid integer"),
+        }
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, BencodeError> {
+        let len = self.read_until(b':')?;
+        let Ok(len) = len.parse::<usize>() else {
+            return self.err("invalid string length");
+        };
+        let Some(end) = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+        else {
+            return self.err("string is longer than input");
+        };
+        let bytes = self.data[self.pos..end].to_vec();
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn value(&mut self) -> Result<Value, BencodeError> {
+        match self.peek()? {
+            b'i' => Ok(Value::Int(self.int()?)),
+            b'0'..=b'9' => Ok(Value::Bytes(self.bytes()?)),
+            b'l' => {
+                self.pos += 1;
+                let mut list = vec![];
+                while self.peek()? != b'e' {
+                    list.push(self.nested()?);
+                }
+                self.pos += 1;
+                Ok(Value::List(list))
+            }
+            b'd' => {
+                self.pos += 1;
+                let mut dict = BTreeMap::new();
+                while self.peek()? != b'e' {
+                    let key = self.bytes()?;
+                    let start = self.pos;
+                    let value = self.nested()?;
+                    // Remember where the top-level info dict is, since the
+                    // info hash is the hash of its exact bytes
+                    if self.depth == 1 && key == b"info" {
+                        self.info = Some(start..self.pos);
+                    }
+                    dict.insert(key, value);
+                }
+                self.pos += 1;
+                Ok(Value::Dict(dict))
+            }
+            _ => self.err("unexpected character"),
+        }
+    }
+
+    fn nested(&mut self) -> Result<Value, BencodeError> {
+        if self.depth >= MAX_DEPTH {
+            return self.err("nested too deeply");
+        }
+        self.depth += 1;
+        let value = self.value();
+        self.depth -= 1;
+        value
+    }
+}
+
+/// Decode a bencoded value, also returning the byte range of the top-level
+/// `info` dictionary if there is one.
+pub fn decode_with_info(data: &[u8]) -> Result<(Value, Option<Range<usize>>), BencodeError> {
+    let mut decoder = Decoder {
+        data,
+        pos: 0,
+        depth: 1,
+        info: None,
+    };
+    let value = decoder.value()?;
+    if decoder.pos != data.len() {
+        return decoder.err("trailing data after value");
+    }
+    Ok((value, decoder.info))
+}
+
+pub fn decode(data: &[u8]) -> Result<Value, BencodeError> {
+    decode_with_info(data).map(|v| v.0)
+}
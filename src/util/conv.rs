@@ -3,12 +3,26 @@ use std::error::Error;
 use crossterm::event::{KeyCode, KeyModifiers, MediaKeyCode, ModifierKeyCode};
 use reqwest::Url;
 
+use crate::source::Item;
+
 pub fn get_hash(magnet: String) -> Option<String> {
     magnet
         .split_once("xt=urn:btih:")
         .and_then(|m| m.1.split_once('&').map(|m| m.0.to_owned()))
 }
 
+/// The lowercase info hash of an item, preferring the hash some sources list
+/// with their results over the one in the magnet link
+pub fn item_hash(item: &Item) -> Option<String> {
+    item.extra
+        .get("hash")
+        .filter(|h| !h.is_empty())
+        .cloned()
+        .or_else(|| get_hash(format!("{}&", item.magnet_link)))
+        .map(|h| h.to_lowercase())
+        .filter(|h| !h.is_empty())
+}
+
 pub fn add_protocol<S: Into<String>>(
     url: S,
     default_https: bool,
@@ -125,3 +139,62 @@ pub fn key_to_string(key: KeyCode, modifier: KeyModifiers) -> String {
     };
     format!("<{}{}>", modifier, key)
 }
+
+/// Parse a key in the format produced by [`key_to_string`], such as `j`,
+/// `<CR>` or `<C-a>`. Names and modifiers are not case sensitive.
+pub fn string_to_key(key: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(match c.is_uppercase() {
+            true => (KeyCode::Char(c), KeyModifiers::SHIFT),
+            false => (KeyCode::Char(c), KeyModifiers::NONE),
+        });
+    }
+
+    let inner = key.strip_prefix('<')?.strip_suffix('>')?;
+    let (modifier, name) = match inner.split_once('-') {
+        Some((m, name)) if m.len() == 1 && !name.is_empty() => {
+            let modifier = match m.to_ascii_uppercase().as_str() {
+                "C" => KeyModifiers::CONTROL,
+                "S" => KeyModifiers::SHIFT,
+                "A" => KeyModifiers::ALT,
+                "U" => KeyModifiers::SUPER,
+                "M" => KeyModifiers::META,
+                "H" => KeyModifiers::HYPER,
+                _ => return None,
+            };
+            (modifier, name)
+        }
+        _ => (KeyModifiers::NONE, inner),
+    };
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(match (modifier, c.is_lowercase()) {
+            // Shift is represented by the uppercase char
+            (KeyModifiers::SHIFT, true) => (KeyCode::Char(c.to_ascii_uppercase()), modifier),
+            _ => (KeyCode::Char(c), modifier),
+        });
+    }
+    let code = match name.to_lowercase().as_str() {
+        "bs" | "backspace" => KeyCode::Backspace,
+        "cr" | "enter" | "return" => KeyCode::Enter,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pgup" | "pageup" => KeyCode::PageUp,
+        "pgdown" | "pagedown" => KeyCode::PageDown,
+        "tab" if modifier == KeyModifiers::SHIFT => KeyCode::BackTab,
+        "tab" => KeyCode::Tab,
+        "del" | "delete" => KeyCode::Delete,
+        "ins" | "insert" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "esc" | "escape" => KeyCode::Esc,
+        f if f.starts_with('f') => KeyCode::F(f[1..].parse().ok()?),
+        _ => return None,
+    };
+    Some((code, modifier))
+}
@@ -24,3 +24,11 @@ pub fn attr(e: ElementRef, s: &Selector, attr: &str) -> String {
         .unwrap_or("")
         .to_owned()
 }
+
+pub fn text(e: ElementRef) -> String {
+    e.text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
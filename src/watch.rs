@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+use reqwest::cookie::Jar;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::{self, QueryArgs},
+    client::{DownloadResult, Profile},
+    config::{self, Config, ConfigManager},
+    history, routing,
+    source::{request_client, Item, SourceConfig, SourceInfo, Sources},
+    sync::SearchQuery,
+    widget::sort::{SelectedSort, SortDir},
+};
+
+pub static SEEN_FILE: &str = "seen.toml";
+
+// Only remember this many ids per watch, oldest are forgotten first
+static MAX_SEEN: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Watch {
+    pub name: String,
+    pub source: Sources,
+    pub query: String,
+    pub category: Option<String>,
+    pub filter: Option<String>,
+    pub user: Option<String>,
+    pub client: Option<Profile>,
+    pub interval: u64,
+    pub download_existing: bool,
+}
+
+impl Default for Watch {
+    fn default() -> Self {
+        Self {
+            name: "".to_owned(),
+            source: Sources::Nyaa,
+            query: "".to_owned(),
+            category: None,
+            filter: None,
+            user: None,
+            client: None,
+            interval: 30,
+            download_existing: false,
+        }
+    }
+}
+
+/// Ids of items already seen for each watch, stored in the config folder
+pub type SeenStore = BTreeMap<String, Vec<String>>;
+
+pub struct WatchResult {
+    pub new_items: Vec<Item>,
+    pub client: Profile,
+    pub download: Option<DownloadResult>,
+}
+
+impl Watch {
+    pub fn from_search(src: Sources, search: &SearchQuery, info: &SourceInfo) -> Self {
+        let category = info.clone().entry_from_id(search.category);
+        let filter = info.filters.get(search.filter).cloned();
+        let name = match search.query.is_empty() {
+            true => format!("{} {}", src, category.name),
+            false => search.query.to_owned(),
+        };
+        Self {
+            name,
+            source: src,
+            query: search.query.to_owned(),
+            category: Some(category.cfg),
+            filter,
+            user: search.user.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether both watches would run the same search
+    pub fn same_search(&self, other: &Watch) -> bool {
+        self.source == other.source
+            && self.query == other.query
+            && self.category == other.category
+            && self.filter == other.filter
+            && self.user == other.user
+    }
+
+    /// Identifies the watch in the log and in the seen items. Unnamed watches
+    /// are identified by their search, so each search keeps its own seen items.
+    pub fn key(&self) -> String {
+        if !self.name.is_empty() {
+            return self.name.to_owned();
+        }
+        let mut key = format!("{}/{}", self.source, self.query);
+        for (field, value) in [
+            ("category", &self.category),
+            ("filter", &self.filter),
+            ("user", &self.user),
+        ] {
+            if let Some(value) = value {
+                key.push_str(&format!(" {}={}", field, value));
+            }
+        }
+        key
+    }
+
+    pub fn query_args(&self) -> QueryArgs {
+        QueryArgs {
+            query: Some(self.query.to_owned()),
+            category: self.category.to_owned(),
+            filter: self.filter.to_owned(),
+            user: self.user.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Use the RSS feed for sources that have one, since it's lighter and always
+/// sorted by newest first.
+pub fn prefer_rss(src: &Sources, config: &mut SourceConfig) {
+    match src {
+        Sources::Nyaa => {
+            if let Some(nyaa) = config.nyaa.as_mut() {
+                nyaa.rss = true;
+            }
+        }
+        Sources::SukebeiNyaa => {
+            if let Some(sukebei) = config.sukebei.as_mut() {
+                sukebei.rss = true;
+            }
+        }
+        Sources::Multi => {
+            let sources = config.multi.as_ref().map(|m| m.sources.clone());
+            for src in sources.unwrap_or_default().into_iter() {
+                if src != Sources::Multi {
+                    prefer_rss(&src, config);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Run the watch once, sending any items not in `seen` to the download client.
+/// Successfully downloaded items are added to `seen`. If `seen` is `None`, the
+/// watch has never been run before.
+pub async fn check(
+    watch: &Watch,
+    config: &Config,
+    seen: Option<&Vec<String>>,
+    source_rq: &reqwest::Client,
+    client_rq: &reqwest::Client,
+) -> Result<(WatchResult, Vec<String>), Box<dyn Error + Send + Sync>> {
+    let mut sources = config.sources.clone();
+    watch.source.load_config(&mut sources);
+    prefer_rss(&watch.source, &mut sources);
+    watch.source.load_info(&sources).await?;
+    let mut search = cli::build_query(&watch.source, &watch.query_args(), &sources)?;
+    if let Some(date) = watch
+        .source
+        .info(&sources)
+        .sorts
+        .iter()
+        .position(|s| s == "Date")
+    {
+        search.sort = SelectedSort {
+            sort: date,
+            dir: SortDir::Desc,
+        };
+    }
+
+    let res = cli::load_results(
+        &watch.source,
+        source_rq,
+        &search,
+        &sources,
+        config.date_format.clone(),
+    )
+    .await?;
+
+    let client = watch
+        .client
+        .clone()
+        .unwrap_or(config.download_client.clone());
+    let mut new_seen = seen.cloned().unwrap_or_default();
+    let new_items = res
+        .items
+        .into_iter()
+        .filter(|i| !new_seen.contains(&i.id))
+        .collect::<Vec<Item>>();
+
+    if new_items.is_empty() || (seen.is_none() && !watch.download_existing) {
+        // On the first run, only remember what's already there
+        new_seen.extend(new_items.iter().map(|i| i.id.to_owned()));
+        return Ok((
+            WatchResult {
+                new_items,
+                client,
+                download: None,
+            },
+            truncate_seen(new_seen),
+        ));
+    }
+
+    let mut client_config = config.client.clone();
+    client.load_config(&mut client_config);
+    // Routing is skipped when the watch has its own client
+    let routing = match watch.client {
+        Some(_) => vec![],
+        None => config.routing.clone().unwrap_or_default(),
+    };
+    let (resolved, errors) = watch
+        .source
+        .resolve(source_rq, new_items.clone(), &sources)
+        .await;
+    let mut download = routing::download(
+        &routing,
+        Some(&watch.source),
+        new_items.len() > 1,
+        resolved.clone(),
+        &client,
+        &client_config,
+        client_rq.clone(),
+        source_rq.clone(),
+    )
+    .await;
+    download.errors.extend(errors);
+    download.resolved = resolved;
+    new_seen.extend(download.success_ids.iter().cloned());
+
+    Ok((
+        WatchResult {
+            new_items,
+            client,
+            download: Some(download),
+        },
+        truncate_seen(new_seen),
+    ))
+}
+
+fn truncate_seen(mut seen: Vec<String>) -> Vec<String> {
+    if seen.len() > MAX_SEEN {
+        seen.drain(..seen.len() - MAX_SEEN);
+    }
+    seen
+}
+
+pub fn load_seen(path: impl AsRef<Path>) -> Result<SeenStore, Box<dyn Error + Send + Sync>> {
+    config::load_path(path.as_ref().join(SEEN_FILE)).map_err(|e| e.to_string().into())
+}
+
+pub fn store_seen(
+    path: impl AsRef<Path>,
+    seen: &SeenStore,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    config::store_path(path.as_ref().join(SEEN_FILE), seen).map_err(|e| e.to_string().into())
+}
+
+fn log(msg: String) {
+    println!("[{}] {}", Local::now().format("%Y-%m-%d %H:%M:%S"), msg);
+}
+
+fn log_result(watch: &Watch, res: &WatchResult) {
+    let key = watch.key();
+    match res.download.as_ref() {
+        None if res.new_items.is_empty() => log(format!("{}: No new items", key)),
+        None => log(format!(
+            "{}: Remembered {} existing items",
+            key,
+            res.new_items.len()
+        )),
+        Some(dl) => {
+            for item in res
+                .new_items
+                .iter()
+                .filter(|i| dl.success_ids.contains(&i.id))
+            {
+                let client = dl.routed_to(&item.id).unwrap_or(&res.client);
+                log(format!("{}: Sent \"{}\" to {}", key, item.title, client));
+            }
+            for e in dl.errors.iter() {
+                eprintln!("{}: Failed to download with {}:\n{}", key, res.client, e);
+            }
+        }
+    }
+}
+
+/// Periodically run every watch in the config, reloading the config on each
+/// pass so changes apply without restarting. If `once` is set, every watch is
+/// run a single time regardless of its interval. Otherwise, a pass that can't
+/// start (like a broken config) is logged and tried again on the next tick.
+pub async fn run<C: ConfigManager>(
+    config_manager: C,
+    once: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut last_run: HashMap<String, Instant> = HashMap::new();
+    loop {
+        match run_pass(&config_manager, once, &mut last_run).await {
+            Ok(_) if !once => {}
+            Ok((0, _)) => return Ok(()),
+            Ok((failed, total)) => {
+                return Err(format!("{} of {} watches failed", failed, total).into())
+            }
+            Err(e) if once => return Err(e),
+            Err(e) => eprintln!("Failed to run watches:\n{}", e),
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+/// Run every watch that is due, returning how many failed out of the total
+async fn run_pass<C: ConfigManager>(
+    config_manager: &C,
+    once: bool,
+    last_run: &mut HashMap<String, Instant>,
+) -> Result<(usize, usize), Box<dyn Error + Send + Sync>> {
+    let config = config_manager.load().map_err(|e| e.to_string())?;
+    let watches = config.watches.clone().unwrap_or_default();
+    if watches.is_empty() {
+        return Err("No watches found in config, add one with a [[watch]] table".into());
+    }
+
+    let jar = Arc::new(Jar::default());
+    let source_rq = request_client(&jar, config.timeout, config.request_proxy.clone())
+        .map_err(|e| e.to_string())?;
+    // Don't use proxy for clients
+    let client_rq = request_client(&jar, config.timeout, None).map_err(|e| e.to_string())?;
+
+    let mut seen = load_seen(config_manager.path())?;
+    let mut failed = 0;
+    for watch in watches.iter() {
+        let key = watch.key();
+        let interval = Duration::from_secs(watch.interval.max(1) * 60);
+        if !once && last_run.get(&key).is_some_and(|t| t.elapsed() < interval) {
+            continue;
+        }
+        last_run.insert(key.to_owned(), Instant::now());
+
+        match check(watch, &config, seen.get(&key), &source_rq, &client_rq).await {
+            Ok((res, new_seen)) => {
+                log_result(watch, &res);
+                if let Some(dl) = res.download.as_ref() {
+                    // Saved right away, since the TUI may save downloads in the meantime
+                    if let Err(e) = history::record_downloads(
+                        config_manager.path(),
+                        &res.new_items,
+                        res.client.clone(),
+                        Some(watch.source.clone()),
+                        dl,
+                    ) {
+                        eprintln!("{}: Failed to save download history:\n{}", key, e);
+                    }
+                }
+                seen.insert(key, new_seen);
+            }
+            Err(e) => {
+                eprintln!("{}: Failed to check for new items:\n{}", key, e);
+                failed += 1;
+            }
+        }
+    }
+    store_seen(config_manager.path(), &seen)?;
+    Ok((failed, watches.len()))
+}
@@ -15,7 +15,7 @@ use ratatui::{
 use serde::{Deserialize, Serialize};
 use unicode_width::UnicodeWidthStr as _;
 
-use crate::{app::Context, style, theme::Theme};
+use crate::{app::Context, keybinds::KeyMap, style, theme::Theme};
 
 #[cfg(feature = "captcha")]
 pub mod captcha;
@@ -23,8 +23,11 @@ pub mod captcha;
 pub mod batch;
 pub mod category;
 pub mod clients;
+pub mod details;
+pub mod downloads;
 pub mod filter;
 pub mod help;
+pub mod history;
 pub mod input;
 pub mod notifications;
 pub mod notify_box;
@@ -33,13 +36,14 @@ pub mod results;
 pub mod search;
 pub mod sort;
 pub mod sources;
+pub mod status;
 pub mod themes;
 pub mod user;
 
 pub trait Widget {
     fn draw(&mut self, buf: &mut Frame, ctx: &Context, area: Rect);
     fn handle_event(&mut self, app: &mut Context, e: &Event);
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>>;
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>>;
 }
 
 pub trait EnumIter<T> {
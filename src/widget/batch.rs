@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use human_bytes::human_bytes;
 use ratatui::{
     layout::{Constraint, Margin, Rect},
@@ -9,6 +9,7 @@ use ratatui::{
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     source::ItemType,
     title,
 };
@@ -40,7 +41,7 @@ impl super::Widget for BatchWidget {
             .iter()
             .map(|i| {
                 Row::new([
-                    i.icon.label.fg((i.icon.color)(&ctx.theme)),
+                    i.icon.label.to_owned().fg((i.icon.color)(&ctx.theme)),
                     i.title.to_owned().fg(match i.item_type {
                         ItemType::Trusted => ctx.theme.success,
                         ItemType::Remake => ctx.theme.error,
@@ -110,43 +111,42 @@ impl super::Widget for BatchWidget {
             ..
         }) = evt
         {
-            use KeyCode::*;
-            match (code, modifiers) {
-                (Esc | Tab | BackTab, _) => {
+            match ctx.keys.batch.get(code, modifiers) {
+                Some(Action::SwitchPane) => {
                     ctx.mode = Mode::Normal;
                 }
-                (Char('q'), &KeyModifiers::NONE) => {
+                Some(Action::Quit) => {
                     ctx.quit();
                 }
-                (Char('j') | Down, &KeyModifiers::NONE) => {
+                Some(Action::Down) => {
                     self.table.next(ctx.batch.len(), 1);
                 }
-                (Char('k') | Up, &KeyModifiers::NONE) => {
+                Some(Action::Up) => {
                     self.table.next(ctx.batch.len(), -1);
                 }
-                (Char('J'), &KeyModifiers::SHIFT) => {
+                Some(Action::JumpDown) => {
                     self.table.next(ctx.batch.len(), 4);
                 }
-                (Char('K'), &KeyModifiers::SHIFT) => {
+                Some(Action::JumpUp) => {
                     self.table.next(ctx.batch.len(), -4);
                 }
-                (Char('g'), &KeyModifiers::NONE) => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
-                (Char('G'), &KeyModifiers::SHIFT) => {
+                Some(Action::Bottom) => {
                     self.table.select(ctx.batch.len() - 1);
                 }
-                (Char(' '), &KeyModifiers::NONE) => {
+                Some(Action::ToggleBatch) => {
                     if let Some(i) = self.table.selected() {
                         self.table.next(ctx.batch.len(), 0);
                         ctx.batch.remove(i);
                         self.table.next(ctx.batch.len(), 0);
                     }
                 }
-                (Char('a'), &KeyModifiers::CONTROL) => {
+                Some(Action::DownloadAll) => {
                     ctx.mode = Mode::Loading(LoadType::Batching);
                 }
-                (Char('x'), &KeyModifiers::CONTROL) => {
+                Some(Action::ClearBatch) => {
                     ctx.batch.clear();
                 }
                 _ => {}
@@ -154,18 +154,19 @@ impl super::Widget for BatchWidget {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Download single torrent"),
-            ("Ctrl-A", "Download all torrents"),
-            ("Ctrl-X", "Clear batch"),
-            ("Esc/Tab/Shift-Tab", "Back to results"),
-            ("q", "Exit app"),
-            ("g/G", "Goto Top/Bottom"),
-            ("k, ↑", "Up"),
-            ("j, ↓", "Down"),
-            ("K, J", "Up/Down 4 items"),
-            ("Space", "Toggle item for batch download"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::DownloadAll, "Download all torrents"),
+            (Action::ClearBatch, "Clear batch"),
+            (Action::SwitchPane, "Back to results"),
+            (Action::Quit, "Exit app"),
+            (Action::Top, "Goto Top"),
+            (Action::Bottom, "Goto Bottom"),
+            (Action::Up, "Up"),
+            (Action::Down, "Down"),
+            (Action::JumpUp, "Up 4 items"),
+            (Action::JumpDown, "Down 4 items"),
+            (Action::ToggleBatch, "Remove item from batch"),
+        ]))
     }
 }
@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
     widgets::StatefulWidget as _,
@@ -6,7 +6,10 @@ use ratatui::{
 };
 use ratatui_image::{protocol::StatefulProtocol, StatefulImage};
 
-use crate::app::{Context, LoadType, Mode};
+use crate::{
+    app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
+};
 
 use super::{input::InputWidget, Widget};
 
@@ -67,14 +70,15 @@ impl Widget for CaptchaPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc => {
+            match ctx.keys.captcha.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     ctx.mode = Mode::Loading(LoadType::SolvingCaptcha(self.input.input.clone()));
                 }
                 _ => {}
@@ -83,14 +87,7 @@ impl Widget for CaptchaPopup {
         self.input.handle_event(ctx, e);
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, f, q", "Close"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[(Action::Confirm, "Confirm"), (Action::Close, "Close")]))
     }
 }
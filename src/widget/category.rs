@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Margin, Rect},
     style::{Color, Style, Stylize as _},
@@ -9,6 +9,7 @@ use ratatui::{
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     style,
     theme::Theme,
     title,
@@ -26,32 +27,29 @@ pub struct CatEntry {
 
 #[derive(Clone)]
 pub struct CatIcon {
-    pub label: &'static str,
+    pub label: String,
     pub color: fn(&Theme) -> Color,
 }
 
 impl Default for CatIcon {
     fn default() -> Self {
         CatIcon {
-            label: "???",
+            label: "???".to_owned(),
             color: |t: &Theme| t.fg,
         }
     }
 }
 
 impl CatEntry {
-    pub fn new(
-        name: &str,
-        cfg: &str,
-        id: usize,
-        label: &'static str,
-        color: fn(&Theme) -> Color,
-    ) -> Self {
+    pub fn new(name: &str, cfg: &str, id: usize, label: &str, color: fn(&Theme) -> Color) -> Self {
         CatEntry {
             name: name.to_string(),
             cfg: cfg.to_string(),
             id,
-            icon: CatIcon { label, color },
+            icon: CatIcon {
+                label: label.to_owned(),
+                color,
+            },
         }
     }
 }
@@ -116,7 +114,7 @@ impl Widget for CategoryPopup {
                         false => "   ",
                     }
                     .into(),
-                    e.icon.label.fg((e.icon.color)(&ctx.theme)),
+                    e.icon.label.to_owned().fg((e.icon.color)(&ctx.theme)),
                     " ".into(),
                     e.name.to_owned().into(),
                 ])])
@@ -156,11 +154,12 @@ impl Widget for CategoryPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Enter => {
+            match ctx.keys.category.get(code, modifiers) {
+                Some(Action::Confirm) => {
                     if let Some(cat) = ctx.src_info.cats.get(self.major) {
                         if let Some(item) = cat.entries.get(self.minor) {
                             self.selected = item.id;
@@ -169,10 +168,10 @@ impl Widget for CategoryPopup {
                     }
                     ctx.mode = Mode::Loading(LoadType::Categorizing);
                 }
-                KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('q') => {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     if let Some(cat) = ctx.src_info.cats.get(self.major) {
                         self.minor = match self.minor + 1 >= cat.entries.len() {
                             true => {
@@ -184,7 +183,7 @@ impl Widget for CategoryPopup {
                         self.table.select(self.major + self.minor + 1);
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     if ctx.src_info.cats.get(self.major).is_some() {
                         self.minor = match self.minor < 1 {
                             true => {
@@ -199,21 +198,21 @@ impl Widget for CategoryPopup {
                         self.table.select(self.major + self.minor + 1);
                     }
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     if let Some(cat) = ctx.src_info.cats.get(self.major) {
                         self.minor = cat.entries.len() - 1;
                         self.table.select(self.major + self.minor + 1);
                     }
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.minor = 0;
                     self.table.select(self.major + self.minor + 1);
                 }
-                KeyCode::Tab | KeyCode::Char('J') => {
+                Some(Action::NextTab) => {
                     self.next_tab(ctx.src_info.cats.len());
                     self.table.select(self.major + self.minor + 1);
                 }
-                KeyCode::BackTab | KeyCode::Char('K') => {
+                Some(Action::PrevTab) => {
                     self.prev_tab(ctx.src_info.cats.len());
                     self.table.select(self.major + self.minor + 1);
                 }
@@ -222,16 +221,16 @@ impl Widget for CategoryPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, c, q", "Close"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-            ("Tab, J", "Next Tab"),
-            ("S-Tab, K", "Prev Tab"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Confirm, "Confirm"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+            (Action::NextTab, "Next Tab"),
+            (Action::PrevTab, "Prev Tab"),
+        ]))
     }
 }
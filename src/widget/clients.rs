@@ -1,27 +1,42 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crate::{
+    app::{Context, Mode},
+    client::{ClientConfig, Profile},
+    keybinds::{Action, KeyMap},
+    style, title,
+};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Rect},
     widgets::{Row, StatefulWidget as _, Table},
     Frame,
 };
-use strum::VariantArray;
-
-use crate::{
-    app::{Context, Mode},
-    client::Client,
-    style, title,
-};
 
 use super::{border_block, StatefulTable, Widget};
 
 pub struct ClientsPopup {
-    pub table: StatefulTable<Client>,
+    pub table: StatefulTable<Profile>,
 }
 
 impl Default for ClientsPopup {
     fn default() -> Self {
         ClientsPopup {
-            table: StatefulTable::new(Client::VARIANTS),
+            table: StatefulTable::new(&ClientConfig::default().all_profiles()),
+        }
+    }
+}
+
+impl ClientsPopup {
+    /// List the built-in clients followed by the profiles in the config
+    pub fn load_profiles(&mut self, conf: &ClientConfig) {
+        self.table.items = conf.all_profiles();
+        if self.table.selected().is_none() {
+            self.table.select(0);
+        }
+    }
+
+    pub fn select(&mut self, profile: &Profile) {
+        if let Some(i) = self.table.items.iter().position(|p| p == profile) {
+            self.table.select(i);
         }
     }
 }
@@ -29,11 +44,24 @@ impl Default for ClientsPopup {
 impl Widget for ClientsPopup {
     fn draw(&mut self, f: &mut Frame, ctx: &Context, area: Rect) {
         let buf = f.buffer_mut();
-        let center = super::centered_rect(30, self.table.items.len() as u16 + 2, area);
-        let items = self.table.items.iter().map(|item| {
+        let names = self
+            .table
+            .items
+            .iter()
+            .map(|item| match item {
+                Profile::Client(c) => c.to_string(),
+                Profile::Named(name) => match item.client(&ctx.config.client) {
+                    Some(c) => format!("{} ({})", name, c),
+                    None => name.to_owned(),
+                },
+            })
+            .collect::<Vec<String>>();
+        let width = names.iter().map(|n| n.len() as u16 + 6).max().unwrap_or(0);
+        let center = super::centered_rect(width.max(30), names.len() as u16 + 2, area);
+        let items = self.table.items.iter().zip(names).map(|(item, name)| {
             Row::new(vec![match item == &ctx.client {
-                true => format!("  {}", item),
-                false => format!("   {}", item),
+                true => format!("  {}", name),
+                false => format!("   {}", name),
             }])
         });
         super::clear(center, buf, ctx.theme.bg);
@@ -47,29 +75,30 @@ impl Widget for ClientsPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('d') | KeyCode::Char('q') => {
+            match ctx.keys.clients.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     self.table.next_wrap(1);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     self.table.next_wrap(-1);
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
-                    if let Some(c) = self.table.selected() {
-                        ctx.client = *c;
-                        ctx.config.download_client = *c;
+                Some(Action::Confirm) => {
+                    if let Some(c) = self.table.selected().cloned() {
+                        ctx.client = c.clone();
+                        ctx.config.download_client = c.clone();
 
                         c.load_config(&mut ctx.config.client);
                         match ctx.save_config() {
@@ -84,14 +113,14 @@ impl Widget for ClientsPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, d, q", "Close"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Confirm, "Confirm"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+        ]))
     }
 }
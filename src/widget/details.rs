@@ -0,0 +1,247 @@
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Margin, Rect},
+    text::{Line, Span},
+    widgets::{
+        Paragraph, ScrollbarOrientation, ScrollbarState, StatefulWidget as _, Widget as _, Wrap,
+    },
+    Frame,
+};
+use unicode_width::UnicodeWidthStr as _;
+
+use crate::{
+    app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
+    source::{Item, ItemDetails},
+    style, title,
+    torrent::{format_size, TorrentInfo},
+};
+
+use super::{border_block, Widget};
+
+#[derive(Default)]
+pub enum DetailsContent {
+    #[default]
+    Loading,
+    Post(ItemDetails),
+    Torrent(TorrentInfo),
+}
+
+#[derive(Default)]
+pub struct DetailsPopup {
+    pub item: Option<Item>,
+    pub content: DetailsContent,
+    scroll: u16,
+    max_scroll: u16,
+}
+
+impl DetailsPopup {
+    pub fn loading(&mut self, item: Item) {
+        self.item = Some(item);
+        self.content = DetailsContent::Loading;
+        self.scroll = 0;
+    }
+
+    /// Whether the popup is for the item with this id
+    pub fn is_showing(&self, id: &str) -> bool {
+        self.item.as_ref().is_some_and(|i| i.id == id)
+    }
+
+    fn scroll_by(&mut self, amt: isize) {
+        self.scroll = (self.scroll as isize + amt).clamp(0, self.max_scroll as isize) as u16;
+    }
+
+    fn lines(&self, ctx: &Context) -> Vec<Line<'static>> {
+        match &self.content {
+            DetailsContent::Loading => vec![Line::from("Loading…")],
+            DetailsContent::Post(details) => post_lines(ctx, details),
+            DetailsContent::Torrent(info) => torrent_lines(ctx, info),
+        }
+    }
+
+    fn name(&self) -> String {
+        let name = match &self.content {
+            DetailsContent::Loading => None,
+            DetailsContent::Post(details) => Some(details.title.to_owned()),
+            DetailsContent::Torrent(info) => Some(info.name.to_owned()),
+        };
+        name.filter(|t| !t.is_empty())
+            .or(self.item.as_ref().map(|i| i.title.to_owned()))
+            .unwrap_or_default()
+    }
+}
+
+fn field(ctx: &Context, name: &'static str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{:<11}", name),
+            style!(bold, fg:ctx.theme.border_focused_color),
+        ),
+        Span::raw(value),
+    ])
+}
+
+fn heading(ctx: &Context, name: String) -> Line<'static> {
+    Line::styled(
+        name,
+        style!(bold, underlined, fg:ctx.theme.border_focused_color),
+    )
+}
+
+fn torrent_lines(ctx: &Context, info: &TorrentInfo) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        field(ctx, "Size", format_size(info.total_size)),
+        field(
+            ctx,
+            "Pieces",
+            format!("{} x {}", info.pieces, format_size(info.piece_length)),
+        ),
+        field(ctx, "Info hash", info.info_hash.to_owned()),
+        field(ctx, "Private", info.private.to_string()),
+    ];
+    if let Some(created_by) = info.created_by.to_owned() {
+        lines.push(field(ctx, "Created by", created_by));
+    }
+    if let Some(comment) = info.comment.to_owned() {
+        lines.push(field(ctx, "Comment", comment));
+    }
+    lines.push(Line::default());
+    lines.push(heading(ctx, format!("Files ({})", info.files.len())));
+    lines.extend(info.file_tree().into_iter().map(Line::from));
+    lines.push(Line::default());
+    lines.push(heading(ctx, format!("Trackers ({})", info.trackers.len())));
+    lines.extend(info.trackers.iter().map(|t| Line::from(t.to_owned())));
+    lines
+}
+
+fn post_lines(ctx: &Context, details: &ItemDetails) -> Vec<Line<'static>> {
+    let field = |name, value| field(ctx, name, value);
+    let mut lines = vec![
+        field("Submitter", details.submitter.to_owned()),
+        field("Date", details.date.to_owned()),
+        field("Size", details.size.to_owned()),
+        field("Info hash", details.info_hash.to_owned()),
+        field("Comments", details.comments.to_string()),
+        Line::default(),
+        heading(ctx, format!("Files ({})", details.files.len())),
+    ];
+    lines.extend(details.files.iter().map(|f| {
+        Line::from(vec![
+            Span::raw(f.path.to_owned()),
+            Span::styled(format!(" ({})", f.size), style!(fg:ctx.theme.border_color)),
+        ])
+    }));
+    if !details.description.is_empty() {
+        lines.push(Line::default());
+        lines.push(heading(ctx, "Description".to_owned()));
+        lines.extend(
+            details
+                .description
+                .lines()
+                .map(|l| Line::from(l.trim_end().to_owned())),
+        );
+    }
+    lines
+}
+
+impl Widget for DetailsPopup {
+    fn draw(&mut self, f: &mut Frame, ctx: &Context, area: Rect) {
+        let buf = f.buffer_mut();
+        let center = super::centered_rect(
+            area.width.saturating_sub(8).min(120),
+            area.height.saturating_sub(4),
+            area,
+        );
+        let inner_width = center.width.saturating_sub(2).max(1) as usize;
+
+        let lines = self.lines(ctx);
+        // Estimate the height of the wrapped text to know how far to scroll
+        let height = lines
+            .iter()
+            .map(|l| l.width().max(1).div_ceil(inner_width))
+            .sum::<usize>() as u16;
+        self.max_scroll = height.saturating_sub(center.height.saturating_sub(2));
+        self.scroll = self.scroll.min(self.max_scroll);
+
+        let name = self.name();
+        let name = match name.width() + 4 > center.width as usize {
+            true => {
+                name.chars()
+                    .take((center.width as usize).saturating_sub(8))
+                    .collect::<String>()
+                    + "…"
+            }
+            false => name,
+        };
+        let p = Paragraph::new(lines)
+            .block(border_block(&ctx.theme, true).title(title!(name)))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+
+        super::clear(center, buf, ctx.theme.bg);
+        p.render(center, buf);
+
+        // Only show scrollbar if content overflows
+        if self.max_scroll > 0 {
+            let mut sb_state =
+                ScrollbarState::new(self.max_scroll as usize + 1).position(self.scroll as usize);
+            let sb =
+                super::scrollbar(ctx, ScrollbarOrientation::VerticalRight).begin_symbol(Some(""));
+            let sb_area = center.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            });
+            sb.render(sb_area, buf, &mut sb_state);
+        }
+    }
+
+    fn handle_event(&mut self, ctx: &mut Context, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            match ctx.keys.details.get(code, modifiers) {
+                Some(Action::Close) => {
+                    ctx.mode = Mode::Normal;
+                }
+                Some(Action::Down) => self.scroll_by(1),
+                Some(Action::Up) => self.scroll_by(-1),
+                Some(Action::JumpDown) => self.scroll_by(10),
+                Some(Action::JumpUp) => self.scroll_by(-10),
+                Some(Action::Top) => self.scroll = 0,
+                Some(Action::Bottom) => self.scroll = self.max_scroll,
+                Some(Action::OpenInBrowser) => {
+                    if let Some(link) = self.item.as_ref().map(|i| i.post_link.clone()) {
+                        let res = open::that_detached(link.clone());
+                        if let Err(e) = res {
+                            ctx.show_error(format!("Failed to open {}:\n{}", link, e));
+                        } else {
+                            ctx.notify(format!("Opened {}", link));
+                        }
+                    }
+                }
+                Some(Action::Download) => {
+                    ctx.mode = Mode::Loading(LoadType::Downloading);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Download, "Download"),
+            (Action::Close, "Close"),
+            (Action::Down, "Scroll down"),
+            (Action::Up, "Scroll up"),
+            (Action::JumpDown, "Scroll down 10 lines"),
+            (Action::JumpUp, "Scroll up 10 lines"),
+            (Action::Top, "Goto Top"),
+            (Action::Bottom, "Goto Bottom"),
+            (Action::OpenInBrowser, "Open in browser"),
+        ]))
+    }
+}
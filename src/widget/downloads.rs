@@ -0,0 +1,264 @@
+use std::cmp::min;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Margin, Rect},
+    text::Line,
+    widgets::{Paragraph, Row, ScrollbarOrientation, StatefulWidget, Table, Widget as _},
+    Frame,
+};
+
+use crate::{
+    app::{Context, LoadType, Mode},
+    history::{DownloadChange, DownloadEntry},
+    keybinds::{Action, KeyMap},
+    source::{Item, Sources},
+    style, title,
+};
+
+use super::{
+    border_block,
+    input::{self, InputWidget},
+    VirtualStatefulTable, Widget,
+};
+
+pub struct DownloadsPopup {
+    pub table: VirtualStatefulTable,
+    pub input: InputWidget,
+    /// Whether the search input is focused
+    pub filtering: bool,
+    /// The item to send to the download client again, and the source it was found on
+    pub resend: Option<(Item, Option<Sources>)>,
+}
+
+impl Default for DownloadsPopup {
+    fn default() -> Self {
+        DownloadsPopup {
+            table: VirtualStatefulTable::new(),
+            input: InputWidget::new(60, None),
+            filtering: false,
+            resend: None,
+        }
+    }
+}
+
+impl DownloadsPopup {
+    /// Indices of the downloads with a title matching the search
+    fn visible(&self, ctx: &Context) -> Vec<usize> {
+        let search = self.input.input.to_lowercase();
+        ctx.downloads
+            .downloads
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.title.to_lowercase().contains(&search))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn selected<'a>(&self, ctx: &'a Context) -> Option<(usize, &'a DownloadEntry)> {
+        let idx = *self.visible(ctx).get(self.table.selected().unwrap_or(0))?;
+        Some((idx, ctx.downloads.downloads.get(idx)?))
+    }
+
+    fn handle_filtering(
+        &mut self,
+        ctx: &mut Context,
+        e: &Event,
+        code: &KeyCode,
+        mods: &KeyModifiers,
+    ) {
+        // Typed characters always go to the search, even if they are bound
+        let typing = matches!(code, KeyCode::Char(_))
+            && matches!(mods, &KeyModifiers::NONE | &KeyModifiers::SHIFT);
+        match ctx.keys.downloads.get(code, mods).filter(|_| !typing) {
+            Some(Action::Close) => {
+                self.input.clear();
+                self.filtering = false;
+            }
+            Some(Action::Confirm) => self.filtering = false,
+            _ => {
+                self.input.handle_event(ctx, e);
+                self.table.select(0);
+            }
+        }
+    }
+}
+
+impl Widget for DownloadsPopup {
+    fn draw(&mut self, f: &mut Frame, ctx: &Context, area: Rect) {
+        let buf = f.buffer_mut();
+        let visible = self.visible(ctx);
+        let num_items = visible.len();
+        let height = min(
+            num_items.max(1) as u16 + 2,
+            area.height.saturating_sub(7).max(3),
+        );
+        let center = super::centered_rect(area.width.saturating_sub(8).min(100), height, area);
+        if self.table.selected().unwrap_or(0) >= num_items {
+            self.table.select(num_items.saturating_sub(1));
+        }
+
+        let items = visible
+            .iter()
+            .filter_map(|i| ctx.downloads.downloads.get(*i))
+            .map(|d| {
+                Row::new(vec![
+                    Line::styled(d.date.to_owned(), style!(fg:ctx.theme.border_color)),
+                    Line::from(d.title.to_owned()),
+                    Line::styled(d.client.to_string(), style!(fg:ctx.theme.border_color)),
+                ])
+            });
+
+        super::scroll_padding(
+            self.table.selected().unwrap_or(0),
+            center.height as usize,
+            2,
+            num_items,
+            1,
+            self.table.state.offset_mut(),
+        );
+
+        super::clear(center, buf, ctx.theme.bg);
+        let block = border_block(&ctx.theme, !self.filtering).title(title!("Downloads"));
+        if num_items == 0 {
+            let msg = match ctx.downloads.downloads.is_empty() {
+                true => "  Nothing downloaded yet",
+                false => "  No matching downloads",
+            };
+            Paragraph::new(msg).block(block).render(center, buf);
+        } else {
+            let table = Table::new(
+                items,
+                [
+                    Constraint::Length(16),
+                    Constraint::Min(10),
+                    Constraint::Length(16),
+                ],
+            )
+            .block(block)
+            .highlight_style(style!(bg:ctx.theme.hl_bg));
+            StatefulWidget::render(table, center, buf, &mut self.table.state);
+        }
+
+        // Only show scrollbar if content overflows
+        if num_items as u16 + 1 >= center.height {
+            let sb = super::scrollbar(ctx, ScrollbarOrientation::VerticalRight);
+            let sb_area = center.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            });
+            sb.render(
+                sb_area,
+                buf,
+                &mut self.table.scrollbar_state.content_length(num_items),
+            );
+        }
+
+        if self.filtering || !self.input.input.is_empty() {
+            let search_area = Rect::new(
+                center.x,
+                center.y.saturating_sub(3),
+                center.width.min(50),
+                3,
+            );
+            let indicator = Paragraph::new(">")
+                .block(border_block(&ctx.theme, self.filtering).title(title!("Search downloads")));
+            super::clear(search_area, buf, ctx.theme.bg);
+            indicator.render(search_area, buf);
+
+            let input_area = search_area.inner(&Margin {
+                vertical: 1,
+                horizontal: 1,
+            });
+            let input_area = Rect::new(
+                input_area.x + 2,
+                input_area.y,
+                input_area.width.saturating_sub(2),
+                input_area.height,
+            );
+            Paragraph::new(self.input.input.clone()).render(input_area, buf);
+            if self.filtering {
+                self.input.show_cursor(f, input_area);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, ctx: &mut Context, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            if self.filtering {
+                return self.handle_filtering(ctx, e, code, modifiers);
+            }
+            let len = self.visible(ctx).len();
+            match ctx.keys.downloads.get(code, modifiers) {
+                Some(Action::Close) => {
+                    ctx.mode = Mode::Normal;
+                }
+                Some(Action::Down) => {
+                    self.table.next_wrap(len, 1);
+                }
+                Some(Action::Up) => {
+                    self.table.next_wrap(len, -1);
+                }
+                Some(Action::Bottom) => {
+                    self.table.select(len.saturating_sub(1));
+                }
+                Some(Action::Top) => {
+                    self.table.select(0);
+                }
+                Some(Action::Search) => {
+                    self.filtering = true;
+                }
+                Some(Action::Confirm) => {
+                    if let Some((_, entry)) = self.selected(ctx) {
+                        self.resend = Some((entry.to_item(), entry.source.clone()));
+                        ctx.mode = Mode::Loading(LoadType::Redownloading);
+                    }
+                }
+                Some(Action::OpenInBrowser) => {
+                    if let Some((_, entry)) = self.selected(ctx) {
+                        let link = entry.post_link.clone();
+                        if link.is_empty() {
+                            return ctx.show_error("No post link for this download");
+                        }
+                        match open::that_detached(link.clone()) {
+                            Ok(()) => ctx.notify(format!("Opened {}", link)),
+                            Err(e) => ctx.show_error(format!("Failed to open {}:\n{}", link, e)),
+                        }
+                    }
+                }
+                Some(Action::Delete) => {
+                    if let Some((_, entry)) = self.selected(ctx) {
+                        let changes = vec![DownloadChange::Remove(entry.clone())];
+                        ctx.downloads.apply(&changes);
+                        ctx.save_downloads(changes);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let mut help = keys.help(&[
+            (Action::Confirm, "Download again"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+            (Action::Search, "Search downloads"),
+            (Action::OpenInBrowser, "Open in browser"),
+            (Action::Delete, "Remove from history"),
+        ]);
+        if let Some(input_help) = input::InputWidget::get_help(keys) {
+            help.extend(input_help);
+        }
+        Some(help)
+    }
+}
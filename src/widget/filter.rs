@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Rect},
     widgets::{Row, StatefulWidget as _, Table},
@@ -7,6 +7,7 @@ use ratatui::{
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     style, title,
 };
 
@@ -50,26 +51,27 @@ impl Widget for FilterPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => {
+            match ctx.keys.filter.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     self.table.next_wrap(ctx.src_info.filters.len(), 1);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     self.table.next_wrap(ctx.src_info.filters.len(), -1);
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     self.table.select(ctx.src_info.filters.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     if let Some(i) = self.table.state.selected() {
                         self.selected = i;
                         ctx.mode = Mode::Loading(LoadType::Filtering);
@@ -83,14 +85,14 @@ impl Widget for FilterPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, f, q", "Close"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Confirm, "Confirm"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+        ]))
     }
 }
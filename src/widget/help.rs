@@ -1,22 +1,24 @@
 use std::cmp::{max, min};
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Alignment, Constraint, Margin, Rect},
     text::Line,
     widgets::{Row, ScrollbarOrientation, StatefulWidget as _, Table},
     Frame,
 };
+use unicode_width::UnicodeWidthStr as _;
 
 use crate::{
     app::{Context, Mode},
+    keybinds::{Action, KeyMap},
     style, title,
 };
 
 use super::{border_block, StatefulTable, Widget};
 
 pub struct HelpPopup {
-    pub table: StatefulTable<(&'static str, &'static str)>,
+    pub table: StatefulTable<(String, &'static str)>,
     pub prev_mode: Mode,
 }
 
@@ -30,7 +32,7 @@ impl Default for HelpPopup {
 }
 
 impl HelpPopup {
-    pub fn with_items(&mut self, items: Vec<(&'static str, &'static str)>, prev_mode: Mode) {
+    pub fn with_items(&mut self, items: Vec<(String, &'static str)>, prev_mode: Mode) {
         self.table.scrollbar_state = self.table.scrollbar_state.content_length(items.len());
         self.table.items = items;
         self.prev_mode = prev_mode;
@@ -44,7 +46,7 @@ impl Widget for HelpPopup {
 
         // Get max len of Key and Action
         let (key_max, map_max) = self.table.items.iter().fold((15, 15), |acc, e| {
-            (max(acc.0, e.0.len() as u16), max(acc.1, e.1.len() as u16))
+            (max(acc.0, e.0.width() as u16), max(acc.1, e.1.len() as u16))
         });
         // Cap height between the number of entries + 3 for padding, and 20
         let height = min(max_size, self.table.items.len() + 3) as u16;
@@ -52,7 +54,7 @@ impl Widget for HelpPopup {
         let center = super::centered_rect(key_max + map_max + 6, height, area);
         let items = self.table.items.iter().map(|(key, map)| {
             Row::new([
-                Line::from(*key).alignment(Alignment::Right),
+                Line::from(key.as_str()).alignment(Alignment::Right),
                 Line::from("⇒"),
                 Line::from(*map),
             ])
@@ -104,23 +106,24 @@ impl Widget for HelpPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('?') | KeyCode::F(1) | KeyCode::Char('q') => {
+            match ctx.keys.help.get(code, modifiers) {
+                Some(Action::Close) => {
                     self.prev_mode.clone_into(&mut ctx.mode);
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     self.table.next_wrap(1);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     self.table.next_wrap(-1);
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
                 _ => {}
@@ -128,7 +131,7 @@ impl Widget for HelpPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+    fn get_help(_keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
         None
     }
 }
@@ -0,0 +1,237 @@
+use std::cmp::min;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Margin, Rect},
+    text::Line,
+    widgets::{Paragraph, Row, ScrollbarOrientation, StatefulWidget, Table, Widget as _},
+    Frame,
+};
+
+use crate::{
+    app::{Context, LoadType, Mode},
+    history::SavedSearch,
+    keybinds::{Action, KeyMap},
+    style, title,
+};
+
+use super::{
+    border_block,
+    input::{self, InputWidget},
+    VirtualStatefulTable, Widget,
+};
+
+pub struct HistoryPopup {
+    pub table: VirtualStatefulTable,
+    pub input: InputWidget,
+    /// Whether the name of a saved search is being typed
+    pub naming: bool,
+    /// The search to run once the popup closes
+    pub restore: Option<SavedSearch>,
+}
+
+impl Default for HistoryPopup {
+    fn default() -> Self {
+        HistoryPopup {
+            table: VirtualStatefulTable::new(),
+            input: InputWidget::new(40, None),
+            naming: false,
+            restore: None,
+        }
+    }
+}
+
+impl HistoryPopup {
+    fn selected<'a>(&self, ctx: &'a Context) -> Option<&'a SavedSearch> {
+        ctx.history.get(self.table.selected().unwrap_or(0))
+    }
+
+    fn handle_naming(&mut self, ctx: &mut Context, e: &Event, code: &KeyCode, mods: &KeyModifiers) {
+        // Typed characters always go to the name, even if they are bound
+        let typing = matches!(code, KeyCode::Char(_))
+            && matches!(mods, &KeyModifiers::NONE | &KeyModifiers::SHIFT);
+        match ctx.keys.history.get(code, mods).filter(|_| !typing) {
+            Some(Action::Close) => self.naming = false,
+            Some(Action::Confirm) => {
+                let name = self.input.input.trim().to_owned();
+                if name.is_empty() {
+                    return;
+                }
+                if let Some(search) = self.selected(ctx).cloned() {
+                    ctx.history.save(name.to_owned(), &search);
+                    ctx.save_history();
+                    ctx.notify(format!("Saved search \"{}\"", name));
+                    if let Some(idx) = ctx.history.saved.iter().position(|s| s.name == name) {
+                        self.table.select(idx);
+                    }
+                }
+                self.naming = false;
+            }
+            _ => self.input.handle_event(ctx, e),
+        }
+    }
+}
+
+impl Widget for HistoryPopup {
+    fn draw(&mut self, f: &mut Frame, ctx: &Context, area: Rect) {
+        let buf = f.buffer_mut();
+        let num_items = ctx.history.len();
+        let height = min(
+            num_items.max(1) as u16 + 2,
+            area.height.saturating_sub(6).max(3),
+        );
+        let center = super::centered_rect(area.width.saturating_sub(8).min(80), height, area);
+        if self.table.selected().unwrap_or(0) >= num_items {
+            self.table.select(num_items.saturating_sub(1));
+        }
+
+        let saved = ctx.history.saved.iter().map(|s| ("★ ", s));
+        let recent = ctx.history.recent.iter().map(|s| ("  ", s));
+        let items = saved.chain(recent).map(|(prefix, s)| {
+            let mut info = vec![s.source.to_string(), s.category.to_owned()];
+            if let Some(user) = s.user.as_ref().filter(|u| !u.is_empty()) {
+                info.push(format!("by {}", user));
+            }
+            Row::new(vec![
+                Line::from(format!("{}{}", prefix, s.title())),
+                Line::styled(info.join(" "), style!(fg:ctx.theme.border_color)),
+            ])
+        });
+
+        super::scroll_padding(
+            self.table.selected().unwrap_or(0),
+            center.height as usize,
+            2,
+            num_items,
+            1,
+            self.table.state.offset_mut(),
+        );
+
+        super::clear(center, buf, ctx.theme.bg);
+        let block = border_block(&ctx.theme, !self.naming).title(title!("Search History"));
+        if num_items == 0 {
+            Paragraph::new("  No searches yet")
+                .block(block)
+                .render(center, buf);
+        } else {
+            let table = Table::new(
+                items,
+                [Constraint::Percentage(60), Constraint::Percentage(40)],
+            )
+            .block(block)
+            .highlight_style(style!(bg:ctx.theme.hl_bg));
+            StatefulWidget::render(table, center, buf, &mut self.table.state);
+        }
+
+        // Only show scrollbar if content overflows
+        if num_items as u16 + 1 >= center.height {
+            let sb = super::scrollbar(ctx, ScrollbarOrientation::VerticalRight);
+            let sb_area = center.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            });
+            sb.render(
+                sb_area,
+                buf,
+                &mut self.table.scrollbar_state.content_length(num_items),
+            );
+        }
+
+        if self.naming {
+            let name_area = Rect::new(
+                center.x,
+                (center.y + center.height).min(area.height.saturating_sub(3)),
+                center.width.min(44),
+                3,
+            );
+            let indicator =
+                Paragraph::new(">").block(border_block(&ctx.theme, true).title(title!("Save as")));
+            super::clear(name_area, buf, ctx.theme.bg);
+            indicator.render(name_area, buf);
+
+            let input_area = name_area.inner(&Margin {
+                vertical: 1,
+                horizontal: 1,
+            });
+            let input_area = Rect::new(
+                input_area.x + 2,
+                input_area.y,
+                input_area.width.saturating_sub(2),
+                input_area.height,
+            );
+            Paragraph::new(self.input.input.clone()).render(input_area, buf);
+            self.input.show_cursor(f, input_area);
+        }
+    }
+
+    fn handle_event(&mut self, ctx: &mut Context, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            if self.naming {
+                return self.handle_naming(ctx, e, code, modifiers);
+            }
+            let len = ctx.history.len();
+            match ctx.keys.history.get(code, modifiers) {
+                Some(Action::Close) => {
+                    ctx.mode = Mode::Normal;
+                }
+                Some(Action::Down) => {
+                    self.table.next_wrap(len, 1);
+                }
+                Some(Action::Up) => {
+                    self.table.next_wrap(len, -1);
+                }
+                Some(Action::Bottom) => {
+                    self.table.select(len.saturating_sub(1));
+                }
+                Some(Action::Top) => {
+                    self.table.select(0);
+                }
+                Some(Action::Confirm) => {
+                    if let Some(search) = self.selected(ctx).cloned() {
+                        self.restore = Some(search);
+                        ctx.mode = Mode::Loading(LoadType::Restoring);
+                    }
+                }
+                Some(Action::SaveSearch) => {
+                    if let Some(search) = self.selected(ctx) {
+                        self.input.input = search.title();
+                        self.input.set_cursor(self.input.input.chars().count());
+                        self.naming = true;
+                    }
+                }
+                Some(Action::Delete) => {
+                    if let Some(search) = ctx.history.remove(self.table.selected().unwrap_or(0)) {
+                        if !search.name.is_empty() {
+                            ctx.notify(format!("Removed saved search \"{}\"", search.name));
+                        }
+                        ctx.save_history();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let mut help = keys.help(&[
+            (Action::Confirm, "Run search"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+            (Action::SaveSearch, "Save search with a name"),
+            (Action::Delete, "Remove search"),
+        ]);
+        if let Some(input_help) = input::InputWidget::get_help(keys) {
+            help.extend(input_help);
+        }
+        Some(help)
+    }
+}
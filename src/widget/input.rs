@@ -11,7 +11,7 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthChar;
 
-use crate::app::Context;
+use crate::{app::Context, keybinds::KeyMap};
 
 pub struct InputWidget {
     pub input: String,
@@ -239,8 +239,8 @@ impl super::Widget for InputWidget {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
+    fn get_help(_keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let help = [
             ("←, Ctrl-h", "Move left"),
             ("→, Ctrl-l", "Move right"),
             ("Ctrl-u", "Clear search"),
@@ -252,6 +252,7 @@ impl super::Widget for InputWidget {
             ("Ctrl/Alt-Backspace", "Delete word backwards"),
             ("Del", "Delete letter forwards"),
             ("Backspace", "Delete letter backwards"),
-        ])
+        ];
+        Some(help.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
     }
 }
@@ -2,7 +2,7 @@ use crossterm::event::Event;
 use ratatui::{layout::Rect, Frame};
 use serde::{Deserialize, Serialize};
 
-use crate::app::Context;
+use crate::{app::Context, keybinds::KeyMap};
 
 use super::{notify_box::NotifyBox, Corner, Widget};
 
@@ -127,7 +127,7 @@ impl Widget for NotificationWidget {
 
     fn handle_event(&mut self, _ctx: &mut Context, _e: &Event) {}
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
+    fn get_help(_keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
         None
     }
 }
@@ -2,9 +2,10 @@ use std::cmp::{max, min};
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     title,
 };
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Margin, Rect},
     widgets::{Paragraph, Widget as _},
@@ -60,16 +61,17 @@ impl Widget for PagePopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc => {
+            match ctx.keys.page.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                     // Clear input on Esc
                     self.input.clear();
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     ctx.page = max(
                         min(
                             self.input.input.parse().unwrap_or(1),
@@ -88,11 +90,11 @@ impl Widget for PagePopup {
         self.input.handle_event(ctx, e);
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        let mut search_help = vec![("Enter", "Confirm"), ("Esc", "Stop")];
-        if let Some(input_help) = input::InputWidget::get_help() {
-            search_help.extend(input_help);
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let mut help = keys.help(&[(Action::Confirm, "Confirm"), (Action::Close, "Stop")]);
+        if let Some(input_help) = input::InputWidget::get_help(keys) {
+            help.extend(input_help);
         }
-        Some(search_help)
+        Some(help)
     }
 }
@@ -1,6 +1,6 @@
 use core::str;
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Margin, Rect},
     style::{Style, Stylize as _},
@@ -12,7 +12,9 @@ use ratatui::{
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     title,
+    watch::Watch,
     widget::sort::SortDir,
 };
 
@@ -174,13 +176,17 @@ impl super::Widget for ResultsWidget {
                 let lines = visible_items
                     .iter()
                     .map(|i| {
-                        Line::from(
-                            match selected_ids.contains(&i.id) {
-                                true => symbols::border::QUADRANT_BLOCK,
-                                false => vert_left,
+                        match (
+                            selected_ids.contains(&i.id),
+                            ctx.downloads.contains(i, &ctx.item_source(i)),
+                        ) {
+                            (true, _) => Line::from(symbols::border::QUADRANT_BLOCK),
+                            // Already sent to a download client
+                            (false, true) => {
+                                Line::styled("✓", Style::default().fg(ctx.theme.success))
                             }
-                            .to_owned(),
-                        )
+                            (false, false) => Line::from(vert_left.to_owned()),
+                        }
                     })
                     .collect::<Vec<Line>>();
                 let para = Paragraph::new(lines);
@@ -221,53 +227,52 @@ impl super::Widget for ResultsWidget {
             ..
         }) = e
         {
-            use KeyCode::*;
-            match (code, modifiers) {
-                (Char('c'), &KeyModifiers::NONE) => {
+            match ctx.keys.normal.get(code, modifiers) {
+                Some(Action::Categories) => {
                     ctx.mode = Mode::Category;
                 }
-                (Char('s'), &KeyModifiers::NONE) => {
+                Some(Action::Sort) => {
                     ctx.mode = Mode::Sort(SortDir::Desc);
                 }
-                (Char('S'), &KeyModifiers::SHIFT) => {
+                Some(Action::SortReversed) => {
                     ctx.mode = Mode::Sort(SortDir::Asc);
                 }
-                (Char('f'), &KeyModifiers::NONE) => {
+                Some(Action::Filters) => {
                     ctx.mode = Mode::Filter;
                 }
-                (Char('t'), &KeyModifiers::NONE) => {
+                Some(Action::Themes) => {
                     ctx.mode = Mode::Theme;
                 }
-                (Char('/') | Char('i'), &KeyModifiers::NONE) => {
+                Some(Action::Search) => {
                     ctx.mode = Mode::Search;
                 }
-                (Char('p'), &KeyModifiers::CONTROL) => {
+                Some(Action::GotoPage) => {
                     ctx.mode = Mode::Page;
                 }
-                (Char('p') | Char('h') | Left, &KeyModifiers::NONE) => {
+                Some(Action::PrevPage) => {
                     if ctx.page > 1 {
                         ctx.page -= 1;
                         ctx.mode = Mode::Loading(LoadType::Searching);
                     }
                 }
-                (Char('n') | Char('l') | Right, &KeyModifiers::NONE) => {
+                Some(Action::NextPage) => {
                     if ctx.page < ctx.results.response.last_page {
                         ctx.page += 1;
                         ctx.mode = Mode::Loading(LoadType::Searching);
                     }
                 }
-                (Char('r'), &KeyModifiers::NONE) => {
+                Some(Action::Reload) => {
                     ctx.mode = Mode::Loading(LoadType::Searching);
                 }
-                (Char('q'), &KeyModifiers::NONE) => {
+                Some(Action::Quit) => {
                     ctx.quit();
                 }
-                (Char('j') | KeyCode::Down, &KeyModifiers::NONE) => {
+                Some(Action::Down) => {
                     let prev = self.table.selected().unwrap_or(0);
                     let selected = self.table.next(ctx.results.response.items.len(), 1);
                     self.select_on_move(ctx, prev, selected);
                 }
-                (Char('k') | KeyCode::Up, &KeyModifiers::NONE) => {
+                Some(Action::Up) => {
                     let prev = self.table.selected().unwrap_or(0);
                     let selected = self.table.next(ctx.results.response.items.len(), -1);
                     self.select_on_move(ctx, selected, prev);
@@ -281,13 +286,13 @@ impl super::Widget for ResultsWidget {
                     //    );
                     //}
                 }
-                (Char('J'), &KeyModifiers::SHIFT) => {
+                Some(Action::JumpDown) => {
                     self.table.next(ctx.results.response.items.len(), 4);
                 }
-                (Char('K'), &KeyModifiers::SHIFT) => {
+                Some(Action::JumpUp) => {
                     self.table.next(ctx.results.response.items.len(), -4);
                 }
-                (Char('G'), &KeyModifiers::SHIFT) => {
+                Some(Action::Bottom) => {
                     let prev = self.table.selected().unwrap_or(0);
                     let selected = ctx.results.response.items.len().saturating_sub(1);
                     self.table.select(selected);
@@ -303,16 +308,16 @@ impl super::Widget for ResultsWidget {
                         //);
                     }
                 }
-                (Char('g'), &KeyModifiers::NONE) => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
-                (Char('H') | Char('P'), &KeyModifiers::SHIFT) => {
+                Some(Action::FirstPage) => {
                     if ctx.page != 1 {
                         ctx.page = 1;
                         ctx.mode = Mode::Loading(LoadType::Searching);
                     }
                 }
-                (Char('L') | Char('N'), &KeyModifiers::SHIFT) => {
+                Some(Action::LastPage) => {
                     if ctx.page != ctx.results.response.last_page
                         && ctx.results.response.last_page > 0
                     {
@@ -320,19 +325,19 @@ impl super::Widget for ResultsWidget {
                         ctx.mode = Mode::Loading(LoadType::Searching);
                     }
                 }
-                (Enter, &KeyModifiers::NONE) => {
+                Some(Action::Download) => {
                     ctx.mode = Mode::Loading(LoadType::Downloading);
                 }
-                (Char('s'), &KeyModifiers::CONTROL) => {
+                Some(Action::Sources) => {
                     ctx.mode = Mode::Sources;
                 }
-                (Char('d'), &KeyModifiers::NONE) => {
+                Some(Action::Clients) => {
                     ctx.mode = Mode::Clients;
                 }
-                (Char('u'), &KeyModifiers::NONE) => {
+                Some(Action::User) => {
                     ctx.mode = Mode::User;
                 }
-                (Char('o'), &KeyModifiers::NONE) => {
+                Some(Action::OpenInBrowser) => {
                     let link = ctx
                         .results
                         .response
@@ -347,8 +352,56 @@ impl super::Widget for ResultsWidget {
                         ctx.notify(format!("Opened {}", link));
                     }
                 }
-                (Char('y'), &KeyModifiers::NONE) => ctx.mode = Mode::KeyCombo("y".to_string()),
-                (Char(' '), &KeyModifiers::CONTROL) | (Char('v'), &KeyModifiers::NONE) => {
+                Some(Action::Details) => {
+                    ctx.mode = Mode::Loading(LoadType::Details);
+                }
+                Some(Action::Preview) => {
+                    ctx.mode = Mode::Loading(LoadType::Preview);
+                }
+                Some(Action::Watch) => {
+                    let mut watch =
+                        Watch::from_search(ctx.src.clone(), &ctx.results.search, &ctx.src_info);
+                    let watches = ctx.config.watches.get_or_insert_with(Vec::new);
+                    if let Some(key) = watches
+                        .iter()
+                        .find(|w| w.same_search(&watch))
+                        .map(Watch::key)
+                    {
+                        ctx.notify(format!("Already watching \"{}\"", key));
+                    } else {
+                        // Each watch keeps its own seen items, so the name must be unique
+                        let name = watch.name.clone();
+                        for n in 2.. {
+                            if !watches.iter().any(|w| w.key() == watch.key()) {
+                                break;
+                            }
+                            watch.name = format!("{} ({})", name, n);
+                        }
+                        let key = watch.key();
+                        watches.push(watch);
+                        match ctx.config.save_config_on_change {
+                            true => ctx.notify(format!("Added watch \"{}\"", key)),
+                            false => ctx.show_error(format!(
+                                "Added watch \"{}\", but it will not be saved since save_config_on_change is disabled",
+                                key
+                            )),
+                        }
+                        if let Err(e) = ctx.save_config() {
+                            ctx.show_error(e);
+                        }
+                    }
+                }
+                Some(Action::History) => {
+                    ctx.mode = Mode::History;
+                }
+                Some(Action::Downloads) => {
+                    ctx.mode = Mode::Downloads;
+                }
+                Some(Action::Status) => {
+                    ctx.mode = Mode::Status;
+                }
+                Some(Action::Copy) => ctx.mode = Mode::KeyCombo("y".to_string()),
+                Some(Action::VisualToggle) => {
                     self.control_space_toggle = match self.control_space_toggle {
                         VisualMode::None => VisualMode::Toggle,
                         _ => VisualMode::None,
@@ -362,7 +415,7 @@ impl super::Widget for ResultsWidget {
                         self.visual_anchor = 0;
                     }
                 }
-                (Char('V'), &KeyModifiers::SHIFT) => {
+                Some(Action::VisualSelect) => {
                     self.control_space_toggle = match self.control_space_toggle {
                         VisualMode::None => VisualMode::Select,
                         _ => VisualMode::None,
@@ -376,7 +429,7 @@ impl super::Widget for ResultsWidget {
                         self.visual_anchor = 0;
                     }
                 }
-                (Char(' '), &KeyModifiers::NONE) => {
+                Some(Action::ToggleBatch) => {
                     if let Some(sel) = self.table.state.selected() {
                         if let Some(item) = &mut ctx.results.response.items.get_mut(sel) {
                             if let Some(p) = ctx.batch.iter().position(|s| s.id == item.id) {
@@ -387,10 +440,10 @@ impl super::Widget for ResultsWidget {
                         }
                     }
                 }
-                (Tab | BackTab, _) => {
+                Some(Action::SwitchPane) => {
                     ctx.mode = Mode::Batch;
                 }
-                (Esc, &KeyModifiers::NONE) => {
+                Some(Action::Dismiss) => {
                     if self.control_space_toggle != VisualMode::None {
                         ctx.notify("Exited VISUAL mode");
                         self.visual_anchor = 0;
@@ -404,38 +457,47 @@ impl super::Widget for ResultsWidget {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc", "Dismiss notification"),
-            ("q", "Exit App"),
-            ("g/G", "Goto Top/Bottom"),
-            ("k, ↑", "Up"),
-            ("j, ↓", "Down"),
-            ("K, J", "Up/Down 4 items"),
-            ("n, l, →", "Next Page"),
-            ("p, h, ←", "Prev Page"),
-            ("N, L", "Last Page"),
-            ("P, H", "First Page"),
-            ("r", "Reload"),
-            ("o", "Open in browser"),
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Download, "Download"),
+            (Action::Dismiss, "Dismiss notification"),
+            (Action::Quit, "Exit App"),
+            (Action::Top, "Goto Top"),
+            (Action::Bottom, "Goto Bottom"),
+            (Action::Up, "Up"),
+            (Action::Down, "Down"),
+            (Action::JumpUp, "Up 4 items"),
+            (Action::JumpDown, "Down 4 items"),
+            (Action::NextPage, "Next Page"),
+            (Action::PrevPage, "Prev Page"),
+            (Action::LastPage, "Last Page"),
+            (Action::FirstPage, "First Page"),
+            (Action::Reload, "Reload"),
+            (Action::OpenInBrowser, "Open in browser"),
+            (Action::Details, "Show torrent details"),
+            (Action::Preview, "Preview .torrent file"),
+            (Action::Watch, "Watch current search"),
+            (Action::History, "Search history"),
+            (Action::Downloads, "Download history"),
+            (Action::Status, "Torrent status"),
             (
-                "yt, ym, yp, yi, yn",
-                "Copy torrent/magnet/post link/imdb id/name",
+                Action::Copy,
+                "Copy torrent/magnet/post link/imdb id/name (then t, m, p, i, n)",
             ),
-            ("Space", "Toggle item for batch download"),
-            ("Ctrl-Space", "Multi-line select torrents"),
-            ("Tab/Shift-Tab", "Switch to Batches"),
-            ("/, i", "Search"),
-            ("c", "Categories"),
-            ("f", "Filters"),
-            ("s", "Sort"),
-            ("S", "Sort reversed"),
-            ("t", "Themes"),
-            ("u", "Filter by User"),
-            ("d", "Select download client"),
-            ("Ctrl-p", "Goto page"),
-            ("Ctrl-s", "Select source"),
-        ])
+            (Action::ToggleBatch, "Toggle item for batch download"),
+            (Action::VisualToggle, "Multi-line toggle torrents"),
+            (Action::VisualSelect, "Multi-line select torrents"),
+            (Action::SwitchPane, "Switch to Batches"),
+            (Action::Search, "Search"),
+            (Action::Categories, "Categories"),
+            (Action::Filters, "Filters"),
+            (Action::Sort, "Sort"),
+            (Action::SortReversed, "Sort reversed"),
+            (Action::Themes, "Themes"),
+            (Action::User, "Filter by User"),
+            (Action::Clients, "Select download client"),
+            (Action::GotoPage, "Goto page"),
+            (Action::Sources, "Select source"),
+        ]))
     }
 }
@@ -1,4 +1,4 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Margin, Rect},
     style::Stylize,
@@ -8,6 +8,7 @@ use ratatui::{
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     title,
 };
 
@@ -65,12 +66,11 @@ impl super::Widget for SearchWidget {
             ..
         }) = evt
         {
-            use KeyCode::*;
-            match (code, modifiers) {
-                (Esc, &KeyModifiers::NONE) => {
+            match ctx.keys.search.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                (Enter, &KeyModifiers::NONE) => {
+                Some(Action::Confirm) => {
                     ctx.mode = Mode::Loading(LoadType::Searching);
                     ctx.page = 1; // Go back to first page
                 }
@@ -80,11 +80,11 @@ impl super::Widget for SearchWidget {
         self.input.handle_event(ctx, evt);
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        let mut search_help = vec![("Enter", "Confirm"), ("Esc", "Stop")];
-        if let Some(input_help) = input::InputWidget::get_help() {
-            search_help.extend(input_help);
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let mut help = keys.help(&[(Action::Confirm, "Confirm"), (Action::Close, "Stop")]);
+        if let Some(input_help) = input::InputWidget::get_help(keys) {
+            help.extend(input_help);
         }
-        Some(search_help)
+        Some(help)
     }
 }
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Rect},
     widgets::{Row, StatefulWidget as _, Table},
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     style, title,
 };
 
@@ -101,26 +102,27 @@ impl Widget for SortPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('q') => {
+            match ctx.keys.sort.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     self.table.next_wrap(ctx.src_info.sorts.len(), 1);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     self.table.next_wrap(ctx.src_info.sorts.len(), -1);
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     self.table.select(ctx.src_info.sorts.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     if let Some(i) = self.table.state.selected() {
                         self.selected.sort = i;
                         self.selected.dir = match ctx.mode == Mode::Sort(SortDir::Asc) {
@@ -138,14 +140,14 @@ impl Widget for SortPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, s, q", "Close"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Confirm, "Confirm"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+        ]))
     }
 }
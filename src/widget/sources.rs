@@ -1,14 +1,14 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Rect},
     widgets::{Row, StatefulWidget as _, Table},
     Frame,
 };
-use strum::VariantArray;
 
 use crate::{
     app::{Context, LoadType, Mode},
-    source::Sources,
+    keybinds::{Action, KeyMap},
+    source::{SourceConfig, Sources},
     style, title,
 };
 
@@ -21,7 +21,17 @@ pub struct SourcesPopup {
 impl Default for SourcesPopup {
     fn default() -> Self {
         SourcesPopup {
-            table: StatefulTable::new(Sources::VARIANTS),
+            table: StatefulTable::new(Sources::BUILTIN),
+        }
+    }
+}
+
+impl SourcesPopup {
+    /// List the built-in sources followed by the custom sources in the config
+    pub fn load_sources(&mut self, config: &SourceConfig) {
+        self.table.items = Sources::all(config);
+        if self.table.selected().is_none() {
+            self.table.select(0);
         }
     }
 }
@@ -47,30 +57,31 @@ impl Widget for SourcesPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('q') => {
+            match ctx.keys.sources.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     self.table.next_wrap(1);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     self.table.next_wrap(-1);
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     self.table.select(self.table.items.len() - 1);
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.table.select(0);
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     if let Some(src) = self.table.selected() {
                         if !src.eq(&ctx.src) {
-                            ctx.src = *src;
-                            ctx.config.source = *src;
+                            ctx.src = src.clone();
+                            ctx.config.source = src.clone();
                             ctx.mode = Mode::Loading(LoadType::Sourcing);
                             src.load_config(&mut ctx.config.sources);
                             match ctx.save_config() {
@@ -91,14 +102,14 @@ impl Widget for SourcesPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, Ctrl-s, q", "Close"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Confirm, "Confirm"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+        ]))
     }
 }
@@ -0,0 +1,404 @@
+use std::cmp::min;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use human_bytes::human_bytes;
+use ratatui::{
+    layout::{Alignment, Constraint, Margin, Rect},
+    text::Line,
+    widgets::{Paragraph, Row, ScrollbarOrientation, StatefulWidget, Table, Widget as _},
+    Frame,
+};
+
+use crate::{
+    app::{Context, Mode},
+    client::{Profile, StatusResult, TorrentAction, TorrentState, TorrentStatus},
+    keybinds::{Action, KeyMap},
+    style, title,
+};
+
+use super::{
+    border_block,
+    input::{self, InputWidget},
+    Corner, VirtualStatefulTable, Widget,
+};
+
+pub struct StatusPopup {
+    pub table: VirtualStatefulTable,
+    /// `None` until the first response from the clients, each torrent is
+    /// paired with the client it was found in
+    pub torrents: Option<Vec<(Profile, TorrentStatus)>>,
+    /// The clients that failed to list their torrents
+    pub errors: Vec<(Profile, String)>,
+    /// Whether a request to the clients is in progress
+    pub loading: bool,
+    /// Whether the torrents should be loaded again
+    pub refresh: bool,
+    /// The action to send to a download client, with the client, hash and name
+    /// of the torrent
+    pub action: Option<(Profile, String, String, TorrentAction)>,
+    pub input: InputWidget,
+    /// Whether the category input is focused
+    pub categorizing: bool,
+    /// Hash of the torrent waiting for a second press to delete its files
+    confirm_delete: Option<String>,
+}
+
+impl Default for StatusPopup {
+    fn default() -> Self {
+        StatusPopup {
+            table: VirtualStatefulTable::new(),
+            torrents: None,
+            errors: vec![],
+            loading: false,
+            refresh: true,
+            action: None,
+            input: InputWidget::new(60, None),
+            categorizing: false,
+            confirm_delete: None,
+        }
+    }
+}
+
+impl StatusPopup {
+    /// Hashes of every torrent in the download history, newest first, grouped
+    /// by the client each was sent to
+    pub fn hashes(ctx: &Context) -> Vec<(Profile, Vec<String>)> {
+        let mut groups: Vec<(Profile, Vec<String>)> = vec![];
+        for d in ctx.downloads.downloads.iter() {
+            let hash = d.hash.to_lowercase();
+            if hash.is_empty() {
+                continue;
+            }
+            match groups.iter_mut().find(|(p, _)| *p == d.client) {
+                Some((_, hashes)) if hashes.contains(&hash) => {}
+                Some((_, hashes)) => hashes.push(hash),
+                None => groups.push((d.client.clone(), vec![hash])),
+            }
+        }
+        groups
+    }
+
+    pub fn update(&mut self, ctx: &Context, res: StatusResult) {
+        self.loading = false;
+        // The popup was closed before the clients responded
+        if ctx.mode != Mode::Status {
+            return;
+        }
+        let mut torrents = vec![];
+        self.errors.clear();
+        for (client, res) in res.into_iter() {
+            match res {
+                Ok(found) => torrents.extend(found.into_iter().map(|t| (client.clone(), t))),
+                Err(e) => self.errors.push((client, e.to_string())),
+            }
+        }
+        // Keep the same order as the download history
+        let order = ctx
+            .downloads
+            .downloads
+            .iter()
+            .map(|d| d.hash.to_lowercase())
+            .collect::<Vec<String>>();
+        torrents.sort_by_key(|(_, t)| order.iter().position(|h| *h == t.hash));
+        self.torrents = Some(torrents);
+    }
+
+    fn selected(&self) -> Option<&(Profile, TorrentStatus)> {
+        self.torrents
+            .as_ref()?
+            .get(self.table.selected().unwrap_or(0))
+    }
+
+    fn act(&mut self, action: TorrentAction) {
+        if let Some((client, t)) = self.selected() {
+            self.action = Some((client.clone(), t.hash.to_owned(), t.name.to_owned(), action));
+        }
+    }
+
+    fn handle_categorizing(
+        &mut self,
+        ctx: &mut Context,
+        e: &Event,
+        code: &KeyCode,
+        mods: &KeyModifiers,
+    ) {
+        // Typed characters always go to the input, even if they are bound
+        let typing = matches!(code, KeyCode::Char(_))
+            && matches!(mods, &KeyModifiers::NONE | &KeyModifiers::SHIFT);
+        match ctx.keys.status.get(code, mods).filter(|_| !typing) {
+            Some(Action::Close) => self.categorizing = false,
+            Some(Action::Confirm) => {
+                self.categorizing = false;
+                self.act(TorrentAction::SetCategory(self.input.input.to_owned()));
+            }
+            _ => self.input.handle_event(ctx, e),
+        }
+    }
+}
+
+fn format_eta(eta: Option<u64>) -> String {
+    let Some(secs) = eta else {
+        return "∞".to_owned();
+    };
+    let (d, h, m, s) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    match (d, h, m) {
+        (0, 0, 0) => format!("{}s", s),
+        (0, 0, _) => format!("{}m {}s", m, s),
+        (0, _, _) => format!("{}h {}m", h, m),
+        _ => format!("{}d {}h", d, h),
+    }
+}
+
+fn format_speed(speed: u64) -> String {
+    match speed {
+        0 => "-".to_owned(),
+        s => format!("{}/s", human_bytes(s as f64).replace('i', "")),
+    }
+}
+
+impl Widget for StatusPopup {
+    fn draw(&mut self, f: &mut Frame, ctx: &Context, area: Rect) {
+        let buf = f.buffer_mut();
+        let torrents = self.torrents.clone().unwrap_or_default();
+        let num_items = torrents.len();
+        // The errors are only shown in place of the torrents if none were found
+        let error = (num_items == 0 && !self.errors.is_empty()).then(|| {
+            self.errors
+                .iter()
+                .map(|(client, e)| format!("{}:\n{}", client, e))
+                .collect::<Vec<String>>()
+                .join("\n")
+        });
+        // Either the header and torrents, or a message
+        let lines = match error.as_ref() {
+            Some(e) => e.lines().count() + 1,
+            None => num_items + 1,
+        };
+        let height = min(lines as u16 + 2, area.height.saturating_sub(7).max(3));
+        let center = super::centered_rect(area.width.saturating_sub(8).min(110), height, area);
+        if self.table.selected().unwrap_or(0) >= num_items {
+            self.table.select(num_items.saturating_sub(1));
+        }
+
+        let items = torrents.iter().map(|(_, t)| {
+            let state_color = match t.state {
+                TorrentState::Downloading => ctx.theme.fg,
+                TorrentState::Seeding => ctx.theme.success,
+                TorrentState::Error => ctx.theme.error,
+                _ => ctx.theme.border_color,
+            };
+            let eta = match t.state {
+                TorrentState::Downloading | TorrentState::Stalled => format_eta(t.eta),
+                _ => "-".to_owned(),
+            };
+            Row::new(vec![
+                Line::from(t.name.to_owned()),
+                Line::from(human_bytes(t.size as f64).replace('i', "")).alignment(Alignment::Right),
+                Line::from(format!("{:.1}%", t.progress * 100.0)).alignment(Alignment::Right),
+                Line::styled(t.state.to_string(), style!(fg:state_color)),
+                Line::from(format_speed(t.download_speed)).alignment(Alignment::Right),
+                Line::from(format_speed(t.upload_speed)).alignment(Alignment::Right),
+                Line::from(eta).alignment(Alignment::Right),
+            ])
+        });
+
+        super::scroll_padding(
+            self.table.selected().unwrap_or(0),
+            center.height as usize,
+            3,
+            num_items,
+            1,
+            self.table.state.offset_mut(),
+        );
+
+        super::clear(center, buf, ctx.theme.bg);
+        let clients = Self::hashes(ctx)
+            .into_iter()
+            .map(|(c, _)| c.to_string())
+            .collect::<Vec<String>>();
+        let clients = match clients.is_empty() {
+            true => ctx.client.to_string(),
+            false => clients.join(", "),
+        };
+        let block = border_block(&ctx.theme, true).title(title!("Torrents in {}", clients));
+        if let Some(e) = error.as_ref() {
+            let msg = format!("  Failed to get torrents from {}", e.replace('\n', "\n  "));
+            Paragraph::new(msg)
+                .style(style!(fg:ctx.theme.error))
+                .block(block)
+                .render(center, buf);
+        } else if num_items == 0 {
+            let msg = match self.torrents {
+                None => "  Loading torrents...",
+                Some(_) => "  No torrents from the download history found in the clients",
+            };
+            Paragraph::new(msg).block(block).render(center, buf);
+        } else {
+            let header = Row::new(["Name", "Size", "Progress", "State", "Down", "Up", "ETA"])
+                .style(style!(fg:ctx.theme.border_color));
+            let table = Table::new(
+                items,
+                [
+                    Constraint::Min(10),
+                    Constraint::Length(9),
+                    Constraint::Length(8),
+                    Constraint::Length(11),
+                    Constraint::Length(11),
+                    Constraint::Length(11),
+                    Constraint::Length(7),
+                ],
+            )
+            .header(header)
+            .block(block)
+            .highlight_style(style!(bg:ctx.theme.hl_bg));
+            StatefulWidget::render(table, center, buf, &mut self.table.state);
+        }
+
+        if error.is_none() && !self.errors.is_empty() {
+            let failed = self
+                .errors
+                .iter()
+                .map(|(c, _)| c.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            let failed = Line::styled(
+                title!("Failed to reach {}", failed),
+                style!(fg:ctx.theme.error),
+            );
+            if let Some((bl, area)) = Corner::BottomLeft.try_title(failed, center, false) {
+                bl.render(area, buf);
+            }
+        }
+
+        // Only show scrollbar if content overflows
+        if error.is_none() && num_items as u16 + 2 >= center.height {
+            let sb = super::scrollbar(ctx, ScrollbarOrientation::VerticalRight);
+            let sb_area = center.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            });
+            sb.render(
+                sb_area,
+                buf,
+                &mut self.table.scrollbar_state.content_length(num_items),
+            );
+        }
+
+        if self.categorizing {
+            let input_area = Rect::new(
+                center.x,
+                center.y.saturating_sub(3),
+                center.width.min(50),
+                3,
+            );
+            super::clear(input_area, buf, ctx.theme.bg);
+            Paragraph::new(format!("> {}", self.input.input))
+                .block(border_block(&ctx.theme, true).title(title!("Set category")))
+                .render(input_area, buf);
+            let cursor_area = input_area.inner(&Margin {
+                vertical: 1,
+                horizontal: 1,
+            });
+            let cursor_area = Rect::new(
+                cursor_area.x + 2,
+                cursor_area.y,
+                cursor_area.width.saturating_sub(2),
+                cursor_area.height,
+            );
+            self.input.show_cursor(f, cursor_area);
+        }
+    }
+
+    fn handle_event(&mut self, ctx: &mut Context, e: &Event) {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            modifiers,
+            ..
+        }) = e
+        {
+            if self.categorizing {
+                return self.handle_categorizing(ctx, e, code, modifiers);
+            }
+            let len = self.torrents.as_ref().map(Vec::len).unwrap_or(0);
+            let action = ctx.keys.status.get(code, modifiers);
+            let confirm_delete = self.confirm_delete.take();
+            match action {
+                Some(Action::Close) => {
+                    ctx.mode = Mode::Normal;
+                    // Don't show stale torrents when opened again
+                    self.torrents = None;
+                    self.errors.clear();
+                    self.refresh = true;
+                }
+                Some(Action::Down) => {
+                    self.table.next_wrap(len, 1);
+                }
+                Some(Action::Up) => {
+                    self.table.next_wrap(len, -1);
+                }
+                Some(Action::Bottom) => {
+                    self.table.select(len.saturating_sub(1));
+                }
+                Some(Action::Top) => {
+                    self.table.select(0);
+                }
+                Some(Action::Reload) => {
+                    self.refresh = true;
+                }
+                Some(Action::Pause) => self.act(TorrentAction::Pause),
+                Some(Action::Resume) => self.act(TorrentAction::Resume),
+                Some(Action::Remove) => self.act(TorrentAction::Remove),
+                Some(Action::Recheck) => self.act(TorrentAction::Recheck),
+                Some(Action::RemoveData) => {
+                    let Some((_, t)) = self.selected() else {
+                        return;
+                    };
+                    // Deleting files can't be undone, so ask for a second press
+                    if confirm_delete.as_ref() == Some(&t.hash) {
+                        self.act(TorrentAction::RemoveData);
+                    } else {
+                        let key = ctx
+                            .keys
+                            .status
+                            .keys(Action::RemoveData)
+                            .first()
+                            .map(|k| k.to_string())
+                            .unwrap_or_default();
+                        ctx.notify(format!(
+                            "Press {} again to remove \"{}\" and delete its files",
+                            key, t.name
+                        ));
+                        self.confirm_delete = Some(t.hash.to_owned());
+                    }
+                }
+                Some(Action::SetCategory) if self.selected().is_some() => {
+                    self.input.clear();
+                    self.categorizing = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let mut help = keys.help(&[
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+            (Action::Reload, "Refresh now"),
+            (Action::Pause, "Pause"),
+            (Action::Resume, "Resume"),
+            (Action::Remove, "Remove"),
+            (Action::RemoveData, "Remove and delete files"),
+            (Action::Recheck, "Recheck"),
+            (Action::SetCategory, "Set category"),
+        ]);
+        if let Some(input_help) = input::InputWidget::get_help(keys) {
+            help.extend(input_help);
+        }
+        Some(help)
+    }
+}
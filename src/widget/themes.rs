@@ -1,6 +1,6 @@
 use std::cmp::min;
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Margin, Rect},
     widgets::{Row, ScrollbarOrientation, StatefulWidget as _, Table},
@@ -9,6 +9,7 @@ use ratatui::{
 
 use crate::{
     app::{Context, Mode},
+    keybinds::{Action, KeyMap},
     style, title,
 };
 
@@ -90,34 +91,35 @@ impl Widget for ThemePopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc | KeyCode::Char('t') | KeyCode::Char('q') => {
+            match ctx.keys.theme.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                     if Some(self.selected) != self.table.selected() {
                         preview_theme(self.selected, ctx);
                     }
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::Down) => {
                     let idx = self.table.next_wrap(ctx.themes.len(), 1);
                     preview_theme(idx, ctx);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     let idx = self.table.next_wrap(ctx.themes.len(), -1);
                     preview_theme(idx, ctx);
                 }
-                KeyCode::Char('G') => {
+                Some(Action::Bottom) => {
                     let idx = ctx.themes.len().saturating_sub(1);
                     self.table.select(idx);
                     preview_theme(idx, ctx);
                 }
-                KeyCode::Char('g') => {
+                Some(Action::Top) => {
                     self.table.select(0);
                     preview_theme(0, ctx);
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     let idx = self.table.selected().unwrap_or(0);
                     if let Some((_, theme)) = ctx.themes.get_index(idx) {
                         let theme_name = theme.name.clone();
@@ -144,14 +146,14 @@ impl Widget for ThemePopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        Some(vec![
-            ("Enter", "Confirm"),
-            ("Esc, t, q", "Close"),
-            ("j, ↓", "Down"),
-            ("k, ↑", "Up"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-        ])
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        Some(keys.help(&[
+            (Action::Confirm, "Confirm"),
+            (Action::Close, "Close"),
+            (Action::Down, "Down"),
+            (Action::Up, "Up"),
+            (Action::Top, "Top"),
+            (Action::Bottom, "Bottom"),
+        ]))
     }
 }
@@ -1,8 +1,9 @@
 use crate::{
     app::{Context, LoadType, Mode},
+    keybinds::{Action, KeyMap},
     title,
 };
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Margin, Rect},
     widgets::{Paragraph, Widget as _},
@@ -58,14 +59,15 @@ impl Widget for UserPopup {
         if let Event::Key(KeyEvent {
             code,
             kind: KeyEventKind::Press,
+            modifiers,
             ..
         }) = e
         {
-            match code {
-                KeyCode::Esc => {
+            match ctx.keys.user.get(code, modifiers) {
+                Some(Action::Close) => {
                     ctx.mode = Mode::Normal;
                 }
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     ctx.user = Some(self.input.input.to_owned());
                     ctx.mode = Mode::Loading(LoadType::Searching);
                 }
@@ -76,11 +78,11 @@ impl Widget for UserPopup {
         }
     }
 
-    fn get_help() -> Option<Vec<(&'static str, &'static str)>> {
-        let mut search_help = vec![("Enter", "Confirm"), ("Esc", "Stop")];
-        if let Some(input_help) = input::InputWidget::get_help() {
-            search_help.extend(input_help);
+    fn get_help(keys: &KeyMap) -> Option<Vec<(String, &'static str)>> {
+        let mut help = keys.help(&[(Action::Confirm, "Confirm"), (Action::Close, "Stop")]);
+        if let Some(input_help) = input::InputWidget::get_help(keys) {
+            help.extend(input_help);
         }
-        Some(search_help)
+        Some(help)
     }
 }
@@ -0,0 +1,80 @@
+use nyaa::{
+    cli::{self, QueryArgs},
+    config::Config,
+    source::{
+        anidex::{parse_anidex, AniDexConfig, AniDexSource},
+        Sources,
+    },
+    widget::sort::SortDir,
+};
+use reqwest::Url;
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn test_parse_anidex() {
+    let html = include_str!("fixtures/anidex.html");
+    let base_url = Url::parse("https://anidex.info/?page=search").unwrap();
+    let res = parse_anidex::<AniDexSource>(html, &base_url, None, 2).unwrap();
+
+    assert_eq!(res.items.len(), 2);
+    // Less than a full page, so this is the last one
+    assert_eq!(res.last_page, 2);
+    assert_eq!(res.total_results, 52);
+
+    let first = &res.items[0];
+    assert_eq!(first.title, "[Group] Frieren - 01 [1080p]");
+    assert_eq!(first.id, "anidex-321001");
+    assert_eq!(first.post_link, "https://anidex.info/torrent/321001");
+    assert_eq!(first.torrent_link, "https://anidex.info/dl/321001");
+    assert_eq!(
+        first.extra.get("hash").map(String::as_str),
+        Some("aaaabbbbccccddddeeeeffff0000111122223333")
+    );
+    assert_eq!(first.category, 1);
+    assert_eq!(first.size, "1.37 GB");
+    assert_eq!(first.timestamp, 1700000000);
+    assert_eq!(first.seeders, 87);
+    assert_eq!(first.leechers, 5);
+    assert_eq!(first.downloads, 1204);
+
+    let second = &res.items[1];
+    assert_eq!(second.category, 8);
+    assert_eq!(second.seeders, 0);
+    assert_eq!(second.downloads, 33);
+}
+
+#[tokio::test]
+async fn test_anidex_search() {
+    let html = include_str!("fixtures/anidex.html").to_owned();
+    let (addr, server) = serve(vec![(200, html)]);
+
+    let mut config = Config::default();
+    config.sources.anidex = Some(AniDexConfig {
+        base_url: addr,
+        ..Default::default()
+    });
+    let args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        source: Some("anidex".to_owned()),
+        category: Some("AnimeSub".to_owned()),
+        filter: Some("Batches Only".to_owned()),
+        sort: Some("Seeders".to_owned()),
+        sort_dir: Some(SortDir::Asc),
+        page: Some(3),
+        ..Default::default()
+    };
+
+    let (src, _, res) = cli::search(&args, &config).await.unwrap();
+    assert!(src == Sources::AniDex);
+    assert_eq!(res.items.len(), 2);
+
+    // Sorting, filtering and paging are all done by AniDex
+    let requests = server.join().unwrap();
+    assert!(
+        requests[0].starts_with("GET /?page=search&q=frieren&s=seeders&o=asc&offset=100&id=1&b=1 ")
+    );
+}
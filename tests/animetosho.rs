@@ -0,0 +1,139 @@
+use nyaa::{
+    cli::{self, QueryArgs},
+    config::Config,
+    source::{
+        animetosho::{
+            parse_animetosho, parse_animetosho_details, AnimeToshoConfig, AnimeToshoSource,
+        },
+        ItemFile, Sources,
+    },
+};
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn test_parse_animetosho() {
+    let json = include_bytes!("fixtures/animetosho.json");
+    let res = parse_animetosho::<AnimeToshoSource>(json, None, 1).unwrap();
+
+    assert_eq!(res.total_results, 2);
+    assert_eq!(res.last_page, 2);
+    assert_eq!(res.items.len(), 2);
+
+    let first = &res.items[0];
+    assert_eq!(
+        first.title,
+        "[SubsPlease] Frieren - 01 (1080p) [F02B9CEE].mkv"
+    );
+    assert_eq!(first.id, "animetosho-600001");
+    assert_eq!(first.timestamp, 1700000000);
+    assert_eq!(first.seeders, 1203);
+    assert_eq!(first.leechers, 47);
+    assert_eq!(first.downloads, 48213);
+    assert_eq!(first.bytes, 1454513254);
+    // Nyaa takes priority over the other trackers it was mirrored from
+    assert_eq!(first.category, 1);
+    assert_eq!(
+        first.file_name,
+        "[SubsPlease] Frieren - 01 (1080p) [F02B9CEE].mkv.torrent"
+    );
+    assert!(first
+        .magnet_link
+        .starts_with("magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&"));
+    let extra = |key: &str| first.extra.get(key).map(String::as_str);
+    assert_eq!(
+        extra("hash"),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(extra("nyaa_id"), Some("1700000"));
+    assert_eq!(extra("nyaa_link"), Some("https://nyaa.si/view/1700000"));
+    assert_eq!(extra("files"), Some("1"));
+    assert!(extra("nzb").is_some_and(|n| n.ends_with(".nzb")));
+
+    let second = &res.items[1];
+    assert_eq!(second.category, 3);
+    assert_eq!(second.seeders, 0);
+    assert_eq!(
+        second.file_name,
+        "fedcba9876543210fedcba9876543210fedcba98.torrent"
+    );
+    assert_eq!(
+        second.magnet_link,
+        "magnet:?xt=urn:btih:fedcba9876543210fedcba9876543210fedcba98&dn=%5BGroup%5D%20Some%20Show%20-%20Batch"
+    );
+    assert_eq!(second.extra.get("files").map(String::as_str), Some("12"));
+    assert!(!second.extra.contains_key("nyaa_id"));
+    assert!(!second.extra.contains_key("nzb"));
+
+    let empty = parse_animetosho::<AnimeToshoSource>(b"[]", None, 3).unwrap();
+    assert_eq!(empty.last_page, 3);
+}
+
+#[test]
+fn test_parse_animetosho_details() {
+    let json = include_bytes!("fixtures/animetosho_torrent.json");
+    let details = parse_animetosho_details(json, Some("%s".to_owned())).unwrap();
+
+    assert_eq!(details.date, "1700000000");
+    assert_eq!(details.size, "1.5 GB");
+    assert_eq!(
+        details.info_hash,
+        "0123456789abcdef0123456789abcdef01234567"
+    );
+    assert_eq!(
+        details.files,
+        vec![ItemFile {
+            path: "[SubsPlease] Frieren - 01 (1080p) [F02B9CEE].mkv".to_owned(),
+            size: "1.5 GB".to_owned(),
+        }]
+    );
+    assert!(details
+        .description
+        .contains("Nyaa: https://nyaa.si/view/1700000"));
+    assert!(details.description.contains("NZB: https://"));
+    assert!(details.description.ends_with("Attachments: 2"));
+}
+
+#[tokio::test]
+async fn test_animetosho_search() {
+    let json = include_str!("fixtures/animetosho.json").to_owned();
+    let details = include_str!("fixtures/animetosho_torrent.json").to_owned();
+    let (addr, server) = serve(vec![(200, json), (200, details)]);
+
+    let mut config = Config::default();
+    config.sources.animetosho = Some(AnimeToshoConfig {
+        base_url: addr,
+        ..Default::default()
+    });
+    let args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        source: Some("animetosho".to_owned()),
+        category: Some("AniDex".to_owned()),
+        page: Some(2),
+        ..Default::default()
+    };
+
+    // The category only keeps items mirrored from AniDex
+    let (src, _, res) = cli::search(&args, &config).await.unwrap();
+    assert!(src == Sources::AnimeTosho);
+    assert_eq!(res.items.len(), 1);
+    assert_eq!(res.items[0].id, "animetosho-600002");
+
+    let item =
+        parse_animetosho::<AnimeToshoSource>(include_bytes!("fixtures/animetosho.json"), None, 1)
+            .unwrap()
+            .items
+            .remove(0);
+    let details = Sources::AnimeTosho
+        .details(&reqwest::Client::new(), &item, &config.sources)
+        .await
+        .unwrap();
+    assert_eq!(details.files.len(), 1);
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /json?q=frieren&page=2 "));
+    assert!(requests[1].starts_with("GET /json?show=torrent&id=600001 "));
+}
@@ -0,0 +1,168 @@
+use nyaa::{
+    cli::{self, DownloadArgs, OutputFormat, QueryArgs},
+    client::{Client, Profile},
+    config::Config,
+    source::{torznab::TorznabConfig, Sources},
+    widget::sort::SortDir,
+};
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn test_build_query() {
+    let mut config = Config::default();
+    Sources::Nyaa.load_config(&mut config.sources);
+
+    let args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        category: Some("anime-english-translated".to_owned()),
+        filter: Some("TrustedOnly".to_owned()),
+        sort: Some("seeders".to_owned()),
+        sort_dir: Some(SortDir::Asc),
+        page: Some(2),
+        ..Default::default()
+    };
+    let search = cli::build_query(&Sources::Nyaa, &args, &config.sources).unwrap();
+    assert_eq!(search.query, "frieren");
+    assert_eq!(search.category, 12);
+    assert_eq!(search.filter, 2);
+    assert_eq!(search.sort.sort, 2);
+    assert!(search.sort.dir == SortDir::Asc);
+    assert_eq!(search.page, 2);
+
+    // Unspecified options fall back on the source defaults
+    let search = cli::build_query(&Sources::Nyaa, &QueryArgs::default(), &config.sources).unwrap();
+    assert_eq!(search.category, 0);
+    assert_eq!(search.sort.sort, 0);
+    assert!(search.sort.dir == SortDir::Desc);
+    assert_eq!(search.page, 1);
+
+    let bad = QueryArgs {
+        sort: Some("popularity".to_owned()),
+        ..Default::default()
+    };
+    assert!(cli::build_query(&Sources::Nyaa, &bad, &config.sources).is_err());
+    assert!(cli::find_source("torrent galaxy", &config.sources).unwrap() == Sources::TorrentGalaxy);
+    assert!(cli::find_source("piratebay", &config.sources).is_err());
+}
+
+#[tokio::test]
+async fn test_search_output() {
+    let xml = include_str!("fixtures/torznab.xml").to_owned();
+    let (addr, handle) = serve(vec![(200, xml.clone()), (200, xml.clone()), (200, xml)]);
+
+    let mut config = Config::default();
+    config.sources.torznab = Some(TorznabConfig {
+        base_url: format!("{}/api", addr),
+        ..Default::default()
+    });
+    let args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        source: Some("torznab".to_owned()),
+        ..Default::default()
+    };
+
+    let (src, search, res) = cli::search(&args, &config).await.unwrap();
+    let json =
+        cli::format_results(&src, &search, &res, &config.sources, OutputFormat::Json).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["source"], "Torznab");
+    assert_eq!(json["total_results"], 250);
+    assert_eq!(json["items"][0]["seeders"], 1203);
+    assert_eq!(json["items"][0]["category"], "TVAnime");
+    assert_eq!(json["items"][0]["trusted"], false);
+
+    let (src, search, res) = cli::search(&args, &config).await.unwrap();
+    let csv = cli::format_results(&src, &search, &res, &config.sources, OutputFormat::Csv).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().starts_with("id,title,category,"));
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("torznab-0123456789abcdef0123456789abcdef01234567,[SubsPlease] Frieren - 01 (1080p) [F02B9CEE].mkv,TVAnime,5070,"));
+
+    let (src, search, res) = cli::search(&args, &config).await.unwrap();
+    let table =
+        cli::format_results(&src, &search, &res, &config.sources, OutputFormat::Table).unwrap();
+    assert!(table.lines().next().unwrap().starts_with("Cat  Name"));
+    assert_eq!(table.lines().count(), 3);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /api?t=search&extended=1&q=frieren&"));
+}
+
+#[tokio::test]
+async fn test_download() {
+    let out = std::env::temp_dir().join(format!("nyaa-cli-{}.txt", std::process::id()));
+    // Magnets and post urls aren't from the default source, so this rule never matches
+    let config: Config = toml::from_str(&format!(
+        "download_client = \"RunCommand\"\n[client.command]\ncmd = \"echo '{{title}} {{torrent}} {{magnet}}' >> {}\"\n[[routing]]\nsource = \"Nyaa\"\nclient = \"missing\"",
+        out.to_string_lossy()
+    ))
+    .unwrap();
+
+    let page = r#"<html><head><meta property="og:title" content="[Group] Show - 01 (1080p)"></head>
+        <body><a href="/download/1700000.torrent">Download</a>
+        <a href="magnet:?xt=urn:btih:0123456789abcdef&amp;dn=Show">Magnet</a></body></html>"#;
+    let (addr, handle) = serve(vec![(200, page.to_owned()), (404, "Not Found".to_owned())]);
+
+    let args = DownloadArgs {
+        query: QueryArgs {
+            query: Some(format!("{}/view/1700000", addr)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (client, src, items, res) = cli::download(&args, &config).await.unwrap();
+    assert!(client == Profile::Client(Client::Cmd));
+    assert!(src.is_none());
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids, vec!["0123456789abcdef".to_owned()]);
+    assert_eq!(items[0].title, "[Group] Show - 01 (1080p)");
+    assert_eq!(items[0].file_name, "1700000.torrent");
+    assert_eq!(
+        items[0].torrent_link,
+        format!("{}/download/1700000.torrent", addr)
+    );
+
+    // A missing post isn't parsed
+    let args = DownloadArgs {
+        query: QueryArgs {
+            query: Some(format!("{}/view/404", addr)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = cli::download(&args, &config).await.err().unwrap();
+    assert!(err.to_string().ends_with("Invalid response code: 404"));
+    handle.join().unwrap();
+
+    let args = DownloadArgs {
+        query: QueryArgs {
+            query: Some("magnet:?xt=urn:btih:fedcba9876543210&dn=Other%20Show".to_owned()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (_, _, items, res) = cli::download(&args, &config).await.unwrap();
+    assert_eq!(res.success_ids, vec!["fedcba9876543210".to_owned()]);
+    assert_eq!(items[0].title, "Other Show");
+
+    let written = std::fs::read_to_string(&out).unwrap();
+    let _ = std::fs::remove_file(&out);
+    let mut lines = written.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        format!(
+            "[Group] Show - 01 (1080p) {}/download/1700000.torrent magnet:?xt=urn:btih:0123456789abcdef&dn=Show",
+            addr
+        )
+    );
+    assert_eq!(
+        lines.next().unwrap(),
+        "Other Show  magnet:?xt=urn:btih:fedcba9876543210&dn=Other%20Show"
+    );
+}
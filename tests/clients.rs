@@ -0,0 +1,727 @@
+use nyaa::{
+    client::{
+        aria2::Aria2Config,
+        deluge::DelugeConfig,
+        qbit::QbitConfig,
+        rqbit::RqbitConfig,
+        rtorrent::RtorrentConfig,
+        transmission::TransmissionConfig,
+        watch_folder::{WatchFolderConfig, WatchFolderFormat},
+        Client, ClientConfig, Profile, TorrentAction, TorrentState,
+    },
+    config::Config,
+    source::{Item, SourceConfig, Sources},
+};
+
+use crate::common::{item, serve};
+
+#[allow(dead_code)]
+mod common;
+
+fn rpc(result: &str) -> (u16, String) {
+    (
+        200,
+        format!(r#"{{"id":1,"result":{},"error":null}}"#, result),
+    )
+}
+
+#[tokio::test]
+async fn test_deluge() {
+    let (addr, handle) = serve(vec![
+        rpc("true"),
+        rpc("false"),
+        rpc(r#"[["c0ffee","127.0.0.1",58846,"Online"]]"#),
+        rpc("null"),
+        rpc(r#""abcd""#),
+        (
+            200,
+            r#"{"id":1,"result":null,"error":{"message":"Label already exists","code":4}}"#
+                .to_owned(),
+        ),
+        rpc("null"),
+        rpc(r#""ef01""#),
+        rpc("null"),
+        rpc("null"),
+    ]);
+    let conf = ClientConfig {
+        deluge: Some(DelugeConfig {
+            base_url: addr,
+            password: Some("secret".to_owned()),
+            label: Some("anime".to_owned()),
+            paused: Some(true),
+            move_completed_path: Some("/done".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let items = vec![item("first", "abcd"), item("second", "ef01")];
+    let res = Client::Deluge
+        .batch_download(items, conf, reqwest::Client::new(), reqwest::Client::new())
+        .await;
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids, ["first", "second"]);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("POST /json "));
+    assert!(requests[0].contains(r#""method":"auth.login","params":["secret"]"#));
+    // Connects to the first daemon when the WebUI isn't connected yet
+    assert!(requests[3].contains(r#""method":"web.connect","params":["c0ffee"]"#));
+    assert!(requests[4].contains(r#""method":"core.add_torrent_magnet""#));
+    assert!(requests[4].contains(r#""add_paused":true"#));
+    assert!(requests[4].contains(r#""move_completed_path":"/done""#));
+    assert!(requests[6].contains(r#""method":"label.set_torrent","params":["abcd","anime"]"#));
+}
+
+#[tokio::test]
+async fn test_deluge_login() {
+    let (addr, handle) = serve(vec![rpc("false")]);
+    let conf = ClientConfig {
+        deluge: Some(DelugeConfig {
+            base_url: addr,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let res = Client::Deluge
+        .download(
+            item("first", "abcd"),
+            conf,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.success_ids.is_empty());
+    assert!(res.errors[0].to_string().contains("Incorrect password"));
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].contains(r#""params":["deluge"]"#));
+}
+
+#[tokio::test]
+async fn test_aria2() {
+    let (addr, handle) = serve(vec![rpc(
+        r#"[["2089b05ecca3d829"],{"code":1,"message":"No URI to download."}]"#,
+    )]);
+    let conf = ClientConfig {
+        aria2: Some(Aria2Config {
+            base_url: format!("{}/jsonrpc", addr),
+            secret: Some("hunter2".to_owned()),
+            dir: Some("/downloads".to_owned()),
+            max_connection_per_server: Some(4),
+            seed_ratio: Some(1.5),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let items = vec![item("first", "abcd"), item("second", "ef01")];
+    let res = Client::Aria2
+        .batch_download(items, conf, reqwest::Client::new(), reqwest::Client::new())
+        .await;
+    assert_eq!(res.success_ids, ["first"]);
+    assert_eq!(res.errors.len(), 1);
+    assert!(res.errors[0].to_string().contains("No URI to download."));
+
+    // Both items are sent in a single request
+    let requests = handle.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("POST /jsonrpc "));
+    assert!(requests[0].contains(r#""method":"system.multicall""#));
+    assert!(requests[0].contains(
+        r#""params":["token:hunter2",["magnet:?xt=urn:btih:abcd&dn=first"],{"dir":"/downloads","max-connection-per-server":"4","seed-ratio":"1.5"}]"#
+    ));
+    assert!(requests[0].contains("urn:btih:ef01"));
+}
+
+fn xmlrpc(value: &str) -> (u16, String) {
+    (
+        200,
+        format!(
+            "<?xml version=\"1.0\"?><methodResponse><params><param><value>{}</value></param></params></methodResponse>",
+            value
+        ),
+    )
+}
+
+#[tokio::test]
+async fn test_rtorrent() {
+    let (addr, handle) = serve(vec![xmlrpc("<i8>0</i8>")]);
+    let conf = ClientConfig {
+        rtorrent: Some(RtorrentConfig {
+            base_url: format!("{}/RPC2", addr),
+            directory: Some("/data/anime".to_owned()),
+            label: Some("Tom & Jerry".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let res = Client::Rtorrent
+        .download(
+            item("first", "abcd"),
+            conf,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids, ["first"]);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("POST /RPC2 "));
+    assert!(requests[0].contains("<methodName>load.start</methodName>"));
+    assert!(requests[0].contains(
+        "<param><value><string></string></value></param><param><value><string>magnet:?xt=urn:btih:abcd&amp;dn=first</string></value></param>"
+    ));
+    assert!(requests[0].contains("<string>d.directory.set=&quot;/data/anime&quot;</string>"));
+    assert!(requests[0].contains("<string>d.custom1.set=&quot;Tom &amp; Jerry&quot;</string>"));
+}
+
+#[tokio::test]
+async fn test_rutorrent() {
+    let fault = "<?xml version=\"1.0\"?><methodResponse><fault><value><struct><member><name>faultCode</name><value><i4>-503</i4></value></member><member><name>faultString</name><value><string>Could not create download</string></value></member></struct></value></fault></methodResponse>";
+    let (addr, handle) = serve(vec![xmlrpc("<i8>0</i8>"), (200, fault.to_owned())]);
+    let conf = ClientConfig {
+        rtorrent: Some(RtorrentConfig {
+            base_url: format!("{}/rutorrent/plugins/httprpc/", addr),
+            username: Some("user".to_owned()),
+            password: Some("pass".to_owned()),
+            use_magnet: Some(false),
+            paused: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let res = Client::Rtorrent
+        .download(
+            item("first", "abcd"),
+            conf.clone(),
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors.is_empty());
+
+    let res = Client::Rtorrent
+        .download(
+            item("second", "ef01"),
+            conf,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors[0]
+        .to_string()
+        .ends_with("Could not create download"));
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("POST /rutorrent/plugins/httprpc/action.php "));
+    assert!(requests[0].contains("authorization: Basic dXNlcjpwYXNz"));
+    assert!(requests[0].contains("<methodName>load.normal</methodName>"));
+    assert!(requests[0].contains("<string>https://example.com/first.torrent</string>"));
+}
+
+#[tokio::test]
+async fn test_webhook() {
+    let (addr, handle) = serve(vec![(200, "".to_owned()), (200, "".to_owned())]);
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [client.webhook]
+        url = "{}/hook?link={{magnet}}"
+        headers = {{ "X-Token" = "secret", "X-Title" = "{{title}}" }}
+
+        [client.webhook.body]
+        title = "{{title}}"
+        hash = "{{hash}}"
+        tags = ["nyaa", "{{id}}"]
+        "#,
+        addr
+    ))
+    .unwrap();
+    let mut first = item("first", "ABCD");
+    first.title = "Say \"hi\"".to_owned();
+    let res = Client::Webhook
+        .batch_download(
+            vec![first, item("second", "ef01")],
+            config.client,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids.len(), 2);
+
+    let requests = handle.join().unwrap();
+    let first = requests.iter().find(|r| r.contains("first")).unwrap();
+    assert!(first
+        .starts_with("POST /hook?link=magnet%3A%3Fxt%3Durn%3Abtih%3AABCD%26dn%3Dfirst HTTP/1.1"));
+    assert!(first.contains("x-token: secret"));
+    assert!(first.contains(r#"{"hash":"abcd","tags":["nyaa","first"],"title":"Say \"hi\""}"#));
+}
+
+#[tokio::test]
+async fn test_webhook_form() {
+    let (addr, handle) = serve(vec![(200, "".to_owned())]);
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [client.webhook]
+        url = "{}/hook"
+        method = "put"
+        format = "Form"
+        success_codes = [202]
+        body = {{ torrent = "{{torrent}}", paused = true }}
+        "#,
+        addr
+    ))
+    .unwrap();
+    let res = Client::Webhook
+        .download(
+            item("first", "abcd"),
+            config.client,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors[0]
+        .to_string()
+        .ends_with("Webhook returned status code 200"));
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("PUT /hook "));
+    assert!(requests[0].ends_with("paused=true&torrent=https%3A%2F%2Fexample.com%2Ffirst.torrent"));
+}
+
+#[tokio::test]
+async fn test_watch_folder() {
+    let (addr, handle) = serve(vec![(200, "d8:announce0:e".to_owned())]);
+    let dir = std::env::temp_dir().join(format!("nyaa-watch-folder-{}", std::process::id()));
+    let anime = Sources::Nyaa
+        .info(&SourceConfig::default())
+        .entry_from_cfg("AnimeEnglishTranslated");
+    let mut first = item("first", "ABCD");
+    first.torrent_link = format!("{}/first.torrent", addr);
+    first.file_name = "first.torrent".to_owned();
+    first.category = anime.id;
+    first.icon = anime.icon;
+
+    let mut conf = ClientConfig {
+        watch_folder: Some(WatchFolderConfig {
+            watch_dir: dir.to_string_lossy().to_string(),
+            categories: [("animeenglishtranslated".to_owned(), "anime".to_owned())].into(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let res = Client::WatchFolder
+        .download(
+            first.clone(),
+            conf.clone(),
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors.is_empty());
+    let torrent = dir.join("anime").join("first.torrent");
+    assert_eq!(std::fs::read_to_string(&torrent).unwrap(), "d8:announce0:e");
+    // The temporary file is renamed into place
+    assert!(!dir.join("anime").join(".first.torrent.part").exists());
+    assert!(handle.join().unwrap()[0].starts_with("GET /first.torrent "));
+
+    // Magnet files are named after the hash without a torrent file name
+    if let Some(wf) = conf.watch_folder.as_mut() {
+        wf.format = WatchFolderFormat::Magnet;
+    }
+    let res = Client::WatchFolder
+        .batch_download(
+            vec![first, item("second", "EF01")],
+            conf,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids.len(), 2);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("anime").join("first.magnet")).unwrap(),
+        "magnet:?xt=urn:btih:ABCD&dn=first\n"
+    );
+    assert!(dir.join("ef01.magnet").exists());
+
+    // Existing files are kept unless overwriting is enabled
+    let conf = ClientConfig {
+        watch_folder: Some(WatchFolderConfig {
+            watch_dir: dir.to_string_lossy().to_string(),
+            format: WatchFolderFormat::Magnet,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let res = Client::WatchFolder
+        .download(
+            item("second", "EF01"),
+            conf,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors[0].to_string().contains("already exists"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_profiles() {
+    let (home, home_handle) = serve(vec![]);
+    let (seedbox, seedbox_handle) = serve(vec![(200, "".to_owned())]);
+    let config: Config = toml::from_str(&format!(
+        r#"
+        download_client = "seedbox"
+
+        [client.webhook]
+        url = "{}/home"
+
+        [client.profiles.seedbox]
+        client = "Webhook"
+        url = "{}/seedbox"
+        method = "PUT"
+        "#,
+        home, seedbox
+    ))
+    .unwrap();
+    assert!(config.download_client == Profile::Named("seedbox".to_owned()));
+    let names = config
+        .client
+        .all_profiles()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+    assert_eq!(names.last().unwrap(), "seedbox");
+    assert!(config.download_client.client(&config.client) == Some(Client::Webhook));
+    assert!(config.validate().is_ok());
+
+    // A typo in the client is caught when the config is loaded
+    let mut typo = config.clone();
+    typo.download_client = Profile::Named("qbittorrent".to_owned());
+    assert_eq!(
+        typo.validate().unwrap_err(),
+        "Unknown client \"qbittorrent\", it isn't declared in [client.profiles]"
+    );
+    let typo: Config = toml::from_str("[[watch]]\nquery = \"show\"\nclient = \"home\"").unwrap();
+    assert!(typo.validate().is_err());
+
+    // The profile's options replace the client's own table
+    let res = config
+        .download_client
+        .download(
+            item("first", "abcd"),
+            config.client.clone(),
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert!(res.errors.is_empty());
+    assert!(seedbox_handle.join().unwrap()[0].starts_with("PUT /seedbox "));
+    assert!(home_handle.join().unwrap().is_empty());
+
+    let res = Profile::Named("home".to_owned())
+        .download(
+            item("first", "abcd"),
+            config.client,
+            reqwest::Client::new(),
+            reqwest::Client::new(),
+        )
+        .await;
+    assert_eq!(
+        res.errors[0].to_string(),
+        "No client profile named \"home\""
+    );
+}
+
+#[tokio::test]
+async fn test_qbit_status() {
+    let torrents = r#"[{"hash":"ABCD","name":"first","progress":0.5,"size":2048,"dlspeed":1024,"upspeed":0,"eta":60,"state":"downloading"},{"hash":"ef01","name":"second","progress":1,"size":4096,"dlspeed":0,"upspeed":512,"eta":8640000,"state":"stalledUP"}]"#;
+    let (addr, handle) = serve(vec![(200, torrents.to_owned()), (200, "".to_owned())]);
+    let conf = ClientConfig {
+        qbit: Some(QbitConfig {
+            base_url: addr,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let hashes = vec!["abcd".to_owned(), "ef01".to_owned()];
+    let res = Client::Qbit
+        .list_torrents(hashes, conf, reqwest::Client::new())
+        .await
+        .unwrap();
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].hash, "abcd");
+    assert_eq!(res[0].progress, 0.5);
+    assert_eq!(res[0].download_speed, 1024);
+    assert_eq!(res[0].eta, Some(60));
+    assert_eq!(res[0].state, TorrentState::Downloading);
+    assert_eq!(res[1].eta, None);
+    assert_eq!(res[1].state, TorrentState::Seeding);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /api/v2/torrents/info?hashes=abcd|ef01 "));
+    assert!(requests[1].starts_with("GET /api/v2/auth/logout "));
+}
+
+#[tokio::test]
+async fn test_transmission_status() {
+    let torrents = r#"{"arguments":{"torrents":[{"hashString":"abcd","name":"first","percentDone":0.25,"totalSize":2048,"rateDownload":1024,"rateUpload":0,"eta":-1,"status":4,"error":0},{"hashString":"ef01","name":"second","percentDone":0.5,"totalSize":4096,"rateDownload":0,"rateUpload":0,"eta":-1,"status":0,"error":3}]},"result":"success"}"#;
+    let (addr, handle) = serve(vec![(200, torrents.to_owned())]);
+    let conf = ClientConfig {
+        transmission: Some(TransmissionConfig {
+            base_url: format!("{}/transmission/rpc", addr),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let hashes = vec!["abcd".to_owned(), "ef01".to_owned()];
+    let res = Client::Transmission
+        .list_torrents(hashes, conf, reqwest::Client::new())
+        .await
+        .unwrap();
+    assert_eq!(res[0].progress, 0.25);
+    assert_eq!(res[0].eta, None);
+    assert_eq!(res[0].state, TorrentState::Downloading);
+    assert_eq!(res[1].state, TorrentState::Error);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].contains(r#""method":"torrent-get""#));
+    assert!(requests[0].contains(r#""ids":["abcd","ef01"]"#));
+}
+
+#[tokio::test]
+async fn test_rqbit_status() {
+    let torrents = r#"{"torrents":[{"id":0,"info_hash":"1234","name":"other"},{"id":1,"info_hash":"abcd","name":"first"}]}"#;
+    let stats = r#"{"state":"live","progress_bytes":512,"total_bytes":2048,"finished":false,"error":null,"live":{"download_speed":{"mbps":1.0,"human_readable":"1.00 MiB/s"},"upload_speed":{"mbps":0.0,"human_readable":"0.00 MiB/s"},"time_remaining":{"duration":{"secs":30,"nanos":0},"human_readable":"30s"}}}"#;
+    let (addr, handle) = serve(vec![(200, torrents.to_owned()), (200, stats.to_owned())]);
+    let conf = ClientConfig {
+        rqbit: Some(RqbitConfig {
+            base_url: addr,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let res = Client::Rqbit
+        .list_torrents(vec!["abcd".to_owned()], conf, reqwest::Client::new())
+        .await
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].name, "first");
+    assert_eq!(res[0].progress, 0.25);
+    assert_eq!(res[0].download_speed, 1024 * 1024);
+    assert_eq!(res[0].eta, Some(30));
+    assert_eq!(res[0].state, TorrentState::Downloading);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /torrents "));
+    assert!(requests[1].starts_with("GET /torrents/1/stats/v1 "));
+
+    // Clients that only send torrents can't list them
+    let res = Client::Webhook
+        .list_torrents(vec![], ClientConfig::default(), reqwest::Client::new())
+        .await;
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+async fn test_qbit_manage() {
+    let (addr, handle) = serve(vec![
+        (404, "".to_owned()),
+        (200, "".to_owned()),
+        (200, "".to_owned()),
+        (409, "".to_owned()),
+        (200, "".to_owned()),
+        (200, "".to_owned()),
+    ]);
+    let conf = ClientConfig {
+        qbit: Some(QbitConfig {
+            base_url: addr,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    // qBittorrent 5 only knows "stop"
+    Client::Qbit
+        .manage(
+            vec!["abcd".to_owned()],
+            TorrentAction::Pause,
+            conf.clone(),
+            reqwest::Client::new(),
+        )
+        .await
+        .unwrap();
+    Client::Qbit
+        .manage(
+            vec!["abcd".to_owned()],
+            TorrentAction::SetCategory("Anime".to_owned()),
+            conf,
+            reqwest::Client::new(),
+        )
+        .await
+        .unwrap();
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("POST /api/v2/torrents/pause "));
+    assert!(requests[1].starts_with("POST /api/v2/torrents/stop "));
+    assert!(requests[1].ends_with("hashes=abcd"));
+    assert!(requests[3].starts_with("POST /api/v2/torrents/createCategory "));
+    assert!(requests[4].starts_with("POST /api/v2/torrents/setCategory "));
+    assert!(requests[4].ends_with("hashes=abcd&category=Anime"));
+}
+
+#[tokio::test]
+async fn test_transmission_manage() {
+    let ok = r#"{"arguments":{},"result":"success"}"#;
+    let (addr, handle) = serve(vec![(200, ok.to_owned()), (200, ok.to_owned())]);
+    let conf = ClientConfig {
+        transmission: Some(TransmissionConfig {
+            base_url: format!("{}/transmission/rpc", addr),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    for action in [TorrentAction::RemoveData, TorrentAction::Recheck] {
+        Client::Transmission
+            .manage(
+                vec!["abcd".to_owned()],
+                action,
+                conf.clone(),
+                reqwest::Client::new(),
+            )
+            .await
+            .unwrap();
+    }
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].contains(r#""method":"torrent-remove""#));
+    assert!(requests[0].contains(r#""delete-local-data":true"#));
+    assert!(requests[1].contains(r#""method":"torrent-verify""#));
+}
+
+#[tokio::test]
+async fn test_rqbit_manage() {
+    let torrents = r#"{"torrents":[{"id":3,"info_hash":"abcd","name":"first"}]}"#;
+    let (addr, handle) = serve(vec![(200, torrents.to_owned()), (200, "".to_owned())]);
+    let conf = ClientConfig {
+        rqbit: Some(RqbitConfig {
+            base_url: addr,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    Client::Rqbit
+        .manage(
+            vec!["abcd".to_owned()],
+            TorrentAction::Remove,
+            conf.clone(),
+            reqwest::Client::new(),
+        )
+        .await
+        .unwrap();
+    let res = Client::Rqbit
+        .manage(
+            vec!["abcd".to_owned()],
+            TorrentAction::Recheck,
+            conf,
+            reqwest::Client::new(),
+        )
+        .await;
+    assert_eq!(res.unwrap_err().to_string(), "rqbit can't recheck torrents");
+
+    let requests = handle.join().unwrap();
+    assert!(requests[1].starts_with("POST /torrents/3/forget "));
+}
+
+#[tokio::test]
+async fn test_qbit_upload() {
+    let (addr, handle) = serve(vec![
+        (200, "d4:infod4:name5:firstee".to_owned()),
+        (404, "".to_owned()),
+        (200, "Ok.".to_owned()),
+        (200, "".to_owned()),
+    ]);
+    let conf = ClientConfig {
+        qbit: Some(QbitConfig {
+            base_url: addr.clone(),
+            use_magnet: Some(false),
+            category: Some("Anime".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let items = ["first", "second"]
+        .into_iter()
+        .map(|id| Item {
+            torrent_link: format!("{}/{}.torrent", addr, id),
+            file_name: format!("{}.torrent", id),
+            ..item(id, "abcd")
+        })
+        .collect();
+    // The .torrent files are fetched with the source's client, which may use a proxy
+    let source_client = reqwest::Client::builder()
+        .user_agent("source")
+        .build()
+        .unwrap();
+    let res = Client::Qbit
+        .batch_download(items, conf, reqwest::Client::new(), source_client)
+        .await;
+    assert_eq!(res.success_ids, ["first"]);
+    assert!(res.errors[0]
+        .to_string()
+        .contains("Invalid response code: 404"));
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /first.torrent "));
+    assert!(requests[0].contains("user-agent: source"));
+    assert!(requests[2].starts_with("POST /api/v2/torrents/add "));
+    assert!(!requests[2].contains("user-agent: source"));
+    assert!(requests[2].contains("content-type: multipart/form-data"));
+    assert!(requests[2].contains(r#"name="torrents"; filename="first.torrent""#));
+    assert!(requests[2].contains("d4:infod4:name5:firstee"));
+    assert!(requests[2].contains("name=\"category\"\r\n\r\nAnime"));
+    assert!(!requests[2].contains("name=\"urls\""));
+}
+
+#[tokio::test]
+async fn test_qbit_fails() {
+    let (addr, handle) = serve(vec![
+        (200, "d4:infod4:name5:firstee".to_owned()),
+        (404, "".to_owned()),
+        (200, "Fails.".to_owned()),
+        (200, "".to_owned()),
+    ]);
+    let conf = ClientConfig {
+        qbit: Some(QbitConfig {
+            base_url: addr.clone(),
+            use_magnet: Some(false),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let items = ["first", "second"]
+        .into_iter()
+        .map(|id| Item {
+            torrent_link: format!("{}/{}.torrent", addr, id),
+            ..item(id, "abcd")
+        })
+        .collect();
+    let res = Client::Qbit
+        .batch_download(items, conf, reqwest::Client::new(), reqwest::Client::new())
+        .await;
+
+    // The torrent that couldn't be fetched is still reported
+    assert!(res.success_ids.is_empty());
+    assert_eq!(res.errors.len(), 2);
+    assert!(res.errors[0]
+        .to_string()
+        .contains("Invalid response code: 404"));
+    assert_eq!(
+        res.errors[1].to_string(),
+        "qBittorrent failed to add the torrents"
+    );
+
+    let requests = handle.join().unwrap();
+    assert!(requests[3].starts_with("GET /api/v2/auth/logout "));
+}
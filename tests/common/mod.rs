@@ -1,13 +1,20 @@
-use std::{error::Error, path::PathBuf};
+use std::{
+    error::Error,
+    io::{BufRead as _, BufReader, Read as _, Write as _},
+    net::TcpListener,
+    path::PathBuf,
+    thread::JoinHandle,
+};
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use nyaa::{
     app::App,
-    client::{Client, ClientConfig, DownloadResult},
+    client::{ClientConfig, DownloadResult, Profile, StatusResult, TorrentAction},
     config::{Config, ConfigManager},
     results::Results,
-    source::{Item, SourceResults},
-    sync::{EventSync, ReloadType},
+    routing::RoutingRule,
+    source::{Item, SourceConfig, SourceResults, Sources},
+    sync::{DetailsResult, EventSync, ReloadType, TorrentResult},
 };
 use ratatui::{
     backend::{Backend as _, TestBackend},
@@ -24,12 +31,14 @@ pub struct TestSync {
 
 pub struct TestConfig {
     config_path: PathBuf,
+    config: Config,
 }
 
 impl TestConfig {
-    fn new() -> Self {
+    fn new(config: Config) -> Self {
         Self {
             config_path: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/config"),
+            config,
         }
     }
 }
@@ -123,6 +132,15 @@ pub async fn run_app<S: EventSync + Clone>(
     sync: S,
     w: u16,
     h: u16,
+) -> Result<Terminal<TestBackend>, Box<dyn Error>> {
+    run_app_with_config(sync, w, h, Config::default()).await
+}
+
+pub async fn run_app_with_config<S: EventSync + Clone>(
+    sync: S,
+    w: u16,
+    h: u16,
+    config: Config,
 ) -> Result<Terminal<TestBackend>, Box<dyn Error>> {
     let mut backend = TestBackend::new(w, h);
     let _ = backend.clear();
@@ -130,13 +148,71 @@ pub async fn run_app<S: EventSync + Clone>(
     let _ = terminal.clear();
 
     let mut app = App::default();
-    let config = TestConfig::new();
+    let config = TestConfig::new(config);
 
     app.run_app::<_, S, TestConfig, true>(&mut terminal, sync, config)
         .await?;
     Ok(terminal)
 }
 
+/// An item titled `id`, with a magnet link for `hash` unless it's empty
+pub fn item(id: &str, hash: &str) -> Item {
+    Item {
+        id: id.to_owned(),
+        title: id.to_owned(),
+        magnet_link: match hash.is_empty() {
+            true => "".to_owned(),
+            false => format!("magnet:?xt=urn:btih:{}&dn={}", hash, id),
+        },
+        torrent_link: format!("https://example.com/{}.torrent", id),
+        post_link: format!("https://example.com/view/{}", id),
+        ..Default::default()
+    }
+}
+
+/// Serve each of `responses` (status, body) to one request on a local port,
+/// in order. Joining the handle returns the raw requests that were received.
+pub fn serve(responses: Vec<(u16, String)>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for (status, body) in responses.into_iter() {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut len = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    request.push_str(&line);
+                    break;
+                }
+                if let Some((k, v)) = line.split_once(':') {
+                    if k.eq_ignore_ascii_case("content-length") {
+                        len = v.trim().parse().unwrap_or(0);
+                    }
+                }
+                request.push_str(&line);
+            }
+            let mut content = vec![0; len];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8_lossy(&content));
+            requests.push(request);
+
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+        }
+        requests
+    });
+    (addr, handle)
+}
+
 pub fn reset_buffer(terminal: &Terminal<TestBackend>) -> Buffer {
     let area = terminal.size().unwrap();
     let mut buf = terminal.backend().buffer().clone();
@@ -190,7 +266,58 @@ impl EventSync for TestSync {
         _items: Vec<Item>,
         _config: ClientConfig,
         _rq_client: reqwest::Client,
-        _client: Client,
+        _client: Profile,
+        _src: Sources,
+        _routing: Vec<RoutingRule>,
+        _source_client: reqwest::Client,
+        _sources: SourceConfig,
+    ) {
+    }
+
+    async fn load_info(
+        self,
+        _tx_info: Sender<Result<(), Box<dyn Error + Send + Sync>>>,
+        _src: Sources,
+        _config: SourceConfig,
+    ) {
+    }
+
+    async fn load_details(
+        self,
+        _tx_details: Sender<DetailsResult>,
+        _src: Sources,
+        _client: reqwest::Client,
+        _item: Item,
+        _config: SourceConfig,
+    ) {
+    }
+
+    async fn load_torrent(
+        self,
+        _tx_torrent: Sender<TorrentResult>,
+        _client: reqwest::Client,
+        _item: Item,
+    ) {
+    }
+
+    async fn load_status(
+        self,
+        _tx_status: Sender<StatusResult>,
+        _hashes: Vec<(Profile, Vec<String>)>,
+        _config: ClientConfig,
+        _rq_client: reqwest::Client,
+    ) {
+    }
+
+    async fn manage_torrent(
+        self,
+        _tx_manage: Sender<Result<String, Box<dyn Error + Send + Sync>>>,
+        _hash: String,
+        _name: String,
+        _action: TorrentAction,
+        _config: ClientConfig,
+        _rq_client: reqwest::Client,
+        _client: Profile,
     ) {
     }
 
@@ -199,7 +326,7 @@ impl EventSync for TestSync {
 
 impl ConfigManager for TestConfig {
     fn load(&self) -> Result<Config, Box<dyn Error>> {
-        Ok(Config::default())
+        Ok(self.config.clone())
     }
 
     fn store(&self, _cfg: &Config) -> Result<(), Box<dyn Error>> {
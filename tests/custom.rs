@@ -0,0 +1,184 @@
+use nyaa::{
+    cli::{self, QueryArgs},
+    config::Config,
+    source::{
+        custom::{parse_custom, CustomConfig},
+        Sources,
+    },
+};
+use reqwest::Url;
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+fn config(base_url: &str) -> Config {
+    toml::from_str(&format!(
+        r#"
+        default_source = "private"
+
+        [source.custom.private]
+        base_url = "{}"
+
+        [[source.custom.private.categories]]
+        name = "Anime"
+        entries = [
+            {{ name = "All Anime", value = "1_0", label = "Ani", color = "Gray" }},
+            {{ name = "English Translated", value = "1_2", label = "Sub", color = "LightMagenta" }},
+        ]
+
+        [[source.custom.private.categories]]
+        name = "Literature"
+        entries = [{{ name = "Books", value = "3_1", label = "Lit", color = "Green" }}]
+        "#,
+        base_url
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_parse_custom() {
+    let config = config("https://tracker.example/");
+    let cfg = config.sources.custom.get("private").unwrap();
+    let html = include_str!("fixtures/custom.html");
+    let url = Url::parse("https://tracker.example/?q=show").unwrap();
+    let res = parse_custom("private", cfg, html, &url, None, 1).unwrap();
+
+    assert_eq!(res.items.len(), 2);
+    assert_eq!(res.last_page, 5);
+
+    let first = &res.items[0];
+    assert_eq!(first.id, "private-1001");
+    assert_eq!(first.title, "[Group] Show - 01 (1080p) [ABCD1234].mkv");
+    assert_eq!(first.post_link, "https://tracker.example/view/1001");
+    assert_eq!(
+        first.torrent_link,
+        "https://tracker.example/download/1001.torrent"
+    );
+    assert!(first
+        .magnet_link
+        .starts_with("magnet:?xt=urn:btih:0123456789ABCDEF"));
+    assert_eq!(
+        first.extra.get("hash").map(String::as_str),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(first.category, 2);
+    assert_eq!(first.icon.label, "Sub");
+    assert_eq!(first.size, "1.4 GB");
+    assert_eq!(first.bytes, 1503238553);
+    assert_eq!(first.timestamp, 1697241600);
+    assert_eq!(first.seeders, 1204);
+    assert_eq!(first.leechers, 31);
+    assert_eq!(first.downloads, 8452);
+
+    let second = &res.items[1];
+    assert_eq!(second.category, 3);
+    assert_eq!(second.size, "24.5 MB");
+}
+
+#[test]
+fn test_parse_custom_selectors() {
+    let html = r#"
+        <div class="result">
+            <h3><a href="/t/55">Linux ISO</a></h3>
+            <span class="size">700 MB</span>
+            <span class="added">1697241600</span>
+            <span class="peers">Seeders: 12 / Leechers: 4</span>
+        </div>
+        <div class="result"><span class="size">Missing title</span></div>
+    "#;
+    let cfg: CustomConfig = toml::from_str(
+        r#"
+        base_url = "https://internal.example/"
+        [selectors]
+        rows = "div.result"
+        title = "h3 > a"
+        post_link = { selector = "h3 > a", attr = "href" }
+        torrent_link = ""
+        magnet_link = ""
+        size = "span.size"
+        date = "span.added"
+        seeders = { selector = "span.peers", pattern = "Seeders: (\\d+)" }
+        leechers = { selector = "span.peers", pattern = "Leechers: (\\d+)" }
+        downloads = ""
+        category = ""
+        pagination = ""
+        "#,
+    )
+    .unwrap();
+    let url = Url::parse("https://internal.example/search").unwrap();
+    let res = parse_custom("internal", &cfg, html, &url, None, 2).unwrap();
+
+    // Rows without a title are skipped
+    assert_eq!(res.items.len(), 1);
+    assert_eq!(res.last_page, 2);
+    let item = &res.items[0];
+    assert_eq!(item.id, "internal-55");
+    assert_eq!(item.post_link, "https://internal.example/t/55");
+    assert!(item.torrent_link.is_empty());
+    assert_eq!(item.timestamp, 1697241600);
+    assert_eq!(item.seeders, 12);
+    assert_eq!(item.leechers, 4);
+    assert_eq!(item.category, 0);
+
+    let bad: CustomConfig = toml::from_str("[selectors]\ntitle = \"td:::\"").unwrap();
+    let err = parse_custom("bad", &bad, html, &url, None, 1)
+        .err()
+        .unwrap();
+    assert!(err.to_string().starts_with("Invalid selector \"td:::\""));
+}
+
+#[tokio::test]
+async fn test_custom_search() {
+    let html = include_str!("fixtures/custom.html").to_owned();
+    let (addr, server) = serve(vec![(200, html.clone()), (200, html)]);
+
+    let mut config = config(&addr);
+    assert!(config.source == Sources::Custom("private".to_owned()));
+    let mut args = QueryArgs {
+        query: Some("show name".to_owned()),
+        source: Some("private".to_owned()),
+        sort: Some("Seeders".to_owned()),
+        ..Default::default()
+    };
+
+    let (src, _, res) = cli::search(&args, &config).await.unwrap();
+    assert!(src == Sources::Custom("private".to_owned()));
+    assert_eq!(res.items.len(), 2);
+
+    config.sources.custom.get_mut("private").unwrap().search_url =
+        "search/{query}/{category}/{sort}-{dir}/{page}".to_owned();
+    args.category = Some("Books".to_owned());
+    args.page = Some(3);
+    cli::search(&args, &config).await.unwrap();
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /?q=show%20name&c=0_0&f=0&p=1&s=seeders&o=desc "));
+    assert!(requests[1].starts_with("GET /search/show%20name/3_1/seeders-desc/3 "));
+
+    // Custom sources are listed after the built-in ones
+    assert!(
+        cli::find_source("private", &config.sources).unwrap()
+            == Sources::Custom("private".to_owned())
+    );
+    assert!(cli::find_source("public", &config.sources).is_err());
+}
+
+#[test]
+fn test_unknown_sources() {
+    let mut config = config("https://tracker.example/");
+    assert!(config.validate().is_ok());
+
+    // A typo is parsed as a custom source, but isn't declared
+    config.source = Sources::try_from("Nyaaa".to_owned()).unwrap();
+    let err = config.validate().unwrap_err();
+    assert!(err.contains("Unknown source \"Nyaaa\""));
+
+    let config: Config = toml::from_str("[[routing]]\nsource = \"plugin:tracker\"").unwrap();
+    let err = config.validate().unwrap_err();
+    assert!(err.contains("Unknown plugin \"tracker\""));
+
+    assert!(Sources::try_from(String::new()).is_err());
+    assert!(toml::from_str::<Config>("default_source = \"plugin:\"").is_err());
+}
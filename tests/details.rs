@@ -0,0 +1,72 @@
+#[allow(dead_code)]
+mod common;
+
+use nyaa::source::{
+    nyaa_html::{fetch_details, parse_details},
+    ItemFile,
+};
+
+use crate::common::serve;
+
+#[test]
+fn test_parse_details() {
+    let html = include_str!("fixtures/nyaa_view.html");
+    let details = parse_details(html).unwrap();
+
+    assert_eq!(
+        details.title,
+        "[SubsPlease] Sousou no Frieren (01-28) (1080p) [Batch]"
+    );
+    assert_eq!(details.submitter, "subsplease");
+    assert_eq!(details.date, "2024-03-30 12:00 UTC");
+    assert_eq!(details.size, "38.4 GB");
+    assert_eq!(
+        details.info_hash,
+        "0123456789abcdef0123456789abcdef01234567"
+    );
+    assert_eq!(details.comments, 2);
+    assert!(details.description.starts_with("Batch of episodes 01-28."));
+    assert!(details
+        .description
+        .ends_with("Encoded from the official release."));
+
+    let folder = "[SubsPlease] Sousou no Frieren (01-28) (1080p) [Batch]";
+    assert_eq!(
+        details.files,
+        vec![
+            ItemFile {
+                path: format!("{}/[SubsPlease] Sousou no Frieren - 01 (1080p).mkv", folder),
+                size: "1.4 GB".to_owned(),
+            },
+            ItemFile {
+                path: format!("{}/[SubsPlease] Sousou no Frieren - 02 (1080p).mkv", folder),
+                size: "1.3 GB".to_owned(),
+            },
+            ItemFile {
+                path: format!("{}/Extras/NCOP.mkv", folder),
+                size: "120.5 MB".to_owned(),
+            },
+            ItemFile {
+                path: "README.txt".to_owned(),
+                size: "512 B".to_owned(),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_details() {
+    let html = include_str!("fixtures/nyaa_view.html");
+    let (addr, server) = serve(vec![(200, html.to_owned()), (404, "".to_owned())]);
+    let client = reqwest::Client::new();
+
+    let url = format!("{}/view/1234567", addr);
+    let details = fetch_details(&client, &url, Some(5)).await.unwrap();
+    assert_eq!(details.files.len(), 4);
+
+    let err = fetch_details(&client, &url, Some(5)).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid response code: 404"));
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /view/1234567 "));
+}
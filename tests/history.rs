@@ -0,0 +1,309 @@
+#[allow(dead_code)]
+mod common;
+
+use crossterm::event::KeyCode;
+use nyaa::{
+    app::Context,
+    client::{Client, DownloadResult, Profile},
+    history::{self, DownloadChange, DownloadHistory, History, SavedSearch},
+    source::{nyaa_html::NyaaHtmlSource, Source, SourceConfig, Sources},
+    sync::SearchQuery,
+    widget::{
+        sort::{SelectedSort, SortDir},
+        status::StatusPopup,
+    },
+};
+
+use crate::common::{item, reset_buffer, run_app, EventBuilder};
+
+fn search(query: &str) -> SavedSearch {
+    SavedSearch {
+        query: query.to_owned(),
+        category: "AllAnime".to_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_history_push() {
+    let mut history = History::default();
+    history.push(search("one"));
+    history.push(search("two"));
+    history.push(search("one"));
+    let queries = history
+        .recent
+        .iter()
+        .map(|s| s.query.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(queries, vec!["one", "two"]);
+
+    for i in 0..200 {
+        history.push(search(&i.to_string()));
+    }
+    assert_eq!(history.recent.len(), 100);
+    assert_eq!(history.recent[0].query, "199");
+}
+
+#[test]
+fn test_history_saved() {
+    let mut history = History::default();
+    history.push(search("frieren"));
+    history.push(search("one piece"));
+    history.save("Frieren".to_owned(), &search("frieren 1080p"));
+    history.save("Frieren".to_owned(), &search("frieren"));
+    assert_eq!(history.saved.len(), 1);
+    assert_eq!(history.saved[0].query, "frieren");
+
+    // Saved searches come first
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap().title(), "Frieren");
+    assert_eq!(history.get(1).unwrap().title(), "one piece");
+    assert!(history.get(3).is_none());
+
+    assert_eq!(history.remove(0).unwrap().name, "Frieren");
+    assert_eq!(history.remove(1).unwrap().query, "frieren");
+    assert!(history.remove(1).is_none());
+    assert_eq!(history.len(), 1);
+}
+
+#[test]
+fn test_saved_search_query() {
+    let query = SearchQuery {
+        query: "frieren".to_owned(),
+        page: 3,
+        category: 12,
+        filter: 2,
+        sort: SelectedSort {
+            sort: 4,
+            dir: SortDir::Asc,
+        },
+        user: Some("subsplease".to_owned()),
+    };
+    let saved = SavedSearch::from_search(Sources::Nyaa, &query, &NyaaHtmlSource::info());
+    assert_eq!(saved.category, "AnimeEnglishTranslated");
+    assert_eq!(saved.filter, "Trusted Only");
+    assert_eq!(saved.sort, "Size");
+
+    let restored = saved.to_query(&SourceConfig::default()).unwrap();
+    assert_eq!(restored.query, "frieren");
+    assert_eq!(restored.page, 1);
+    assert_eq!(restored.category, 12);
+    assert_eq!(restored.filter, 2);
+    assert_eq!(restored.sort.sort, 4);
+    assert!(restored.sort.dir == SortDir::Asc);
+    assert_eq!(restored.user.as_deref(), Some("subsplease"));
+
+    let missing = SavedSearch {
+        category: "Missing".to_owned(),
+        ..saved
+    };
+    assert!(missing.to_query(&SourceConfig::default()).is_err());
+}
+
+#[test]
+fn test_history_store() {
+    let dir = std::env::temp_dir().join(format!("nyaa-history-{}", std::process::id()));
+    let mut history = history::load_history(&dir).unwrap();
+    assert!(history.is_empty());
+
+    history.push(search("frieren"));
+    history.save("Frieren".to_owned(), &search("frieren"));
+    history::store_history(&dir, &history).unwrap();
+
+    let loaded = history::load_history(&dir).unwrap();
+    assert_eq!(loaded.recent.len(), 1);
+    assert_eq!(loaded.saved[0].name, "Frieren");
+    assert!(loaded.saved[0].same_search(&loaded.recent[0]));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_download_history() {
+    let items = vec![item("1", "ABCDEF"), item("2", ""), item("3", "123456")];
+    let res = DownloadResult::new(None, vec!["1".to_owned(), "2".to_owned()], vec![], true);
+    let mut downloads = DownloadHistory::default();
+    downloads.record(&items, Client::Cmd, Some(Sources::Nyaa), &res);
+    assert_eq!(downloads.downloads.len(), 2);
+    assert_eq!(downloads.downloads[0].id, "2");
+    assert_eq!(downloads.downloads[1].hash, "abcdef");
+
+    // Matched by hash on any source, or by id on the same source
+    assert!(downloads.contains(&item("99", "abcdef"), &Sources::TorrentGalaxy));
+    assert!(downloads.contains(&item("2", ""), &Sources::Nyaa));
+    assert!(!downloads.contains(&item("2", ""), &Sources::SukebeiNyaa));
+    assert!(!downloads.contains(&items[2], &Sources::Nyaa));
+    // Sources like TorrentGalaxy list the hash without a magnet link
+    let mut listed = item("tgx-1", "");
+    listed.extra.insert("hash".to_owned(), "ABCDEF".to_owned());
+    assert!(downloads.contains(&listed, &Sources::TorrentGalaxy));
+
+    // Downloading again moves the item to the top
+    let res = DownloadResult::new(None, vec!["1".to_owned()], vec![], false);
+    downloads.record(&items[..1], Client::Cmd, Some(Sources::Nyaa), &res);
+    assert_eq!(downloads.downloads.len(), 2);
+    assert_eq!(downloads.downloads[0].id, "1");
+
+    let resend = downloads.downloads[0].to_item();
+    assert_eq!(resend.title, "1");
+    assert_eq!(resend.magnet_link, items[0].magnet_link);
+    assert_eq!(resend.post_link, items[0].post_link);
+
+    downloads.apply(&[DownloadChange::Remove(downloads.downloads[0].clone())]);
+    assert!(!downloads.contains(&items[0], &Sources::Nyaa));
+    assert!(downloads.contains(&item("2", ""), &Sources::Nyaa));
+}
+
+#[test]
+fn test_status_hashes() {
+    let mut ctx = Context::default();
+    let items = vec![item("1", "ABCDEF"), item("2", ""), item("3", "123456")];
+    let mut res = DownloadResult::new(None, vec!["1".to_owned(), "3".to_owned()], vec![], true);
+    res.routed = vec![("3".to_owned(), Profile::Named("seedbox".to_owned()))];
+    ctx.downloads.record(&items, Client::Qbit, None, &res);
+    let res = DownloadResult::new(None, vec!["1".to_owned()], vec![], false);
+    ctx.downloads
+        .record(&items[..1], Client::Qbit, Some(Sources::Nyaa), &res);
+
+    // Each client is only asked about the torrents sent to it
+    let groups = StatusPopup::hashes(&ctx);
+    assert_eq!(groups.len(), 2);
+    assert!(groups[0].0 == Profile::Client(Client::Qbit));
+    assert_eq!(groups[0].1, ["abcdef"]);
+    assert!(groups[1].0 == Profile::Named("seedbox".to_owned()));
+    assert_eq!(groups[1].1, ["123456"]);
+}
+
+#[test]
+fn test_item_source() {
+    let mut ctx = Context::default();
+    ctx.src = Sources::Multi;
+    let mut found = item("1", "abcdef");
+    assert!(ctx.item_source(&found) == Sources::Multi);
+
+    // Results from Multi are recorded under the source they came from
+    found
+        .extra
+        .insert("source".to_owned(), "TorrentGalaxy".to_owned());
+    assert!(ctx.item_source(&found) == Sources::TorrentGalaxy);
+    let res = DownloadResult::new(None, vec!["1".to_owned()], vec![], false);
+    let source = Some(ctx.item_source(&found));
+    ctx.downloads.record(&[found], Client::Cmd, source, &res);
+    assert!(ctx.downloads.downloads[0].source == Some(Sources::TorrentGalaxy));
+}
+
+#[test]
+fn test_record_downloads() {
+    let dir = std::env::temp_dir().join(format!("nyaa-downloads-{}", std::process::id()));
+    let items = vec![item("1", "abcdef")];
+    let failed = DownloadResult::new(None, vec![], vec![], false);
+    history::record_downloads(&dir, &items, Client::Cmd, None, &failed).unwrap();
+    assert!(!dir.exists());
+
+    let res = DownloadResult::new(None, vec!["1".to_owned()], vec![], false);
+    history::record_downloads(&dir, &items, Client::Cmd, None, &res).unwrap();
+    history::record_downloads(&dir, &items, Client::Cmd, None, &res).unwrap();
+    let downloads = history::load_downloads(&dir).unwrap();
+    assert_eq!(downloads.downloads.len(), 1);
+    assert_eq!(downloads.downloads[0].title, "1");
+    assert!(downloads.contains(&items[0], &Sources::Nyaa));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_update_downloads() {
+    let dir = std::env::temp_dir().join(format!("nyaa-update-downloads-{}", std::process::id()));
+    let items = [item("1", "abcdef"), item("2", "123456")];
+    let res = DownloadResult::new(None, vec!["1".to_owned(), "2".to_owned()], vec![], true);
+
+    // The TUI records an item while another instance saves one to the file
+    let mut downloads = DownloadHistory::default();
+    let changes = downloads.record(&items[..1], Client::Cmd, Some(Sources::Nyaa), &res);
+    history::record_downloads(&dir, &items[1..], Client::Cmd, Some(Sources::Nyaa), &res).unwrap();
+    let downloads = history::update_downloads(&dir, &changes).unwrap();
+    let ids = downloads
+        .downloads
+        .iter()
+        .map(|d| d.id.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(ids, ["1", "2"]);
+
+    // Removing an entry doesn't bring back the ones missing from memory
+    let removed = DownloadChange::Remove(downloads.downloads[1].clone());
+    let downloads = history::update_downloads(&dir, &[removed]).unwrap();
+    assert_eq!(downloads.downloads.len(), 1);
+    assert_eq!(history::load_downloads(&dir).unwrap().downloads[0].id, "1");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_history_popup() {
+    let sync = EventBuilder::new()
+        .string("/frieren")
+        .enter()
+        .string("R")
+        .quit()
+        .build();
+    let res = reset_buffer(&run_app(sync, 60, 22).await.unwrap());
+    let lines = buffer_lines(&res);
+    assert!(lines.iter().any(|l| l.contains("┌Search History─")));
+    assert!(lines.iter().any(|l| l.contains("  frieren")));
+
+    // Save the search under a new name
+    let sync = EventBuilder::new()
+        .string("/frieren")
+        .enter()
+        .string("Rs")
+        .key(KeyCode::Backspace)
+        .string("EN")
+        .enter()
+        .quit()
+        .build();
+    let res = reset_buffer(&run_app(sync, 60, 22).await.unwrap());
+    let lines = buffer_lines(&res);
+    assert!(lines.iter().any(|l| l.contains("★ friereEN")));
+    assert!(lines.iter().any(|l| l.contains("  frieren")));
+}
+
+#[tokio::test]
+async fn test_history_restore() {
+    let mut clear = EventBuilder::new();
+    clear.string("/frieren").enter().string("/");
+    for _ in 0..7 {
+        clear.key(KeyCode::Backspace);
+    }
+    // Run a second search, then go back to the first one
+    let sync = clear
+        .string("one piece")
+        .enter()
+        .string("Rj")
+        .enter()
+        .quit()
+        .build();
+    let res = reset_buffer(&run_app(sync, 60, 22).await.unwrap());
+    let lines = buffer_lines(&res);
+    assert!(!lines.iter().any(|l| l.contains("┌Search History─")));
+    assert!(lines.iter().any(|l| l.contains("│frieren ")));
+}
+
+#[tokio::test]
+async fn test_downloads_popup() {
+    let sync = EventBuilder::new().string("D").quit().build();
+    let res = reset_buffer(&run_app(sync, 60, 22).await.unwrap());
+    let lines = buffer_lines(&res);
+    assert!(lines.iter().any(|l| l.contains("┌Downloads─")));
+    assert!(lines.iter().any(|l| l.contains("Nothing downloaded yet")));
+
+    // Typed characters go to the search instead of closing the popup
+    let sync = EventBuilder::new().string("D/qD").quit().build();
+    let res = reset_buffer(&run_app(sync, 60, 22).await.unwrap());
+    let lines = buffer_lines(&res);
+    assert!(lines.iter().any(|l| l.contains("┌Search downloads─")));
+    assert!(lines.iter().any(|l| l.contains("> qD")));
+}
+
+fn buffer_lines(buf: &ratatui::buffer::Buffer) -> Vec<String> {
+    buf.content
+        .chunks(buf.area.width as usize)
+        .map(|row| row.iter().map(|c| c.symbol()).collect())
+        .collect()
+}
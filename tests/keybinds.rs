@@ -0,0 +1,142 @@
+#[allow(dead_code)]
+mod common;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use nyaa::{
+    config::Config,
+    keybinds::{Action, KeybindConfig, Keybinds},
+    util::conv::{key_to_string, string_to_key},
+    widget::{results::ResultsWidget, Widget as _},
+};
+
+use crate::common::{reset_buffer, run_app_with_config, EventBuilder};
+
+#[test]
+fn test_string_to_key() {
+    for key in [
+        "j",
+        "J",
+        "?",
+        "<CR>",
+        "<C-a>",
+        "<S-Tab>",
+        "<Space>",
+        "<C-Space>",
+        "<F1>",
+    ] {
+        let (code, modifiers) = string_to_key(key).unwrap();
+        assert_eq!(key_to_string(code, modifiers), key);
+    }
+    assert_eq!(
+        string_to_key("<c-A>"),
+        Some((KeyCode::Char('A'), KeyModifiers::CONTROL))
+    );
+    assert_eq!(
+        string_to_key("<S-x>"),
+        Some((KeyCode::Char('X'), KeyModifiers::SHIFT))
+    );
+    assert_eq!(
+        string_to_key("<enter>"),
+        Some((KeyCode::Enter, KeyModifiers::NONE))
+    );
+    assert_eq!(string_to_key("<X-a>"), None);
+    assert_eq!(string_to_key("<Nope>"), None);
+    assert_eq!(string_to_key("jk"), None);
+}
+
+#[test]
+fn test_keybind_config() {
+    let config = toml::from_str::<Config>(
+        r#"
+        [keybinds.normal]
+        x = "Categories"
+        c = "None"
+        "<c-d>" = "Download"
+
+        [keybinds.batch]
+        "<S-Tab>" = "Quit"
+        "#,
+    )
+    .unwrap();
+    let keys = config.keybinds.unwrap().build().unwrap();
+
+    let get = |code, modifiers| keys.normal.get(&code, &modifiers);
+    assert_eq!(
+        get(KeyCode::Char('x'), KeyModifiers::NONE),
+        Some(Action::Categories)
+    );
+    assert_eq!(get(KeyCode::Char('c'), KeyModifiers::NONE), None);
+    assert_eq!(
+        get(KeyCode::Char('d'), KeyModifiers::CONTROL),
+        Some(Action::Download)
+    );
+    // Defaults are kept for anything not overridden
+    assert_eq!(
+        get(KeyCode::Enter, KeyModifiers::NONE),
+        Some(Action::Download)
+    );
+    assert_eq!(
+        get(KeyCode::Char('j'), KeyModifiers::NONE),
+        Some(Action::Down)
+    );
+    assert_eq!(
+        keys.batch.get(&KeyCode::BackTab, &KeyModifiers::NONE),
+        Some(Action::Quit)
+    );
+    assert_eq!(
+        keys.batch.get(&KeyCode::Tab, &KeyModifiers::NONE),
+        Some(Action::SwitchPane)
+    );
+
+    // Help shows the bound keys
+    let help = ResultsWidget::get_help(&keys.normal).unwrap();
+    assert!(help.contains(&("x".to_owned(), "Categories")));
+    assert!(help.contains(&("<CR>, <C-d>".to_owned(), "Download")));
+    let default_help = ResultsWidget::get_help(&Keybinds::default().normal).unwrap();
+    assert!(default_help.contains(&("c".to_owned(), "Categories")));
+
+    let err = toml::from_str::<KeybindConfig>("[normal]\n\"<Nope>\" = \"Quit\"")
+        .unwrap()
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err, "Invalid key \"<Nope>\" in [keybinds.normal]");
+}
+
+#[tokio::test]
+async fn test_remapped_keys() {
+    let config = toml::from_str::<Config>(
+        r#"
+        [keybinds.normal]
+        x = "Categories"
+        c = "None"
+
+        [keybinds.category]
+        x = "Close"
+        "#,
+    )
+    .unwrap();
+
+    // Unbound key does nothing, remapped key opens the popup
+    let sync = EventBuilder::new().string("cx").quit().build();
+    let res = reset_buffer(
+        &run_app_with_config(sync, 60, 22, config.clone())
+            .await
+            .unwrap(),
+    );
+    let lines = buffer_lines(&res);
+    assert!(lines.iter().any(|l| l.contains("┌Category─")));
+
+    // Remapped key closes the popup again
+    let sync = EventBuilder::new().string("xx").quit().build();
+    let res = reset_buffer(&run_app_with_config(sync, 60, 22, config).await.unwrap());
+    let lines = buffer_lines(&res);
+    assert!(!lines.iter().any(|l| l.contains("┌Category─")));
+}
+
+fn buffer_lines(buf: &ratatui::buffer::Buffer) -> Vec<String> {
+    buf.content
+        .chunks(buf.area.width as usize)
+        .map(|row| row.iter().map(|c| c.symbol()).collect())
+        .collect()
+}
@@ -0,0 +1,156 @@
+use nyaa::{
+    cli::{self, OutputFormat, QueryArgs},
+    config::Config,
+    results::ResultResponse,
+    source::{
+        multi::{self, MultiConfig},
+        nyaa_html::NyaaConfig,
+        torznab::TorznabConfig,
+        Item, ItemType, Sources,
+    },
+    util::conv::item_hash,
+    widget::sort::{SelectedSort, SortDir},
+};
+
+use crate::common::{item, serve};
+
+#[allow(dead_code)]
+mod common;
+
+fn ranked(id: &str, hash: &str, timestamp: i64, seeders: u32) -> Item {
+    Item {
+        timestamp,
+        seeders,
+        ..item(id, hash)
+    }
+}
+
+#[test]
+fn test_merge_results() {
+    let nyaa = ResultResponse {
+        items: vec![
+            ranked("nyaa-1", "AAAA", 10, 5),
+            ranked("nyaa-2", "bbbb", 30, 0),
+        ],
+        last_page: 2,
+        total_results: 150,
+    };
+    let tgx = ResultResponse {
+        items: vec![
+            ranked("tgx-1", "aaaa", 20, 50),
+            ranked("tgx-2", "cccc", 20, 1),
+        ],
+        last_page: 5,
+        total_results: 250,
+    };
+    let res = multi::merge_results(vec![(Sources::Nyaa, nyaa), (Sources::TorrentGalaxy, tgx)]);
+
+    // The same hash from the second source is dropped, regardless of case
+    let ids = res.items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["nyaa-1", "nyaa-2", "tgx-2"]);
+    assert_eq!(res.last_page, 5);
+    assert_eq!(res.total_results, 399);
+    assert!(multi::item_source(&res.items[0]) == Some(Sources::Nyaa));
+    assert!(multi::item_source(&res.items[2]) == Some(Sources::TorrentGalaxy));
+}
+
+#[test]
+fn test_item_hash() {
+    let mut tgx = item("tgx-1", "AAAA");
+    assert_eq!(item_hash(&tgx).as_deref(), Some("aaaa"));
+    // The hash listed by the source is used even without a magnet link
+    tgx.magnet_link.clear();
+    assert_eq!(item_hash(&tgx), None);
+    tgx.extra.insert("hash".to_owned(), "BBBB".to_owned());
+    assert_eq!(item_hash(&tgx).as_deref(), Some("bbbb"));
+    tgx.extra.insert("hash".to_owned(), "".to_owned());
+    assert_eq!(item_hash(&tgx), None);
+}
+
+#[test]
+fn test_sort_filter() {
+    let mut items = vec![
+        ranked("a", "1", 10, 5),
+        ranked("b", "2", 30, 0),
+        ranked("c", "3", 20, 50),
+    ];
+    items[2].item_type = ItemType::Trusted;
+
+    multi::sort_items(&mut items, SelectedSort::default());
+    let ids = items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["b", "c", "a"]);
+
+    let seeders = SelectedSort {
+        sort: 2,
+        dir: SortDir::Asc,
+    };
+    multi::sort_items(&mut items, seeders);
+    let ids = items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["b", "a", "c"]);
+
+    let mut alive = items.clone();
+    multi::filter_items(&mut alive, 1);
+    assert_eq!(alive.len(), 2);
+
+    multi::filter_items(&mut items, 2);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, "c");
+}
+
+#[tokio::test]
+async fn test_multi_search() {
+    let xml = include_str!("fixtures/torznab.xml").to_owned();
+    let (torznab_addr, torznab) = serve(vec![(200, xml)]);
+    let (nyaa_addr, nyaa) = serve(vec![(500, "".to_owned())]);
+
+    let mut config = Config::default();
+    config.sources.torznab = Some(TorznabConfig {
+        base_url: format!("{}/api", torznab_addr),
+        ..Default::default()
+    });
+    config.sources.nyaa = Some(NyaaConfig {
+        base_url: nyaa_addr,
+        ..Default::default()
+    });
+    config.sources.multi = Some(MultiConfig {
+        sources: vec![Sources::Torznab, Sources::Nyaa],
+        ..Default::default()
+    });
+    let args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        source: Some("multi".to_owned()),
+        sort: Some("seeders".to_owned()),
+        sort_dir: Some(SortDir::Asc),
+        ..Default::default()
+    };
+
+    // Nyaa failing doesn't hide the results from Torznab
+    let (src, search, res) = cli::search(&args, &config).await.unwrap();
+    assert!(src == Sources::Multi);
+    let json =
+        cli::format_results(&src, &search, &res, &config.sources, OutputFormat::Json).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["source"], "Multi");
+    assert_eq!(json["items"][0]["seeders"], 0);
+    assert_eq!(json["items"][1]["seeders"], 1203);
+    assert_eq!(json["items"][1]["category"], "TVAnime");
+
+    let requests = torznab.join().unwrap();
+    assert!(requests[0].starts_with("GET /api?t=search&extended=1&q=frieren&"));
+    let requests = nyaa.join().unwrap();
+    assert!(requests[0].contains("q=frieren"));
+
+    // Fails only once every source fails
+    let (nyaa_addr, nyaa) = serve(vec![(500, "".to_owned())]);
+    config.sources.nyaa = Some(NyaaConfig {
+        base_url: nyaa_addr,
+        ..Default::default()
+    });
+    config.sources.multi = Some(MultiConfig {
+        sources: vec![Sources::Nyaa],
+        ..Default::default()
+    });
+    let err = cli::search(&args, &config).await.err().unwrap();
+    assert!(err.to_string().starts_with("Nyaa: "));
+    nyaa.join().unwrap();
+}
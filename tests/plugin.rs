@@ -0,0 +1,204 @@
+use std::{fs, os::unix::fs::PermissionsExt as _, path::PathBuf};
+
+use nyaa::{
+    cli::{self, QueryArgs},
+    config::Config,
+    source::{plugin::PluginConfig, ItemType, Sources},
+};
+
+fn plugin(dir: &PathBuf, name: &str, script: &str) -> PathBuf {
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{}", script)).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+static TRACKER: &str = r#"
+req=$(cat)
+echo "$req" >> "$1"
+case "$req" in
+*'"method":"info"'*)
+    echo '{
+        "categories": [{ "name": "Video", "entries": [
+            { "name": "Movies", "value": "movies", "label": "Mov", "color": "LightBlue" },
+            { "name": "TV", "value": "tv", "label": "TV", "color": "Green" }
+        ]}],
+        "filters": ["All", "Verified"],
+        "sorts": ["Newest", "Seeders"]
+    }' ;;
+*'"method":"search"'*)
+    echo '{ "last_page": 4, "items": [{
+        "id": "42",
+        "title": "Some Show S01E01",
+        "post_link": "https://tracker.example/t/42",
+        "magnet_link": "magnet:?xt=urn:btih:0123456789ABCDEF0123456789ABCDEF01234567",
+        "bytes": 1503238553,
+        "timestamp": 1697241600,
+        "seeders": 15,
+        "leechers": 2,
+        "category": "tv",
+        "trusted": true,
+        "extra": { "uploader": "someone" }
+    }]}' ;;
+*) echo '{ "error": "Unsupported request" }' ;;
+esac
+"#;
+
+#[tokio::test]
+async fn test_plugin_search() {
+    let dir = std::env::temp_dir().join(format!("nyaa-plugin-{}", std::process::id()));
+    let log = dir.join("requests.log");
+    let cmd = plugin(&dir, "tracker.sh", TRACKER);
+
+    let mut config: Config = toml::from_str("default_source = \"plugin:tracker\"").unwrap();
+    assert!(config.source == Sources::Plugin("tracker".to_owned()));
+    config.sources.plugin.insert(
+        "tracker".to_owned(),
+        PluginConfig {
+            cmd: Some(cmd.to_string_lossy().to_string()),
+            args: vec![log.to_string_lossy().to_string()],
+            default_sort: "Seeders".to_owned(),
+            ..Default::default()
+        },
+    );
+
+    let src = Sources::Plugin("tracker".to_owned());
+    // Nothing is run until the info is loaded
+    assert!(!src.info_loaded(&config.sources));
+    assert_eq!(src.info(&config.sources).sorts, vec!["Date"]);
+    assert!(!log.exists());
+    src.load_info(&config.sources).await.unwrap();
+    assert!(src.info_loaded(&config.sources));
+
+    let info = src.info(&config.sources);
+    assert_eq!(info.filters, vec!["All", "Verified"]);
+    assert_eq!(info.sorts, vec!["Newest", "Seeders"]);
+    assert_eq!(info.entry_from_cfg("TV").id, 2);
+    assert_eq!(src.default_sort(&config.sources).sort, 1);
+
+    let args = QueryArgs {
+        query: Some("some show".to_owned()),
+        source: Some("tracker".to_owned()),
+        category: Some("TV".to_owned()),
+        filter: Some("Verified".to_owned()),
+        sort: Some("Newest".to_owned()),
+        page: Some(2),
+        ..Default::default()
+    };
+    let (found, _, res) = cli::search(&args, &config).await.unwrap();
+    assert!(found == src);
+    assert_eq!(res.last_page, 4);
+    assert_eq!(res.items.len(), 1);
+
+    let item = &res.items[0];
+    assert_eq!(item.id, "tracker-42");
+    assert_eq!(item.title, "Some Show S01E01");
+    assert_eq!(item.size, "1.5 GB");
+    assert_eq!(item.file_name, "42.torrent");
+    assert_eq!(item.category, 2);
+    assert_eq!(item.icon.label, "TV ");
+    assert!(matches!(item.item_type, ItemType::Trusted));
+    assert_eq!(
+        item.extra.get("uploader").map(String::as_str),
+        Some("someone")
+    );
+    assert_eq!(
+        item.extra.get("hash").map(String::as_str),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+
+    // Info is only requested once
+    let requests = fs::read_to_string(&log).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+    let requests = requests
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect::<Vec<serde_json::Value>>();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0]["method"], "info");
+    assert_eq!(requests[1]["method"], "search");
+    assert_eq!(requests[1]["query"], "some show");
+    assert_eq!(requests[1]["page"], 2);
+    assert_eq!(requests[1]["category"], "tv");
+    assert_eq!(requests[1]["filter"], "Verified");
+    assert_eq!(requests[1]["sort"], "Newest");
+    assert_eq!(requests[1]["sort_dir"], "Desc");
+}
+
+#[tokio::test]
+async fn test_plugin_errors() {
+    let dir = std::env::temp_dir().join(format!("nyaa-plugin-errors-{}", std::process::id()));
+    plugin(
+        &dir,
+        "broken",
+        "echo run >> \"$0.runs\"\necho 'site is down' >&2\nexit 3",
+    );
+    plugin(
+        &dir,
+        "refuses",
+        "cat > /dev/null\necho '{ \"error\": \"Login required\" }'",
+    );
+
+    let mut config = Config::default();
+    // Relative commands are run from the plugins folder
+    config.sources.plugins_path = Some(dir.clone());
+    config
+        .sources
+        .plugin
+        .insert("broken".to_owned(), PluginConfig::default());
+    config.sources.plugin.insert(
+        "refuses".to_owned(),
+        PluginConfig {
+            cmd: Some("refuses".to_owned()),
+            ..Default::default()
+        },
+    );
+    config
+        .sources
+        .plugin
+        .insert("missing".to_owned(), PluginConfig::default());
+
+    let search = |name: &str| QueryArgs {
+        source: Some(name.to_owned()),
+        ..Default::default()
+    };
+    let err = cli::search(&search("broken"), &config)
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("Exited with status code"));
+    assert!(err.contains("site is down"));
+
+    // The failure is kept, so the plugin isn't run again
+    let again = cli::search(&search("broken"), &config)
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert_eq!(again, err);
+    assert!(Sources::Plugin("broken".to_owned()).info_loaded(&config.sources));
+    let runs = fs::read_to_string(dir.join("broken.runs")).unwrap();
+    assert_eq!(runs.lines().count(), 1);
+
+    let err = cli::search(&search("refuses"), &config)
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("Login required"));
+
+    let err = cli::search(&search("missing"), &config)
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("Failed to run plugin"));
+
+    // A plugin that can't start still has the default info
+    let info = Sources::Plugin("missing".to_owned()).info(&config.sources);
+    assert_eq!(info.filters, vec!["No Filter"]);
+    assert_eq!(info.cats.len(), 1);
+    let _ = fs::remove_dir_all(&dir);
+}
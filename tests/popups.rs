@@ -208,7 +208,7 @@ async fn test_themes() {
 async fn test_download_client() {
     let sync = EventBuilder::new()
         .string('d')
-        .string("jjjj")
+        .string("jjjjjjjjj")
         .enter()
         .string('d')
         .quit()
@@ -221,9 +221,6 @@ async fn test_download_client() {
             r#"│                └────────────────────────────────────────┘│"#,
             r#"└──────────────────────────────────────────────────────────┘"#,
             r#"┌Results 1-0 (0 total): Page 1/0─dl: Default App, src: Nyaa┐"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
             r#"│              ┌Download Client─────────────┐              │"#,
             r#"│              │   qBittorrent              │              │"#,
             r#"│              │   Transmission             │              │"#,
@@ -231,13 +228,16 @@ async fn test_download_client() {
             r#"│              │  Default App              │              │"#,
             r#"│              │   Download Torrent File    │              │"#,
             r#"│              │   Run Command              │              │"#,
+            r#"│              │   Deluge                   │              │"#,
+            r#"│              │   aria2                    │              │"#,
+            r#"│              │   rTorrent                 │              │"#,
+            r#"│              │   Webhook                  │              │"#,
+            r#"│              │   Watch Folder             │              │"#,
             r#"│              └────────────────────────────┘              │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
             r#"└─────────────────────────────────────────────────────────d┘"#,
         ])
     );
@@ -261,22 +261,22 @@ async fn test_source() {
             r#"└──────────────────────────────────────────────────────────┘"#,
             r#"┌Results 1-0 (0 total): Page 1dl: Run Command, src: Sukebei┐"#,
             r#"│                                                          │"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
             r#"│              ┌Source──────────────────────┐              │"#,
             r#"│              │   Nyaa                     │              │"#,
             r#"│              │  Sukebei                  │              │"#,
             r#"│              │   TorrentGalaxy            │              │"#,
+            r#"│              │   Torznab                  │              │"#,
+            r#"│              │   Multi                    │              │"#,
+            r#"│              │   AnimeTosho               │              │"#,
+            r#"│              │   TokyoTosho               │              │"#,
+            r#"│              │   AniDex                   │              │"#,
+            r#"│              │   1337x                    │              │"#,
             r#"│              └────────────────────────────┘              │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
             r#"│                                                          │"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
-            r#"│                                                          │"#,
             r#"└─────────────────────────────────────────────────────<C-s>┘"#,
         ])
     );
@@ -372,3 +372,29 @@ async fn test_page() {
         ])
     );
 }
+
+#[tokio::test]
+async fn test_status() {
+    let sync = EventBuilder::new().string('a').quit().build();
+
+    assert_eq!(
+        reset_buffer(&run_app(sync, 60, 15).await.unwrap()),
+        Buffer::with_lines([
+            r#"┌Search──────────────────────────────Press F1 or ? for help┐"#,
+            r#"│                                                          │"#,
+            r#"└──────────────────────────────────────────────────────────┘"#,
+            r#"┌Results 1-0 (0 total): Page 1/0─dl: Run Command, src: Nyaa┐"#,
+            r#"│                                                          │"#,
+            r#"│                                                          │"#,
+            r#"│   ┌Torrents in Run Command───────────────────────────┐   │"#,
+            r#"│   │  Loading torrents...                             │   │"#,
+            r#"│   └──────────────────────────────────────────────────┘   │"#,
+            r#"│                                                          │"#,
+            r#"│                                                          │"#,
+            r#"│                                                          │"#,
+            r#"│                                                          │"#,
+            r#"│                                                          │"#,
+            r#"└─────────────────────────────────────────────────────────a┘"#,
+        ])
+    );
+}
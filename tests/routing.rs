@@ -0,0 +1,143 @@
+use nyaa::{
+    client::{Client, Profile},
+    config::Config,
+    history::DownloadHistory,
+    routing,
+    source::{Item, Sources},
+};
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+fn item(id: &str, category: usize, bytes: usize) -> Item {
+    Item {
+        id: id.to_owned(),
+        title: format!("[Group] {}", id),
+        magnet_link: format!("magnet:?xt=urn:btih:{}", id),
+        category,
+        bytes,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_route() {
+    let config: Config = toml::from_str(
+        r#"
+        [[routing]]
+        source = "Nyaa"
+        categories = [31, 32]
+
+        [[routing]]
+        title = "(?i)batch"
+        min_size = "10 GiB"
+
+        [[routing]]
+        uploader = "subsplease"
+        "#,
+    )
+    .unwrap();
+    let rules = config.routing.unwrap();
+
+    let mut uploaded = item("show", 12, 0);
+    uploaded
+        .extra
+        .insert("uploader".to_owned(), "SubsPlease".to_owned());
+    let items = vec![
+        item("book", 31, 0),
+        item("Show BATCH", 12, 20 * 1024 * 1024 * 1024),
+        item("small batch", 12, 1024),
+        item("music", 22, 0),
+        item("book 2", 32, 0),
+        uploaded,
+    ];
+    let groups = routing::route(&rules, Some(&Sources::Nyaa), items.clone()).unwrap();
+    let groups = groups
+        .iter()
+        .map(|(rule, items)| (*rule, items.iter().map(|i| i.id.as_str()).collect()))
+        .collect::<Vec<(Option<usize>, Vec<&str>)>>();
+    assert_eq!(
+        groups,
+        [
+            (Some(0), vec!["book", "book 2"]),
+            (Some(1), vec!["Show BATCH"]),
+            (None, vec!["small batch", "music"]),
+            (Some(2), vec!["show"]),
+        ]
+    );
+
+    // The source of the rule must match too
+    let groups =
+        routing::route(&rules, Some(&Sources::TorrentGalaxy), items[..1].to_vec()).unwrap();
+    assert_eq!(groups[0].0, None);
+    // Magnet links and post urls have no source
+    let groups = routing::route(&rules, None, items[..1].to_vec()).unwrap();
+    assert_eq!(groups[0].0, None);
+
+    let invalid: Config = toml::from_str("[[routing]]\ntitle = \"(\"").unwrap();
+    let err = routing::route(&invalid.routing.unwrap(), Some(&Sources::Nyaa), items)
+        .err()
+        .unwrap();
+    assert!(err.starts_with("Invalid title regex \"(\""));
+}
+
+#[tokio::test]
+async fn test_routing_download() {
+    let (addr, handle) = serve(vec![(200, "".to_owned()), (200, "".to_owned())]);
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [client.webhook]
+        url = "{0}/default"
+
+        [client.profiles.books]
+        client = "Webhook"
+        url = "{0}/books"
+
+        [[routing]]
+        categories = [31]
+        client = "books"
+
+        [[routing]]
+        categories = [12]
+        overrides = {{ method = "PUT" }}
+        "#,
+        addr
+    ))
+    .unwrap();
+    let rules = config.routing.unwrap();
+    let items = vec![item("anime", 12, 0), item("book", 31, 0)];
+    let default = Profile::Client(Client::Webhook);
+    let books = Profile::Named("books".to_owned());
+    assert!(
+        routing::profiles(&rules, Some(&Sources::Nyaa), &items, &default)
+            == [default.clone(), books.clone()]
+    );
+
+    let res = routing::download(
+        &rules,
+        Some(&Sources::Nyaa),
+        true,
+        items.clone(),
+        &default,
+        &config.client,
+        reqwest::Client::new(),
+        reqwest::Client::new(),
+    )
+    .await;
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids, ["anime", "book"]);
+
+    // Each item is recorded with the client it was routed to
+    let mut history = DownloadHistory::default();
+    history.record(&items, default.clone(), Some(Sources::Nyaa), &res);
+    let book = history.downloads.iter().find(|d| d.id == "book").unwrap();
+    assert!(book.client == books);
+    let anime = history.downloads.iter().find(|d| d.id == "anime").unwrap();
+    assert!(anime.client == default);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("PUT /default "));
+    assert!(requests[1].starts_with("POST /books "));
+}
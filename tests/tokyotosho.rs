@@ -0,0 +1,89 @@
+use nyaa::{
+    cli::{self, QueryArgs},
+    config::Config,
+    source::{
+        tokyotosho::{parse_tokyotosho, TokyoToshoConfig, TokyoToshoSource},
+        Sources,
+    },
+};
+use reqwest::Url;
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn test_parse_tokyotosho() {
+    let html = include_str!("fixtures/tokyotosho.html");
+    let base_url = Url::parse("https://www.tokyotosho.info/search.php").unwrap();
+    let res = parse_tokyotosho::<TokyoToshoSource>(html, &base_url, None, 1).unwrap();
+
+    assert_eq!(res.items.len(), 2);
+    assert_eq!(res.last_page, 2);
+
+    let first = &res.items[0];
+    assert_eq!(
+        first.title,
+        "[SubsPlease] Frieren - 01 (1080p) [F02B9CEE].mkv"
+    );
+    assert_eq!(first.id, "tokyotosho-1800001");
+    assert_eq!(
+        first.post_link,
+        "https://www.tokyotosho.info/details.php?id=1800001"
+    );
+    assert_eq!(
+        first.torrent_link,
+        "https://nyaa.si/download/1700000.torrent"
+    );
+    assert!(first.magnet_link.starts_with("magnet:?xt=urn:btih:0123"));
+    assert_eq!(
+        first.extra.get("hash").map(String::as_str),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(first.category, 1);
+    assert_eq!(first.size, "1.36 GB");
+    assert_eq!(first.timestamp, 1700000000 - 20);
+    assert_eq!(first.seeders, 1203);
+    assert_eq!(first.leechers, 47);
+    assert_eq!(first.downloads, 48213);
+
+    let second = &res.items[1];
+    assert_eq!(second.category, 7);
+    assert_eq!(second.size, "512 MB");
+    assert_eq!(second.bytes, 512 * 1024 * 1024);
+    assert_eq!(second.seeders, 0);
+}
+
+#[tokio::test]
+async fn test_tokyotosho_search() {
+    let html = include_str!("fixtures/tokyotosho.html").to_owned();
+    let (addr, server) = serve(vec![(200, html.clone()), (200, html)]);
+
+    let mut config = Config::default();
+    config.sources.tokyotosho = Some(TokyoToshoConfig {
+        base_url: addr,
+        ..Default::default()
+    });
+    let mut args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        source: Some("tokyotosho".to_owned()),
+        category: Some("Raws".to_owned()),
+        filter: Some("No Dead Torrents".to_owned()),
+        ..Default::default()
+    };
+
+    // Dead torrents are filtered locally
+    let (src, _, res) = cli::search(&args, &config).await.unwrap();
+    assert!(src == Sources::TokyoTosho);
+    assert_eq!(res.items.len(), 1);
+    assert_eq!(res.items[0].id, "tokyotosho-1800001");
+
+    // Without a query, the listing is used instead of the search page
+    args.query = None;
+    cli::search(&args, &config).await.unwrap();
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /search.php?terms=frieren&type=7&"));
+    assert!(requests[1].starts_with("GET /?cat=7&page=1 "));
+}
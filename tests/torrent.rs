@@ -0,0 +1,130 @@
+#[allow(dead_code)]
+mod common;
+
+use std::collections::BTreeMap;
+
+use nyaa::{
+    torrent::{fetch_torrent, TorrentFile, TorrentInfo},
+    util::bencode::{decode, Value},
+};
+
+use crate::common::serve;
+
+#[test]
+fn test_decode() {
+    assert_eq!(decode(b"i-42e").unwrap(), Value::Int(-42));
+    assert_eq!(decode(b"4:spam").unwrap(), Value::Bytes(b"spam".to_vec()));
+    assert_eq!(
+        decode(b"l4:spami7ee").unwrap(),
+        Value::List(vec![Value::Bytes(b"spam".to_vec()), Value::Int(7)])
+    );
+    assert_eq!(
+        decode(b"d3:bar0:3:fooli1eee").unwrap(),
+        Value::Dict(BTreeMap::from([
+            (b"bar".to_vec(), Value::Bytes(vec![])),
+            (b"foo".to_vec(), Value::List(vec![Value::Int(1)])),
+        ]))
+    );
+
+    assert!(decode(b"i12").is_err());
+    assert!(decode(b"5:spam").is_err());
+    assert!(decode(b"l4:spam").is_err());
+    assert!(decode(b"i1ei2e").is_err());
+    assert!(decode(b"x").is_err());
+}
+
+#[test]
+fn test_parse_multi_file() {
+    let info = TorrentInfo::parse(include_bytes!("fixtures/multi.torrent")).unwrap();
+
+    assert_eq!(
+        info.name,
+        "[SubsPlease] Sousou no Frieren (01-02) (1080p) [Batch]"
+    );
+    assert_eq!(info.info_hash, "4498be799f00265cf7cf30450ccbe828bb4ce9ad");
+    assert_eq!(info.total_size, 3025456844);
+    assert_eq!(info.piece_length, 1048576);
+    assert_eq!(info.pieces, 3);
+    assert!(info.private);
+    assert_eq!(info.created_by.as_deref(), Some("mktorrent 1.1"));
+    assert_eq!(
+        info.trackers,
+        vec![
+            "http://nyaa.tracker.wf:7777/announce",
+            "udp://open.stealth.si:80/announce",
+            "udp://tracker.opentrackr.org:1337/announce",
+        ]
+    );
+    assert_eq!(
+        info.files[2],
+        TorrentFile {
+            path: vec!["Extras".to_owned(), "NCOP.mkv".to_owned()],
+            size: 126353408,
+        }
+    );
+    assert_eq!(
+        info.file_tree(),
+        vec![
+            "[SubsPlease] Sousou no Frieren (01-02) (1080p) [Batch]/",
+            "├─ [SubsPlease] Sousou no Frieren - 01 (1080p).mkv (1.5 GB)",
+            "├─ [SubsPlease] Sousou no Frieren - 02 (1080p).mkv (1.4 GB)",
+            "└─ Extras/",
+            "   ├─ NCOP.mkv (126.4 MB)",
+            "   └─ Scans/",
+            "      └─ cover.jpg (512 B)",
+        ]
+    );
+}
+
+#[test]
+fn test_parse_single_file() {
+    let info = TorrentInfo::parse(include_bytes!("fixtures/single.torrent")).unwrap();
+
+    assert_eq!(info.name, "Frieren - 01.mkv");
+    assert_eq!(info.info_hash, "c793b99312aa0e79f572e3f6c840b09ee265df32");
+    assert_eq!(info.total_size, 1503238553);
+    assert!(!info.private);
+    assert_eq!(
+        info.trackers,
+        vec!["udp://tracker.opentrackr.org:1337/announce"]
+    );
+    assert_eq!(info.file_tree(), vec!["└─ Frieren - 01.mkv (1.5 GB)"]);
+
+    assert!(TorrentInfo::parse(b"d8:announce3:urle").is_err());
+}
+
+#[test]
+fn test_decode_malformed() {
+    // A huge string length must not overflow when added to the position
+    let err = decode(b"18446744073709551615:abc").err().unwrap();
+    assert!(err.to_string().contains("string is longer than input"));
+    assert!(TorrentInfo::parse(b"d4:info18446744073709551615:e").is_err());
+
+    // Deep nesting is an error instead of a stack overflow
+    let deep = vec![b'l'; 200_000];
+    let err = decode(&deep).err().unwrap();
+    assert!(err.to_string().contains("nested too deeply"));
+    assert!(TorrentInfo::parse(&deep).is_err());
+
+    let mut ok = vec![b'l'; 10];
+    ok.extend(vec![b'e'; 10]);
+    assert!(decode(&ok).is_ok());
+}
+
+#[tokio::test]
+async fn test_fetch_torrent() {
+    let torrent = include_str!("fixtures/single.torrent");
+    let (addr, server) = serve(vec![(200, torrent.to_owned()), (404, "".to_owned())]);
+    let client = reqwest::Client::new();
+
+    let url = format!("{}/download/1234567.torrent", addr);
+    let info = fetch_torrent(&client, &url).await.unwrap();
+    assert_eq!(info.info_hash, "c793b99312aa0e79f572e3f6c840b09ee265df32");
+
+    let err = fetch_torrent(&client, &url).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid response code: 404"));
+    assert!(fetch_torrent(&client, "").await.is_err());
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /download/1234567.torrent "));
+}
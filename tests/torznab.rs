@@ -0,0 +1,49 @@
+use nyaa::source::{
+    torznab::{parse_torznab, TorznabSource},
+    ItemType,
+};
+
+#[test]
+fn test_parse_torznab() {
+    let xml = include_bytes!("fixtures/torznab.xml");
+    let res = parse_torznab::<TorznabSource>(xml, None, 100).unwrap();
+
+    assert_eq!(res.total_results, 250);
+    assert_eq!(res.last_page, 3);
+    assert_eq!(res.items.len(), 2);
+
+    let first = &res.items[0];
+    assert_eq!(
+        first.title,
+        "[SubsPlease] Frieren - 01 (1080p) [F02B9CEE].mkv"
+    );
+    assert_eq!(first.id, "torznab-0123456789abcdef0123456789abcdef01234567");
+    assert_eq!(first.seeders, 1203);
+    assert_eq!(first.leechers, 47);
+    assert_eq!(first.downloads, 48213);
+    assert_eq!(first.bytes, 1454513254);
+    assert_eq!(first.category, 5070);
+    assert_eq!(first.post_link, "https://nyaa.si/view/1700000");
+    assert!(first
+        .torrent_link
+        .starts_with("http://127.0.0.1:9117/dl/nyaasi/"));
+    assert!(first
+        .magnet_link
+        .starts_with("magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&"));
+    assert_eq!(
+        first.extra.get("freeleech").map(String::as_str),
+        Some("true")
+    );
+    assert!(matches!(first.item_type, ItemType::None));
+
+    let second = &res.items[1];
+    assert_eq!(second.category, 2040);
+    assert_eq!(second.bytes, 2147483648);
+    assert_eq!(second.seeders, 0);
+    assert_eq!(second.leechers, 3);
+    assert!(second.torrent_link.is_empty());
+    assert_eq!(
+        second.magnet_link,
+        "magnet:?xt=urn:btih:fedcba9876543210fedcba9876543210fedcba98&dn=Some.Movie"
+    );
+}
@@ -0,0 +1,91 @@
+use nyaa::{
+    client::Client,
+    config::Config,
+    source::{request_client, torznab::TorznabConfig, Sources},
+    watch::{self, Watch},
+};
+use reqwest::cookie::Jar;
+use std::sync::Arc;
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+#[tokio::test]
+async fn test_watch_check() {
+    let out = std::env::temp_dir().join(format!("nyaa-watch-{}.txt", std::process::id()));
+    let mut config: Config = toml::from_str(&format!(
+        "download_client = \"RunCommand\"\n[client.command]\ncmd = \"echo '{{title}}' >> {}\"",
+        out.to_string_lossy()
+    ))
+    .unwrap();
+    let xml = include_str!("fixtures/torznab.xml").to_owned();
+    let (addr, handle) = serve(vec![(200, xml.clone()), (200, xml)]);
+    config.sources.torznab = Some(TorznabConfig {
+        base_url: format!("{}/api", addr),
+        ..Default::default()
+    });
+
+    let watch = Watch {
+        name: "frieren".to_owned(),
+        source: Sources::Torznab,
+        query: "frieren".to_owned(),
+        ..Default::default()
+    };
+    let jar = Arc::new(Jar::default());
+    let rq = request_client(&jar, 5, None).unwrap();
+
+    // The first run only remembers existing items
+    let (res, seen) = watch::check(&watch, &config, None, &rq, &rq).await.unwrap();
+    assert_eq!(res.new_items.len(), 2);
+    assert!(res.download.is_none());
+    assert_eq!(seen.len(), 2);
+    assert!(!out.exists());
+
+    // Only unseen items are sent to the client
+    let seen = vec![seen[0].clone()];
+    let (res, seen) = watch::check(&watch, &config, Some(&seen), &rq, &rq)
+        .await
+        .unwrap();
+    handle.join().unwrap();
+    assert_eq!(res.new_items.len(), 1);
+    assert_eq!(res.download.unwrap().success_ids.len(), 1);
+    assert_eq!(seen.len(), 2);
+
+    let written = std::fs::read_to_string(&out).unwrap();
+    let _ = std::fs::remove_file(&out);
+    assert_eq!(written.trim(), "Some.Movie.2023.1080p.WEB-DL");
+}
+
+#[test]
+fn test_watch_key() {
+    let watch = Watch {
+        query: "frieren".to_owned(),
+        ..Default::default()
+    };
+    let trusted = Watch {
+        filter: Some("TrustedOnly".to_owned()),
+        ..watch.clone()
+    };
+    assert_eq!(watch.key(), "Nyaa/frieren");
+    assert_eq!(trusted.key(), "Nyaa/frieren filter=TrustedOnly");
+
+    // Watches sharing a key would share their seen items
+    let mut config = Config {
+        watches: Some(vec![watch.clone(), trusted]),
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+    config.watches = Some(vec![
+        watch.clone(),
+        Watch {
+            client: Some(Client::Qbit.into()),
+            ..watch
+        },
+    ]);
+    assert!(config
+        .validate()
+        .unwrap_err()
+        .contains("more than one watch named \"Nyaa/frieren\""));
+}
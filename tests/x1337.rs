@@ -0,0 +1,160 @@
+use nyaa::{
+    cli::{self, DownloadArgs, QueryArgs},
+    config::Config,
+    source::{
+        x1337::{parse_1337x, parse_1337x_links, X1337Config, X1337Source},
+        Sources,
+    },
+};
+use reqwest::Url;
+
+use crate::common::serve;
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn test_parse_1337x() {
+    let html = include_str!("fixtures/x1337.html");
+    let base_url = Url::parse("https://1337x.to/sort-search/frieren/time/desc/1/").unwrap();
+    let res = parse_1337x::<X1337Source>(html, &base_url, None, 1).unwrap();
+
+    assert_eq!(res.items.len(), 2);
+    assert_eq!(res.last_page, 3);
+
+    let first = &res.items[0];
+    assert_eq!(first.title, "Frieren S01E01 1080p WEB H264");
+    assert_eq!(first.id, "1337x-5512345");
+    assert_eq!(
+        first.post_link,
+        "https://1337x.to/torrent/5512345/Frieren-S01E01-1080p-WEB-H264/"
+    );
+    // Links are only available on the item's page
+    assert!(first.magnet_link.is_empty());
+    assert!(first.torrent_link.is_empty());
+    assert_eq!(first.category, 7);
+    assert_eq!(first.size, "1.4 GB");
+    assert_eq!(first.timestamp, 1697241600);
+    assert_eq!(first.seeders, 412);
+    assert_eq!(first.leechers, 21);
+    assert_eq!(
+        first.extra.get("uploader").map(String::as_str),
+        Some("SubsPlease")
+    );
+
+    let second = &res.items[1];
+    assert_eq!(second.category, 2);
+    assert_eq!(second.size, "24.7 GB");
+    assert_eq!(second.seeders, 0);
+}
+
+#[test]
+fn test_parse_1337x_links() {
+    let html = include_str!("fixtures/x1337_torrent.html");
+    let (magnet, torrent) = parse_1337x_links(html).unwrap();
+    assert!(magnet.starts_with("magnet:?xt=urn:btih:0123456789ABCDEF"));
+    assert!(magnet.contains("&dn=Frieren+S01E01"));
+    assert_eq!(
+        torrent,
+        "http://itorrents.org/torrent/0123456789ABCDEF0123456789ABCDEF01234567.torrent"
+    );
+
+    assert!(parse_1337x_links("<html></html>").is_err());
+}
+
+#[tokio::test]
+async fn test_1337x_search() {
+    let html = include_str!("fixtures/x1337.html").to_owned();
+    let (addr, server) = serve(vec![(200, html.clone()), (200, html)]);
+
+    let mut config = Config::default();
+    config.sources.x1337 = Some(X1337Config {
+        base_url: addr,
+        ..Default::default()
+    });
+    let mut args = QueryArgs {
+        query: Some("frieren".to_owned()),
+        source: Some("1337x".to_owned()),
+        filter: Some("No Dead Torrents".to_owned()),
+        sort: Some("Seeders".to_owned()),
+        ..Default::default()
+    };
+
+    let (src, _, res) = cli::search(&args, &config).await.unwrap();
+    assert!(src == Sources::X1337);
+    assert_eq!(res.items.len(), 1);
+
+    args.category = Some("Anime".to_owned());
+    args.page = Some(2);
+    cli::search(&args, &config).await.unwrap();
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /sort-search/frieren/seeders/desc/1/ "));
+    assert!(requests[1].starts_with("GET /sort-category-search/frieren/Anime/seeders/desc/2/ "));
+}
+
+#[tokio::test]
+async fn test_1337x_resolve_download() {
+    let html = include_str!("fixtures/x1337.html").to_owned();
+    let page = include_str!("fixtures/x1337_torrent.html").to_owned();
+    let (addr, server) = serve(vec![(200, html), (200, page)]);
+
+    let out = std::env::temp_dir().join(format!("nyaa-1337x-{}.txt", std::process::id()));
+    let mut config: Config = toml::from_str(&format!(
+        "download_client = \"RunCommand\"\n[client.command]\ncmd = \"echo '{{magnet}}' >> {}\"",
+        out.to_string_lossy()
+    ))
+    .unwrap();
+    config.sources.x1337 = Some(X1337Config {
+        base_url: addr,
+        ..Default::default()
+    });
+    let args = DownloadArgs {
+        query: QueryArgs {
+            query: Some("frieren".to_owned()),
+            source: Some("1337x".to_owned()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // The magnet link is fetched from the item's page before it's downloaded
+    let (_, src, items, res) = cli::download(&args, &config).await.unwrap();
+    assert!(src == Some(Sources::X1337));
+    assert!(res.errors.is_empty());
+    assert_eq!(res.success_ids, vec![items[0].id.to_owned()]);
+    assert_eq!(res.resolved.len(), 1);
+    assert_eq!(
+        res.resolved[0].extra.get("hash").map(String::as_str),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+
+    let requests = server.join().unwrap();
+    assert!(requests[1].starts_with("GET /torrent/5512345/Frieren-S01E01-1080p-WEB-H264/ "));
+
+    let written = std::fs::read_to_string(&out).unwrap();
+    let _ = std::fs::remove_file(&out);
+    assert!(written.starts_with("magnet:?xt=urn:btih:0123456789ABCDEF"));
+}
+
+#[tokio::test]
+async fn test_1337x_resolve_error() {
+    let (addr, server) = serve(vec![(404, "Not Found".to_owned())]);
+
+    let config = Config::default();
+    let item = nyaa::source::Item {
+        id: "1337x-1".to_owned(),
+        title: "Missing".to_owned(),
+        post_link: format!("{}/torrent/1/Missing/", addr),
+        ..Default::default()
+    };
+    let (items, errors) = Sources::X1337
+        .resolve(&reqwest::Client::new(), vec![item], &config.sources)
+        .await;
+    server.join().unwrap();
+    assert!(items.is_empty());
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .to_string()
+        .starts_with("Failed to get the download links for \"Missing\""));
+}